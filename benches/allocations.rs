@@ -0,0 +1,80 @@
+// benches/allocations.rs
+//! Allocation count, bytes and wall time of `parse` versus `parse_borrowed`
+//! on a large generated diagram.
+//!
+//!     cargo bench --bench allocations
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use mermaid_parser::parser::{parse, parse_borrowed};
+
+struct Counting;
+
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(new_size, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const CLASSES: usize = 2_000;
+const RUNS: u32 = 10;
+
+fn generate() -> String {
+    let mut src = String::from("classDiagram\n");
+    for i in 0..CLASSES {
+        src += &format!("class Class{i}\n");
+        src += &format!("Class{i} : +String name{i}\n");
+        src += &format!("Class{i} : -int count\n");
+        src += &format!("Class{i} : +update(String key, int value) : bool\n");
+        if i > 0 {
+            src += &format!("Class{} <|-- Class{i}\n", i - 1);
+            src += &format!("Class{i} *-- Class{}\n", i / 2);
+        }
+    }
+    src
+}
+
+fn measure<T>(label: &str, f: impl Fn() -> T) {
+    let mut elapsed = Duration::ZERO;
+    let (mut allocs, mut bytes) = (0, 0);
+    for _ in 0..RUNS {
+        let (a0, b0) = (ALLOCS.load(Ordering::Relaxed), BYTES.load(Ordering::Relaxed));
+        let start = Instant::now();
+        let out = black_box(f());
+        elapsed += start.elapsed();
+        allocs = ALLOCS.load(Ordering::Relaxed) - a0;
+        bytes = BYTES.load(Ordering::Relaxed) - b0;
+        drop(out);
+    }
+    println!(
+        "{label:<16} {allocs:>10} allocs {:>10} KiB {:>10.2?}/run",
+        bytes / 1024,
+        elapsed / RUNS
+    );
+}
+
+fn main() {
+    let src = generate();
+    println!("input: {CLASSES} classes, {} KiB", src.len() / 1024);
+    measure("parse", || parse(&src).unwrap());
+    measure("parse_borrowed", || parse_borrowed(&src).unwrap());
+}
@@ -0,0 +1,238 @@
+//! Zero-copy variant of the data model in [`types`](crate::types).
+//!
+//! Every name, type and label is a `&'src str` slice of the parsed source, so
+//! [`parse_borrowed`](crate::parser::parse_borrowed) allocates only the
+//! containers.  The owned model is derived from it with
+//! [`Diagram::into_owned`]; that is exactly what [`parse`](crate::parser::parse)
+//! does.
+//!
+//! Shape and invariants mirror [`types`](crate::types) field for field.
+
+use indexmap::IndexMap;
+
+use crate::types::{self, split_namespace, LineStyle, Model, RelationKind, Visibility};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parameter<'src> {
+    pub name: &'src str,
+    pub data_type: Option<&'src str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Member<'src> {
+    Attribute(Attribute<'src>),
+    Method(Method<'src>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attribute<'src> {
+    pub visibility: Visibility,
+    pub name: &'src str,
+    pub data_type: Option<&'src str>,
+    pub is_static: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Method<'src> {
+    pub visibility: Visibility,
+    pub name: &'src str,
+    pub parameters: Vec<Parameter<'src>>,
+    pub return_type: Option<&'src str>,
+    pub is_static: bool,
+    pub is_abstract: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Class<'src> {
+    pub name: &'src str,
+    pub generic: Option<&'src str>,
+    pub annotations: Vec<&'src str>,
+    pub members: Vec<Member<'src>>,
+    pub namespace: &'src str,
+    pub implicit: bool,
+}
+
+impl<'src> Class<'src> {
+    pub fn new(fq_name: &'src str) -> Self {
+        Class {
+            name: fq_name,
+            generic: None,
+            annotations: Vec::new(),
+            members: Vec::new(),
+            namespace: split_namespace(fq_name).0,
+            implicit: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Relation<'src> {
+    pub from: &'src str,
+    pub to: &'src str,
+    pub kind: RelationKind,
+    pub line: LineStyle,
+    pub label_from: Option<&'src str>,
+    pub label_to: Option<&'src str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Note<'src> {
+    pub text: &'src str,
+    pub for_class: Option<&'src str>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Namespace<'src> {
+    pub name: &'src str,
+    pub classes: IndexMap<&'src str, Class<'src>>,
+    pub children: IndexMap<&'src str, Namespace<'src>>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Diagram<'src> {
+    pub namespaces: IndexMap<&'src str, Namespace<'src>>,
+    pub relations: Vec<Relation<'src>>,
+    pub notes: Vec<Note<'src>>,
+}
+
+impl<'src> Diagram<'src> {
+    /// See [`types::Diagram::class_entry`]
+    pub fn class_entry(&mut self, fq_name: &'src str) -> &mut Class<'src> {
+        types::class_entry(self, fq_name)
+    }
+
+    /// See [`types::Diagram::declare_class`]
+    pub fn declare_class(&mut self, fq_name: &'src str) -> &mut Class<'src> {
+        types::declare_class(self, fq_name)
+    }
+
+    /// See [`types::Diagram::add_relation`]
+    pub fn add_relation(&mut self, relation: Relation<'src>) {
+        types::register_ends(self, [relation.from, relation.to]);
+        self.relations.push(relation);
+    }
+
+    /// Copy every slice into an owned [`types::Diagram`].
+    pub fn into_owned(self) -> types::Diagram {
+        self.into()
+    }
+}
+
+impl<'src> Model<'src> for Diagram<'src> {
+    type Key = &'src str;
+    type Namespace = Namespace<'src>;
+    type Class = Class<'src>;
+
+    fn namespaces_mut(&mut self) -> &mut IndexMap<&'src str, Namespace<'src>> {
+        &mut self.namespaces
+    }
+
+    fn new_namespace(name: &'src str) -> Namespace<'src> {
+        Namespace { name, ..Default::default() }
+    }
+
+    fn classes_mut(namespace: &mut Self::Namespace) -> &mut IndexMap<Self::Key, Self::Class> {
+        &mut namespace.classes
+    }
+
+    fn new_class(fq_name: &'src str) -> Class<'src> {
+        Class::new(fq_name)
+    }
+
+    fn implicit_mut(class: &mut Self::Class) -> &mut bool {
+        &mut class.implicit
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Owned conversions
+// ────────────────────────────────────────────────────────────────────────────────
+
+fn owned(s: Option<&str>) -> Option<String> {
+    s.map(str::to_owned)
+}
+
+impl From<Parameter<'_>> for types::Parameter {
+    fn from(p: Parameter<'_>) -> Self {
+        types::Parameter {
+            name: p.name.to_owned(),
+            data_type: owned(p.data_type),
+        }
+    }
+}
+
+impl From<Member<'_>> for types::Member {
+    fn from(m: Member<'_>) -> Self {
+        match m {
+            Member::Attribute(a) => types::Member::Attribute(types::Attribute {
+                visibility: a.visibility,
+                name: a.name.to_owned(),
+                data_type: owned(a.data_type),
+                is_static: a.is_static,
+            }),
+            Member::Method(m) => types::Member::Method(types::Method {
+                visibility: m.visibility,
+                name: m.name.to_owned(),
+                parameters: m.parameters.into_iter().map(Into::into).collect(),
+                return_type: owned(m.return_type),
+                is_static: m.is_static,
+                is_abstract: m.is_abstract,
+            }),
+        }
+    }
+}
+
+impl From<Class<'_>> for types::Class {
+    fn from(c: Class<'_>) -> Self {
+        types::Class {
+            name: c.name.to_owned(),
+            generic: owned(c.generic),
+            annotations: c.annotations.into_iter().map(str::to_owned).collect(),
+            members: c.members.into_iter().map(Into::into).collect(),
+            namespace: c.namespace.to_owned(),
+            implicit: c.implicit,
+        }
+    }
+}
+
+impl From<Relation<'_>> for types::Relation {
+    fn from(r: Relation<'_>) -> Self {
+        types::Relation {
+            from: r.from.to_owned(),
+            to: r.to.to_owned(),
+            kind: r.kind,
+            line: r.line,
+            label_from: owned(r.label_from),
+            label_to: owned(r.label_to),
+        }
+    }
+}
+
+impl From<Note<'_>> for types::Note {
+    fn from(n: Note<'_>) -> Self {
+        types::Note {
+            text: n.text.to_owned(),
+            for_class: owned(n.for_class),
+        }
+    }
+}
+
+impl From<Namespace<'_>> for types::Namespace {
+    fn from(ns: Namespace<'_>) -> Self {
+        types::Namespace {
+            name: ns.name.to_owned(),
+            classes: ns.classes.into_iter().map(|(k, v)| (k.to_owned(), v.into())).collect(),
+            children: ns.children.into_iter().map(|(k, v)| (k.to_owned(), v.into())).collect(),
+        }
+    }
+}
+
+impl From<Diagram<'_>> for types::Diagram {
+    fn from(d: Diagram<'_>) -> Self {
+        types::Diagram {
+            namespaces: d.namespaces.into_iter().map(|(k, v)| (k.to_owned(), v.into())).collect(),
+            relations: d.relations.into_iter().map(Into::into).collect(),
+            notes: d.notes.into_iter().map(Into::into).collect(),
+        }
+    }
+}
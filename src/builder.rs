@@ -0,0 +1,253 @@
+//! Fluent construction of a [`Diagram`] from code.
+//!
+//! ```
+//! use mermaid_parser::types::{Diagram, RelationKind, Visibility};
+//!
+//! let diagram = Diagram::builder()
+//!     .class("shop::Order", |c| {
+//!         c.attr(Visibility::Private, "id", "u64")
+//!             .method(Visibility::Public, "cancel", |m| m.param("reason", "String").returns("bool"))
+//!     })
+//!     .class("shop::Item", |c| c.annotation("entity"))
+//!     .relation("shop::Order", RelationKind::Composition, "shop::Item")
+//!     .build()?;
+//!
+//! let order = &diagram.namespaces["shop"].classes["Order"];
+//! assert_eq!(order.name, "shop::Order");
+//! assert_eq!(order.namespace, "shop");
+//! # Ok::<(), mermaid_parser::builder::BuildError>(())
+//! ```
+//!
+//! Classes are registered through [`Diagram::declare_class`] and relations
+//! through [`Diagram::add_relation`], the same paths the parser uses, so
+//! `Class::name`, `Class::namespace` and the namespace map key always agree
+//! and relation ends always exist.  Declaring a class twice merges into the
+//! first declaration.
+//!
+//! Every class name, including relation ends and note targets, must be
+//! `::`-separated identifiers the parser accepts; [`DiagramBuilder::build`]
+//! reports the first one that is not.
+
+use thiserror::Error;
+
+use crate::parser::{is_token, Rule};
+use crate::types::{
+    Attribute, Class, Diagram, LineStyle, Member, Method, Note, Parameter, Relation,
+    RelationKind, Visibility,
+};
+
+impl Diagram {
+    pub fn builder() -> DiagramBuilder {
+        DiagramBuilder::default()
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BuildError {
+    #[error("`{0}` is not a valid class name")]
+    InvalidClassName(String),
+}
+
+#[derive(Debug, Default)]
+pub struct DiagramBuilder {
+    diagram: Diagram,
+    error: Option<BuildError>,
+}
+
+impl DiagramBuilder {
+    /// Declare (or extend) the class with the given fully-qualified name.
+    pub fn class(mut self, fq_name: &str, f: impl FnOnce(ClassBuilder) -> ClassBuilder) -> Self {
+        if !self.check(fq_name) {
+            return self;
+        }
+        let built = f(ClassBuilder {
+            class: Class::new(fq_name),
+        })
+        .class;
+        let class = self.diagram.declare_class(fq_name);
+        if built.generic.is_some() {
+            class.generic = built.generic;
+        }
+        for annotation in built.annotations {
+            if !class.annotations.contains(&annotation) {
+                class.annotations.push(annotation);
+            }
+        }
+        class.members.extend(built.members);
+        self
+    }
+
+    /// Solid relation between two fully-qualified class names
+    pub fn relation(self, from: &str, kind: RelationKind, to: &str) -> Self {
+        self.add_relation(Relation {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind,
+            line: LineStyle::Solid,
+            label_from: None,
+            label_to: None,
+        })
+    }
+
+    /// Relation with full control over line style and labels
+    ///
+    /// Ends that were not declared with [`class`](Self::class) become implicit classes.
+    pub fn add_relation(mut self, relation: Relation) -> Self {
+        if self.check(&relation.from) && self.check(&relation.to) {
+            self.diagram.add_relation(relation);
+        }
+        self
+    }
+
+    /// Free-floating note
+    pub fn note(mut self, text: &str) -> Self {
+        self.diagram.notes.push(Note {
+            text: text.to_owned(),
+            for_class: None,
+        });
+        self
+    }
+
+    /// Note attached to a class
+    pub fn note_for(mut self, fq_name: &str, text: &str) -> Self {
+        if !self.check(fq_name) {
+            return self;
+        }
+        self.diagram.notes.push(Note {
+            text: text.to_owned(),
+            for_class: Some(fq_name.to_owned()),
+        });
+        self
+    }
+
+    /// The diagram, or the first invalid class name it was given
+    pub fn build(self) -> Result<Diagram, BuildError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.diagram),
+        }
+    }
+
+    /// Whether `fq_name` is `::`-separated class identifiers; the first one
+    /// that is not becomes the build error
+    fn check(&mut self, fq_name: &str) -> bool {
+        let valid = fq_name.split("::").all(|segment| is_token(Rule::class_identifier, segment));
+        if !valid && self.error.is_none() {
+            self.error = Some(BuildError::InvalidClassName(fq_name.to_owned()));
+        }
+        valid
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Class
+// ────────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct ClassBuilder {
+    class: Class,
+}
+
+impl ClassBuilder {
+    /// `Foo~T~`
+    pub fn generic(mut self, generic: &str) -> Self {
+        self.class.generic = Some(generic.to_owned());
+        self
+    }
+
+    /// `<<interface>>` – pass the name without the angle brackets
+    pub fn annotation(mut self, annotation: &str) -> Self {
+        self.class.annotations.push(annotation.to_owned());
+        self
+    }
+
+    pub fn attr(self, visibility: Visibility, name: &str, data_type: &str) -> Self {
+        self.attribute(visibility, name, Some(data_type), false)
+    }
+
+    /// `$` attribute
+    pub fn static_attr(self, visibility: Visibility, name: &str, data_type: &str) -> Self {
+        self.attribute(visibility, name, Some(data_type), true)
+    }
+
+    /// Attribute whose type is left out
+    pub fn untyped_attr(self, visibility: Visibility, name: &str) -> Self {
+        self.attribute(visibility, name, None, false)
+    }
+
+    fn attribute(mut self, visibility: Visibility, name: &str, data_type: Option<&str>, is_static: bool) -> Self {
+        self.class.members.push(Member::Attribute(Attribute {
+            visibility,
+            name: name.to_owned(),
+            data_type: data_type.map(str::to_owned),
+            is_static,
+        }));
+        self
+    }
+
+    pub fn method(mut self, visibility: Visibility, name: &str, f: impl FnOnce(MethodBuilder) -> MethodBuilder) -> Self {
+        let method = f(MethodBuilder {
+            method: Method {
+                visibility,
+                name: name.to_owned(),
+                parameters: Vec::new(),
+                return_type: None,
+                is_static: false,
+                is_abstract: false,
+            },
+        })
+        .method;
+        self.class.members.push(Member::Method(method));
+        self
+    }
+
+    /// Any prebuilt member
+    pub fn member(mut self, member: Member) -> Self {
+        self.class.members.push(member);
+        self
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Method
+// ────────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct MethodBuilder {
+    method: Method,
+}
+
+impl MethodBuilder {
+    pub fn param(mut self, name: &str, data_type: &str) -> Self {
+        self.method.parameters.push(Parameter {
+            name: name.to_owned(),
+            data_type: Some(data_type.to_owned()),
+        });
+        self
+    }
+
+    pub fn untyped_param(mut self, name: &str) -> Self {
+        self.method.parameters.push(Parameter {
+            name: name.to_owned(),
+            data_type: None,
+        });
+        self
+    }
+
+    pub fn returns(mut self, data_type: &str) -> Self {
+        self.method.return_type = Some(data_type.to_owned());
+        self
+    }
+
+    /// `$`
+    pub fn is_static(mut self) -> Self {
+        self.method.is_static = true;
+        self
+    }
+
+    /// `*`
+    pub fn is_abstract(mut self) -> Self {
+        self.method.is_abstract = true;
+        self
+    }
+}
@@ -0,0 +1,494 @@
+//! Source code generation from a [`Diagram`].
+//!
+//! One submodule per target language, each adding `Diagram::to_<language>`
+//! methods.  They share the pieces here: a [`TypeMap`] translating Mermaid
+//! type names, a parser for type expressions such as `List~Item~` or
+//! `int[]`, and the reading of relations as fields.
+//!
+//! Rust and TypeScript nest namespaces in one source file.  Java and Kotlin
+//! want one file per class, Python one per namespace, so theirs return a
+//! map from relative path, e.g. `geo/Circle.java`, to file contents.
+//!
+//! Generated code is a skeleton: bodies are stubs and a member without a
+//! type gets a placeholder, but names, visibility, inheritance and fields
+//! follow the diagram.
+
+pub mod java;
+pub mod kotlin;
+pub mod python;
+pub mod rust;
+pub mod typescript;
+
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+
+use crate::types::{split_namespace, Class, Diagram, LineStyle, Member, Method, RelationKind};
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Type names
+// ────────────────────────────────────────────────────────────────────────────────
+
+/// Mermaid type name ➜ target type name, e.g. `int` ➜ `i32`
+///
+/// Only bare names are looked up; generic arguments and array suffixes are
+/// mapped one by one, so `List~int~[]` needs entries for `List` and `int`.
+/// Names without an entry are kept as written.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeMap {
+    names: IndexMap<String, String>,
+}
+
+impl TypeMap {
+    pub fn new() -> Self {
+        TypeMap::default()
+    }
+
+    /// Add or replace an entry, builder style.
+    pub fn with(mut self, mermaid: impl Into<String>, target: impl Into<String>) -> Self {
+        self.insert(mermaid, target);
+        self
+    }
+
+    /// Add or replace an entry; returns the previous target.
+    pub fn insert(&mut self, mermaid: impl Into<String>, target: impl Into<String>) -> Option<String> {
+        self.names.insert(mermaid.into(), target.into())
+    }
+
+    pub fn get(&self, mermaid: &str) -> Option<&str> {
+        self.names.get(mermaid).map(String::as_str)
+    }
+
+    fn from_pairs(pairs: &[(&str, &str)]) -> Self {
+        TypeMap {
+            names: pairs.iter().map(|&(m, t)| (m.to_owned(), t.to_owned())).collect(),
+        }
+    }
+}
+
+/// How a language writes generic arguments and arrays
+pub(crate) struct Generics {
+    pub open: &'static str,
+    pub close: &'static str,
+    pub array: fn(String) -> String,   // element type ➜ array type
+}
+
+/// `List~Map~K,V~~[]` parsed: a name, generic arguments, array suffixes
+#[derive(Debug, PartialEq)]
+pub(crate) struct TypeExpr<'a> {
+    pub name: &'a str,
+    pub args: Vec<TypeExpr<'a>>,
+    pub arrays: usize,
+}
+
+impl<'a> TypeExpr<'a> {
+    /// `None` if `ty` is not a plain type expression
+    pub fn parse(ty: &'a str) -> Option<Self> {
+        let (expr, rest) = Self::parse_prefix(ty.trim())?;
+        rest.trim().is_empty().then_some(expr)
+    }
+
+    fn parse_prefix(s: &'a str) -> Option<(Self, &'a str)> {
+        let s = s.trim_start();
+        let end = s
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | ':' | '.' | '$')))
+            .unwrap_or(s.len());
+        if end == 0 {
+            return None;
+        }
+        let (name, mut rest) = s.split_at(end);
+        let mut args = Vec::new();
+
+        // Mermaid `~T~` or `<T>`; a `~` only opens arguments when a name follows
+        let close = match rest.chars().next() {
+            Some('<') => Some('>'),
+            Some('~') if rest[1..].starts_with(|c: char| c.is_alphabetic() || c == '_') => Some('~'),
+            _ => None,
+        };
+        if let Some(close) = close {
+            rest = &rest[1..];
+            loop {
+                let (arg, after) = Self::parse_prefix(rest)?;
+                args.push(arg);
+                rest = after.trim_start();
+                match rest.chars().next()? {
+                    ',' => rest = &rest[1..],
+                    c if c == close => {
+                        rest = &rest[1..];
+                        break;
+                    }
+                    _ => return None,
+                }
+            }
+        }
+
+        let mut arrays = 0;
+        while let Some(after) = rest.trim_start().strip_prefix('[') {
+            rest = after.trim_start().strip_prefix(']')?;
+            arrays += 1;
+        }
+        Some((TypeExpr { name, args, arrays }, rest))
+    }
+
+    pub fn render(&self, types: &TypeMap, generics: &Generics) -> String {
+        let mut out = types.get(self.name).unwrap_or(self.name).to_owned();
+        if !self.args.is_empty() {
+            let args: Vec<String> = self.args.iter().map(|a| a.render(types, generics)).collect();
+            out = format!("{out}{}{}{}", generics.open, args.join(", "), generics.close);
+        }
+        for _ in 0..self.arrays {
+            out = (generics.array)(out);
+        }
+        out
+    }
+}
+
+/// Translate a Mermaid type expression; unparsable ones are kept as written
+pub(crate) fn map_type(ty: &str, types: &TypeMap, generics: &Generics) -> String {
+    match TypeExpr::parse(ty) {
+        Some(expr) => expr.render(types, generics),
+        None => ty.trim().to_owned(),
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Reading the diagram
+// ────────────────────────────────────────────────────────────────────────────────
+
+/// What a class becomes in code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Interface,   // `<<interface>>`, or realized by another class
+    Enum,        // `<<enumeration>>`/`<<enum>>`: attributes are the variants
+    Abstract,    // `<<abstract>>`, or has an abstract method
+    Class,
+}
+
+pub(crate) fn kind(diagram: &Diagram, class: &Class) -> Kind {
+    let annotated = |names: &[&str]| {
+        class.annotations.iter().any(|a| names.iter().any(|n| a.eq_ignore_ascii_case(n)))
+    };
+    if annotated(&["interface"]) || !diagram.implementors(&class.name).is_empty() {
+        Kind::Interface
+    } else if annotated(&["enumeration", "enum"]) {
+        Kind::Enum
+    } else if annotated(&["abstract"])
+        || class.members.iter().any(|m| matches!(m, Member::Method(m) if m.is_abstract))
+    {
+        Kind::Abstract
+    } else {
+        Kind::Class
+    }
+}
+
+/// Parents split by what they are: (classes extended, interfaces implemented)
+pub(crate) fn parents<'d>(diagram: &'d Diagram, class: &Class) -> (Vec<&'d str>, Vec<&'d str>) {
+    let mut extends = Vec::new();
+    let mut implements = Vec::new();
+    for relation in diagram.relations.iter().filter(|r| r.from == class.name) {
+        let realized = match (relation.kind, relation.line) {
+            (RelationKind::Extension, LineStyle::Solid) => false,
+            (RelationKind::Extension, LineStyle::Dotted) | (RelationKind::Lollipop, _) => true,
+            _ => continue,
+        };
+        let interface = realized
+            || diagram.class(&relation.to).is_some_and(|p| kind(diagram, p) == Kind::Interface);
+        let list = if interface { &mut implements } else { &mut extends };
+        if !list.contains(&relation.to.as_str()) {
+            list.push(relation.to.as_str());
+        }
+    }
+    (extends, implements)
+}
+
+/// Interfaces named and, transitively, the interfaces they extend
+pub(crate) fn interfaces<'d>(diagram: &'d Diagram, names: &[&'d str]) -> Vec<&'d Class> {
+    let mut found: Vec<&'d Class> = Vec::new();
+    let mut pending: Vec<&'d str> = names.iter().rev().copied().collect();
+    while let Some(name) = pending.pop() {
+        if let Some(iface) = diagram.class(name)
+            && !found.iter().any(|f| f.name == iface.name)
+        {
+            found.push(iface);
+            let (e, i) = parents(diagram, iface);
+            pending.extend(e.into_iter().chain(i).rev());
+        }
+    }
+    found
+}
+
+/// Classes extended, directly or not, nearest first
+pub(crate) fn ancestors<'d>(diagram: &'d Diagram, class: &Class) -> Vec<&'d Class> {
+    let mut found: Vec<&'d Class> = Vec::new();
+    let mut pending: Vec<&'d str> = parents(diagram, class).0.into_iter().rev().collect();
+    while let Some(name) = pending.pop() {
+        if let Some(base) = diagram.class(name)
+            && base.name != class.name
+            && !found.iter().any(|f| f.name == base.name)
+        {
+            found.push(base);
+            pending.extend(parents(diagram, base).0.into_iter().rev());
+        }
+    }
+    found
+}
+
+/// Abstract methods a concrete class has to implement: declared by a class
+/// it extends and implemented neither by itself nor by a nearer ancestor.
+/// A concrete ancestor implements all of them, stubs included.
+pub(crate) fn unimplemented<'d>(diagram: &'d Diagram, class: &Class) -> Vec<&'d Method> {
+    let mut implemented: Vec<&str> = class.members.iter().filter(|m| !m.is_static()).map(Member::name).collect();
+    let mut found: Vec<&'d Method> = Vec::new();
+    for base in ancestors(diagram, class) {
+        if kind(diagram, base) == Kind::Class {
+            break;
+        }
+        for member in &base.members {
+            let Member::Method(m) = member else { continue };
+            if m.is_static || implemented.contains(&m.name.as_str()) {
+                continue;
+            }
+            implemented.push(&m.name);
+            if m.is_abstract {
+                found.push(m);
+            }
+        }
+    }
+    found
+}
+
+/// `classes` reordered so that parents come before their children, for
+/// languages where a base must be declared before use
+pub(crate) fn bases_first<'d>(diagram: &'d Diagram, classes: &[&'d Class]) -> Vec<&'d Class> {
+    fn visit<'d>(diagram: &'d Diagram, class: &'d Class, classes: &[&'d Class], ordered: &mut Vec<&'d Class>) {
+        if ordered.iter().any(|c| c.name == class.name) {
+            return;
+        }
+        ordered.push(class);   // placeholder, so cycles end here
+        let at = ordered.len() - 1;
+        let (extends, implements) = parents(diagram, class);
+        for parent in extends.into_iter().chain(implements) {
+            if let Some(parent) = classes.iter().find(|c| c.name == parent) {
+                visit(diagram, parent, classes, ordered);
+            }
+        }
+        let class = ordered.remove(at);
+        ordered.push(class);
+    }
+    let mut ordered = Vec::with_capacity(classes.len());
+    for class in classes {
+        visit(diagram, class, classes, &mut ordered);
+    }
+    ordered
+}
+
+/// How many of a part a whole holds, read from the relation label
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Multiplicity {
+    One,
+    Optional,   // `0..1`
+    Many,       // `*`, `many`, `0..*`, `1..*`, `n`, …
+}
+
+impl Multiplicity {
+    fn from_label(label: Option<&str>) -> Self {
+        match label.map(str::trim) {
+            None | Some("1") | Some("1..1") | Some("") => Multiplicity::One,
+            Some("0..1") => Multiplicity::Optional,
+            Some(_) => Multiplicity::Many,
+        }
+    }
+}
+
+/// A field implied by a composition or aggregation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Part<'d> {
+    pub class: &'d str,           // fully-qualified
+    pub shared: bool,             // aggregation rather than composition
+    pub multiplicity: Multiplicity,
+}
+
+impl Part<'_> {
+    /// Field name: the part's class name, snake_case, plural if many
+    pub fn field_name(&self) -> String {
+        let name = snake_case(split_namespace(self.class).1);
+        match self.multiplicity {
+            Multiplicity::Many => name + "s",
+            _ => name,
+        }
+    }
+}
+
+/// Parts of `class` not already covered by an attribute of that type
+pub(crate) fn parts<'d>(diagram: &'d Diagram, class: &Class) -> Vec<Part<'d>> {
+    let typed = |short: &str| {
+        class.members.iter().any(|m| match m {
+            Member::Attribute(a) => a.data_type.as_deref().is_some_and(|t| mentions(t, short)),
+            Member::Method(_) => false,
+        })
+    };
+    diagram
+        .relations
+        .iter()
+        .filter(|r| r.to == class.name && matches!(r.kind, RelationKind::Composition | RelationKind::Aggregation))
+        .filter(|r| !typed(split_namespace(&r.from).1))
+        .map(|r| Part {
+            class: &r.from,
+            shared: r.kind == RelationKind::Aggregation,
+            multiplicity: Multiplicity::from_label(r.label_from.as_deref()),
+        })
+        .collect()
+}
+
+/// Whether the type expression names `short` anywhere
+fn mentions(ty: &str, short: &str) -> bool {
+    ty.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .any(|word| word == short)
+}
+
+/// Classes grouped by namespace path, in diagram order
+pub(crate) fn modules(diagram: &Diagram) -> IndexMap<Vec<&str>, Vec<&Class>> {
+    let mut modules: IndexMap<Vec<&str>, Vec<&Class>> = IndexMap::new();
+    for class in diagram.classes() {
+        modules.entry(namespace_path(&class.name)).or_default().push(class);
+    }
+    modules
+}
+
+/// `a::b::C` ➜ `["a", "b"]`
+pub(crate) fn namespace_path(fq: &str) -> Vec<&str> {
+    split_namespace(fq).0.split("::").filter(|s| !s.is_empty()).collect()
+}
+
+/// Class named `name` in a type, seen from the module at `path`: a name
+/// local to the module first, then as written
+pub(crate) fn lookup<'d>(diagram: &'d Diagram, name: &str, path: &[&str]) -> Option<&'d Class> {
+    let name = name.replace('.', "::");
+    let scope = path.join("::");
+    let local = if scope.is_empty() { name.clone() } else { format!("{scope}::{name}") };
+    diagram.class(&local).or_else(|| diagram.class(&name))
+}
+
+/// `geo/Circle.java` for `geo::Circle`, for one-file-per-class languages
+pub(crate) fn source_path(class: &Class, extension: &str) -> PathBuf {
+    let mut path: PathBuf = namespace_path(&class.name).into_iter().collect();
+    path.push(format!("{}.{extension}", short(class)));
+    path
+}
+
+/// Namespaces as nested modules
+#[derive(Default)]
+pub(crate) struct Module<'d> {
+    pub classes: Vec<&'d Class>,
+    pub children: IndexMap<&'d str, Module<'d>>,
+}
+
+impl<'d> Module<'d> {
+    pub fn tree(diagram: &'d Diagram) -> Self {
+        let mut root = Module::default();
+        for (path, classes) in modules(diagram) {
+            let mut module = &mut root;
+            for segment in path {
+                module = module.children.entry(segment).or_default();
+            }
+            module.classes.extend(classes);
+        }
+        root
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Names
+// ────────────────────────────────────────────────────────────────────────────────
+
+/// Class name without its namespace
+pub(crate) fn short(class: &Class) -> &str {
+    split_namespace(&class.name).1
+}
+
+/// `T`, `K, V` from the class generic
+pub(crate) fn generic_names(class: &Class) -> Vec<String> {
+    class
+        .generic
+        .iter()
+        .flat_map(|g| g.split(','))
+        .map(|g| g.trim().to_owned())
+        .filter(|g| !g.is_empty())
+        .collect()
+}
+
+/// `<K, V>`, or nothing for a class without generic parameters
+pub(crate) fn type_params(class: &Class) -> String {
+    angled(&generic_names(class))
+}
+
+/// Generic parameters of `class`, then those of the classes and interfaces
+/// it extends or implements, transitively: `UserRepo` realizing `Repo<T>`
+/// is generic over `T` too, so that the members it inherits are well-formed
+pub(crate) fn carried_generics(diagram: &Diagram, class: &Class) -> Vec<String> {
+    fn walk<'d>(diagram: &'d Diagram, class: &'d Class, seen: &mut Vec<&'d str>, names: &mut Vec<String>) {
+        if seen.contains(&class.name.as_str()) {
+            return;
+        }
+        seen.push(&class.name);
+        for g in generic_names(class) {
+            if !names.contains(&g) {
+                names.push(g);
+            }
+        }
+        let (extends, implements) = parents(diagram, class);
+        for parent in extends.into_iter().chain(implements).filter_map(|p| diagram.class(p)) {
+            walk(diagram, parent, seen, names);
+        }
+    }
+    let mut names = Vec::new();
+    if let Some(class) = diagram.class(&class.name) {
+        walk(diagram, class, &mut Vec::new(), &mut names);
+    }
+    names
+}
+
+/// [`carried_generics`] as `<T, U>`, or nothing
+pub(crate) fn carried_params(diagram: &Diagram, class: &Class) -> String {
+    angled(&carried_generics(diagram, class))
+}
+
+fn angled(names: &[String]) -> String {
+    if names.is_empty() { String::new() } else { format!("<{}>", names.join(", ")) }
+}
+
+/// `area_of`/`AreaOf`/`area-of` ➜ `areaOf`
+pub(crate) fn camel_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for (i, word) in snake_case(name).split('_').filter(|w| !w.is_empty()).enumerate() {
+        let mut chars = word.chars();
+        if i > 0 {
+            out.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+        }
+        out.extend(chars);
+    }
+    out
+}
+
+/// `areaOf`/`AreaOf`/`area-of` ➜ `area_of`
+pub(crate) fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    let chars: Vec<char> = name.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '-' {
+            out.push('_');
+        } else if c.is_uppercase() {
+            let prev = i.checked_sub(1).map(|p| chars[p]);
+            let next = chars.get(i + 1);
+            let boundary = prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit())
+                || (prev.is_some_and(char::is_uppercase) && next.is_some_and(|n| n.is_lowercase()));
+            if boundary && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -0,0 +1,422 @@
+//! Java skeletons, one file per class.
+//!
+//! ```
+//! use std::path::Path;
+//! use mermaid_parser::types::{Diagram, RelationKind, Visibility};
+//!
+//! let diagram = Diagram::builder()
+//!     .class("geo::Shape", |c| c.annotation("interface").method(Visibility::Public, "area", |m| m.returns("double")))
+//!     .class("geo::Circle", |c| c.attr(Visibility::Private, "radius", "double"))
+//!     .relation("geo::Circle", RelationKind::Extension, "geo::Shape")
+//!     .build()
+//!     .unwrap();
+//! let files = diagram.to_java();
+//! let circle = &files[Path::new("geo/Circle.java")];
+//! assert!(circle.contains("package geo;\n\npublic class Circle implements Shape {\n    private double radius;\n"));
+//! assert!(circle.contains("    @Override\n    public double area() {\n"));
+//! ```
+//!
+//! | diagram                          | Java                                          |
+//! |----------------------------------|-----------------------------------------------|
+//! | class / `<<abstract>>`           | `public class` / `public abstract class`      |
+//! | `<<interface>>`, realized class  | `public interface`, attributes as getters     |
+//! | `<<enumeration>>`                | `public enum`, one constant per attribute     |
+//! | `+` / `#` / `-` / `~` or none    | `public` / `protected` / `private` / none     |
+//! | `$` / `*`                        | `static` / `abstract`                         |
+//! | namespace `a::b`                 | `package a.b;` in directory `a/b`             |
+//! | extends a class                  | `extends` (the first one only)                |
+//! | extends or realizes an interface | `implements`, members copied in as overrides  |
+//! | composition / aggregation        | private field, `List<T>` if many              |
+//!
+//! Classes of other packages are imported, as are mapped types with a
+//! package such as `java.util.List`.  Primitive generic arguments are boxed:
+//! `List~int~` is `List<Integer>`.  A member without a type is an `Object`,
+//! method bodies throw.  Classes outside any namespace land in the default
+//! package, which named packages cannot import.  A concrete class overrides
+//! the abstract methods it inherits, and takes on the type parameters of
+//! generic parents: `class UserRepo<T> implements Repo<T>`.
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+
+use super::{
+    ancestors, camel_case, carried_params, interfaces, kind, lookup, namespace_path, parents, parts,
+    short, source_path, unimplemented, Kind, Multiplicity, TypeExpr, TypeMap,
+};
+use crate::types::{split_namespace, Attribute, Class, Diagram, Member, Method, Visibility};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JavaOptions {
+    pub types: TypeMap,
+}
+
+impl Default for JavaOptions {
+    fn default() -> Self {
+        JavaOptions { types: default_types() }
+    }
+}
+
+/// Common Mermaid/UML type names and their Java counterparts
+pub fn default_types() -> TypeMap {
+    TypeMap::from_pairs(&[
+        ("bool", "boolean"),
+        ("str", "String"),
+        ("string", "String"),
+        ("decimal", "java.math.BigDecimal"),
+        ("List", "java.util.List"),
+        ("ArrayList", "java.util.ArrayList"),
+        ("Map", "java.util.Map"),
+        ("Dict", "java.util.Map"),
+        ("HashMap", "java.util.HashMap"),
+        ("Set", "java.util.Set"),
+        ("HashSet", "java.util.HashSet"),
+    ])
+}
+
+impl Diagram {
+    /// Java sources with the default options, keyed by relative path.
+    pub fn to_java(&self) -> IndexMap<PathBuf, String> {
+        self.to_java_with(&JavaOptions::default())
+    }
+
+    pub fn to_java_with(&self, options: &JavaOptions) -> IndexMap<PathBuf, String> {
+        self.classes()
+            .map(|class| {
+                let emitter = Emitter {
+                    diagram: self,
+                    options,
+                    package: namespace_path(&class.name),
+                    imports: RefCell::default(),
+                };
+                (source_path(class, "java"), emitter.file(class))
+            })
+            .collect()
+    }
+}
+
+/// Writes the file of one class, collecting what it has to import
+struct Emitter<'d> {
+    diagram: &'d Diagram,
+    options: &'d JavaOptions,
+    package: Vec<&'d str>,
+    imports: RefCell<BTreeSet<String>>,
+}
+
+const STUB: &str = "throw new UnsupportedOperationException(\"not implemented\");";
+
+impl<'d> Emitter<'d> {
+    fn file(&self, class: &Class) -> String {
+        let mut body = String::new();
+        match kind(self.diagram, class) {
+            Kind::Interface => self.interface(&mut body, class),
+            Kind::Enum => self.enumeration(&mut body, class),
+            Kind::Abstract | Kind::Class => self.class(&mut body, class),
+        }
+
+        let mut out = String::from("// Generated from a Mermaid class diagram.\n");
+        if !self.package.is_empty() {
+            let _ = writeln!(out, "package {};", self.package.join("."));
+        }
+        out.push('\n');
+        let imports = self.imports.borrow();
+        for import in imports.iter() {
+            let _ = writeln!(out, "import {import};");
+        }
+        if !imports.is_empty() {
+            out.push('\n');
+        }
+        out + &body
+    }
+
+    // ── items ───────────────────────────────────────────────────────────────
+
+    fn interface(&self, out: &mut String, class: &Class) {
+        let (extends, implements) = parents(self.diagram, class);
+        let parents: Vec<String> = extends.iter().chain(&implements).map(|p| self.parent(p)).collect();
+        let extends = if parents.is_empty() { String::new() } else { format!(" extends {}", parents.join(", ")) };
+        let _ = writeln!(out, "public interface {}{}{extends} {{", short(class), carried_params(self.diagram, class));
+        let mut statics = Vec::new();
+        for member in &class.members {
+            match member {
+                Member::Attribute(a) if !a.is_static => {
+                    let _ = writeln!(out, "    {} {}();", self.ty(a.data_type.as_deref()), getter(&a.name));
+                }
+                Member::Method(m) if m.is_static => statics.push(m),
+                Member::Method(m) => {
+                    let _ = writeln!(out, "    {};", self.signature(m));
+                }
+                Member::Attribute(_) => {}
+            }
+        }
+        for m in statics {
+            let _ = writeln!(out, "\n    static {} {{\n        {STUB}\n    }}", self.signature(m));
+        }
+        out.push_str("}\n");
+    }
+
+    fn enumeration(&self, out: &mut String, class: &Class) {
+        let _ = writeln!(out, "public enum {} {{", short(class));
+        let constants: Vec<&str> = class
+            .members
+            .iter()
+            .filter_map(|m| match m {
+                Member::Attribute(a) => Some(a.name.as_str()),
+                Member::Method(_) => None,
+            })
+            .collect();
+        let methods: Vec<String> = class
+            .members
+            .iter()
+            .filter_map(|m| match m {
+                Member::Method(m) => Some(self.method(m, false)),
+                Member::Attribute(_) => None,
+            })
+            .collect();
+        let _ = write!(out, "    {}", constants.join(",\n    "));
+        out.push_str(if methods.is_empty() { "\n" } else { ";\n" });
+        for m in &methods {
+            out.push('\n');
+            for line in m.lines() {
+                let _ = writeln!(out, "    {line}");
+            }
+        }
+        out.push_str("}\n");
+    }
+
+    fn class(&self, out: &mut String, class: &Class) {
+        let (extends, implements) = parents(self.diagram, class);
+        let mut head = String::from("public ");
+        if kind(self.diagram, class) == Kind::Abstract {
+            head += "abstract ";
+        }
+        let _ = write!(head, "class {}{}", short(class), carried_params(self.diagram, class));
+        if let Some(base) = extends.first() {
+            let _ = write!(head, " extends {}", self.parent(base));
+        }
+        if !implements.is_empty() {
+            let list: Vec<String> = implements.iter().map(|i| self.parent(i)).collect();
+            let _ = write!(head, " implements {}", list.join(", "));
+        }
+        let _ = writeln!(out, "{head} {{");
+
+        let interfaces = interfaces(self.diagram, &implements);
+        let inherited: Vec<&Member> = interfaces
+            .iter()
+            .chain(&ancestors(self.diagram, class))
+            .flat_map(|c| &c.members)
+            .filter(|m| !m.is_static())
+            .collect();
+        let overrides = |name: &str| inherited.iter().any(|m| matches!(m, Member::Method(m) if m.name == name));
+
+        let mut fields: Vec<String> = Vec::new();
+        let mut methods: Vec<String> = Vec::new();
+        for member in &class.members {
+            match member {
+                Member::Attribute(a) => fields.push(self.field(a)),
+                Member::Method(m) => methods.push(self.method(m, overrides(&m.name))),
+            }
+        }
+        for part in parts(self.diagram, class) {
+            let ty = self.reference(part.class);
+            let ty = match part.multiplicity {
+                Multiplicity::One | Multiplicity::Optional => ty,
+                Multiplicity::Many => format!("{}<{ty}>", self.import("java.util.List")),
+            };
+            fields.push(format!("private {ty} {};", ident(&camel_case(&part.field_name()))));
+        }
+
+        // what the interfaces require: getters over a field, and methods
+        let mut required: Vec<&str> = Vec::new();
+        for iface in &interfaces {
+            for member in iface.members.iter().filter(|m| !m.is_static()) {
+                let name = member.name();
+                if required.contains(&name) {
+                    continue;
+                }
+                required.push(name);
+                match member {
+                    Member::Attribute(a) => {
+                        let ty = self.ty(a.data_type.as_deref());
+                        if !class.members.iter().any(|m| m.name() == a.name) {
+                            fields.push(format!("private {ty} {};", ident(&a.name)));
+                        }
+                        methods.push(format!(
+                            "@Override\npublic {ty} {}() {{\n    return {};\n}}",
+                            getter(&a.name),
+                            ident(&a.name)
+                        ));
+                    }
+                    Member::Method(m) if !class.members.iter().any(|own| own.name() == m.name) => {
+                        let m = Method { visibility: Visibility::Public, is_abstract: false, ..m.clone() };
+                        methods.push(self.method(&m, true));
+                    }
+                    Member::Method(_) => {}
+                }
+            }
+        }
+        // and what abstract base classes leave open
+        if kind(self.diagram, class) == Kind::Class {
+            for m in unimplemented(self.diagram, class) {
+                if !required.contains(&m.name.as_str()) {
+                    methods.push(self.method(&Method { is_abstract: false, ..m.clone() }, true));
+                }
+            }
+        }
+
+        for f in &fields {
+            let _ = writeln!(out, "    {f}");
+        }
+        for (i, m) in methods.iter().enumerate() {
+            if i > 0 || !fields.is_empty() {
+                out.push('\n');
+            }
+            for line in m.lines() {
+                let _ = writeln!(out, "    {line}");
+            }
+        }
+        out.push_str("}\n");
+    }
+
+    // ── members ─────────────────────────────────────────────────────────────
+
+    fn field(&self, attribute: &Attribute) -> String {
+        let mut modifiers = modifiers(attribute.visibility).to_owned();
+        if attribute.is_static {
+            modifiers += "static ";
+        }
+        format!("{modifiers}{} {};", self.ty(attribute.data_type.as_deref()), ident(&attribute.name))
+    }
+
+    fn method(&self, method: &Method, overrides: bool) -> String {
+        let mut out = String::new();
+        if overrides {
+            out += "@Override\n";
+        }
+        out += modifiers(method.visibility);
+        if method.is_static {
+            out += "static ";
+        }
+        if method.is_abstract {
+            let _ = write!(out, "abstract {};", self.signature(method));
+        } else {
+            let _ = write!(out, "{} {{\n    {STUB}\n}}", self.signature(method));
+        }
+        out
+    }
+
+    fn signature(&self, method: &Method) -> String {
+        let params: Vec<String> = method
+            .parameters
+            .iter()
+            .map(|p| format!("{} {}", self.ty(p.data_type.as_deref()), ident(&p.name)))
+            .collect();
+        let ret = match &method.return_type {
+            Some(ty) => self.ty(Some(ty)),
+            None => "void".into(),
+        };
+        format!("{ret} {}({})", ident(&method.name), params.join(", "))
+    }
+
+    // ── types ───────────────────────────────────────────────────────────────
+
+    fn ty(&self, ty: Option<&str>) -> String {
+        let Some(ty) = ty else { return "Object".into() };
+        match TypeExpr::parse(ty) {
+            Some(expr) => self.render(&expr, false),
+            None => ty.trim().to_owned(),
+        }
+    }
+
+    /// `boxed` for generic arguments, which cannot be primitives
+    fn render(&self, expr: &TypeExpr, boxed: bool) -> String {
+        let mut out = match lookup(self.diagram, expr.name, &self.package) {
+            Some(class) => self.reference(&class.name),
+            None => self.import(self.options.types.get(expr.name).unwrap_or(expr.name)),
+        };
+        if boxed && expr.arrays == 0 {
+            out = boxed_primitive(&out).map_or(out, str::to_owned);
+        }
+        if !expr.args.is_empty() {
+            let args: Vec<String> = expr.args.iter().map(|a| self.render(a, true)).collect();
+            out = format!("{out}<{}>", args.join(", "));
+        }
+        out + &"[]".repeat(expr.arrays)
+    }
+
+    /// A class extended or implemented, with the type parameters carried over
+    fn parent(&self, fq: &str) -> String {
+        let params = self.diagram.class(fq).map(|c| carried_params(self.diagram, c)).unwrap_or_default();
+        self.reference(fq) + &params
+    }
+
+    /// Simple name of a diagram class, imported if in another package
+    fn reference(&self, fq: &str) -> String {
+        let package = namespace_path(fq);
+        let name = split_namespace(fq).1;
+        if !package.is_empty() && package != self.package {
+            self.imports.borrow_mut().insert(format!("{}.{name}", package.join(".")));
+        }
+        name.to_owned()
+    }
+
+    /// `java.util.List` ➜ `List`, imported
+    fn import(&self, name: &str) -> String {
+        match name.rsplit_once('.') {
+            Some((package, simple)) if package.starts_with(|c: char| c.is_lowercase()) => {
+                if package != "java.lang" {
+                    self.imports.borrow_mut().insert(name.to_owned());
+                }
+                simple.to_owned()
+            }
+            _ => name.to_owned(),
+        }
+    }
+}
+
+fn modifiers(visibility: Visibility) -> &'static str {
+    match visibility {
+        Visibility::Public => "public ",
+        Visibility::Protected => "protected ",
+        Visibility::Private => "private ",
+        Visibility::Package | Visibility::Unspecified => "",
+    }
+}
+
+fn boxed_primitive(ty: &str) -> Option<&'static str> {
+    Some(match ty {
+        "int" => "Integer",
+        "long" => "Long",
+        "short" => "Short",
+        "byte" => "Byte",
+        "float" => "Float",
+        "double" => "Double",
+        "boolean" => "Boolean",
+        "char" => "Character",
+        _ => return None,
+    })
+}
+
+/// `name` ➜ `getName`
+fn getter(attribute: &str) -> String {
+    let mut chars = attribute.chars();
+    let first = chars.next().map(|c| c.to_uppercase().collect::<String>()).unwrap_or_default();
+    format!("get{first}{}", chars.as_str())
+}
+
+/// Escape keywords with a trailing `_`
+fn ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "assert", "boolean", "break", "byte", "case", "catch", "char", "class", "const",
+        "continue", "default", "do", "double", "else", "enum", "extends", "final", "finally", "float",
+        "for", "goto", "if", "implements", "import", "instanceof", "int", "interface", "long", "native",
+        "new", "package", "private", "protected", "public", "return", "short", "static", "strictfp",
+        "super", "switch", "synchronized", "this", "throw", "throws", "transient", "try", "void",
+        "volatile", "while", "true", "false", "null", "_",
+    ];
+    if KEYWORDS.contains(&name) { format!("{name}_") } else { name.to_owned() }
+}
+
@@ -0,0 +1,501 @@
+//! Kotlin skeletons, one file per class.
+//!
+//! ```
+//! use std::path::Path;
+//! use mermaid_parser::types::{Diagram, RelationKind, Visibility};
+//!
+//! let diagram = Diagram::builder()
+//!     .class("geo::Shape", |c| c.annotation("interface").method(Visibility::Public, "area", |m| m.returns("double")))
+//!     .class("geo::Circle", |c| c.attr(Visibility::Private, "radius", "double"))
+//!     .relation("geo::Circle", RelationKind::Extension, "geo::Shape")
+//!     .build()
+//!     .unwrap();
+//! let files = diagram.to_kotlin();
+//! let circle = &files[Path::new("geo/Circle.kt")];
+//! assert!(circle.contains("package geo\n\nclass Circle(\n    private var radius: Double,\n) : Shape {\n"));
+//! assert!(circle.contains("    override fun area(): Double {\n"));
+//! ```
+//!
+//! | diagram                          | Kotlin                                         |
+//! |----------------------------------|------------------------------------------------|
+//! | class / `<<abstract>>`           | `class` (`open` if extended) / `abstract class` |
+//! | `<<interface>>`, realized class  | `interface`, attributes as abstract `val`s     |
+//! | `<<enumeration>>`                | `enum class`, one entry per attribute          |
+//! | `+` / `#` / `-` / `~` or none    | none / `protected` / `private` / `internal` / none |
+//! | `$` / `*`                        | member of the `companion object` / `abstract`  |
+//! | attribute                        | `var` in the primary constructor               |
+//! | namespace `a::b`                 | `package a.b` in directory `a/b`               |
+//! | extends a class                  | `: Base(…)` (the first one only)               |
+//! | extends or realizes an interface | `: Interface`, members copied in as overrides  |
+//! | composition / aggregation        | private `var`, `List<T>` if many, `T?` if optional |
+//!
+//! A subclass constructor takes the parameters of its base class first and
+//! passes them on.  Methods of an open class are `open`, those of a base
+//! class or interface are `override`.  Static attributes are properties
+//! with a getter that has no value yet.  A member without a type is `Any?`,
+//! bodies are `TODO()`.  A concrete class overrides the abstract methods it
+//! inherits, and takes on the type parameters of generic parents:
+//! `class UserRepo<T> : Repo<T>`.
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+
+use super::{
+    ancestors, camel_case, carried_params, interfaces, kind, lookup, namespace_path, parents, parts, short,
+    source_path, unimplemented, Kind, Multiplicity, TypeExpr, TypeMap,
+};
+use crate::types::{split_namespace, Attribute, Class, Diagram, Member, Method, Visibility};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KotlinOptions {
+    pub types: TypeMap,
+}
+
+impl Default for KotlinOptions {
+    fn default() -> Self {
+        KotlinOptions { types: default_types() }
+    }
+}
+
+/// Common Mermaid/UML/Java type names and their Kotlin counterparts
+pub fn default_types() -> TypeMap {
+    TypeMap::from_pairs(&[
+        ("int", "Int"),
+        ("Integer", "Int"),
+        ("long", "Long"),
+        ("short", "Short"),
+        ("byte", "Byte"),
+        ("float", "Float"),
+        ("double", "Double"),
+        ("boolean", "Boolean"),
+        ("bool", "Boolean"),
+        ("char", "Char"),
+        ("str", "String"),
+        ("string", "String"),
+        ("void", "Unit"),
+        ("Object", "Any"),
+        ("decimal", "java.math.BigDecimal"),
+        ("ArrayList", "MutableList"),
+        ("Dict", "Map"),
+        ("HashMap", "MutableMap"),
+        ("HashSet", "MutableSet"),
+    ])
+}
+
+impl Diagram {
+    /// Kotlin sources with the default options, keyed by relative path.
+    pub fn to_kotlin(&self) -> IndexMap<PathBuf, String> {
+        self.to_kotlin_with(&KotlinOptions::default())
+    }
+
+    pub fn to_kotlin_with(&self, options: &KotlinOptions) -> IndexMap<PathBuf, String> {
+        self.classes()
+            .map(|class| {
+                let emitter = Emitter {
+                    diagram: self,
+                    options,
+                    package: namespace_path(&class.name),
+                    imports: RefCell::default(),
+                };
+                (source_path(class, "kt"), emitter.file(class))
+            })
+            .collect()
+    }
+}
+
+/// Writes the file of one class, collecting what it has to import
+struct Emitter<'d> {
+    diagram: &'d Diagram,
+    options: &'d KotlinOptions,
+    package: Vec<&'d str>,
+    imports: RefCell<BTreeSet<String>>,
+}
+
+const STUB: &str = "TODO(\"not implemented\")";
+
+/// A primary constructor parameter
+struct Param {
+    property: String,   // `private var `; none if only passed on to the base class
+    name: String,
+    ty: String,
+    default: &'static str,   // ` = null`, …
+}
+
+impl Param {
+    fn declaration(&self) -> String {
+        format!("{}{}: {}{}", self.property, self.name, self.ty, self.default)
+    }
+}
+
+impl<'d> Emitter<'d> {
+    fn file(&self, class: &Class) -> String {
+        let mut body = String::new();
+        match kind(self.diagram, class) {
+            Kind::Interface => self.interface(&mut body, class),
+            Kind::Enum => self.enumeration(&mut body, class),
+            Kind::Abstract | Kind::Class => self.class(&mut body, class),
+        }
+
+        let mut out = String::from("// Generated from a Mermaid class diagram.\n");
+        if !self.package.is_empty() {
+            let _ = writeln!(out, "package {}", self.package.iter().map(|s| ident(s)).collect::<Vec<_>>().join("."));
+        }
+        out.push('\n');
+        let imports = self.imports.borrow();
+        for import in imports.iter() {
+            let _ = writeln!(out, "import {import}");
+        }
+        if !imports.is_empty() {
+            out.push('\n');
+        }
+        out + &body
+    }
+
+    // ── items ───────────────────────────────────────────────────────────────
+
+    fn interface(&self, out: &mut String, class: &Class) {
+        let (extends, implements) = parents(self.diagram, class);
+        let parents: Vec<String> = extends.iter().chain(&implements).map(|p| self.parent(p)).collect();
+        let supertypes = if parents.is_empty() { String::new() } else { format!(" : {}", parents.join(", ")) };
+        let _ = writeln!(out, "interface {}{}{supertypes} {{", short(class), carried_params(self.diagram, class));
+        for member in &class.members {
+            match member {
+                Member::Attribute(a) if !a.is_static => {
+                    let _ = writeln!(out, "    val {}: {}", ident(&a.name), self.ty(a.data_type.as_deref()));
+                }
+                Member::Method(m) if !m.is_static => {
+                    let _ = writeln!(out, "    {}", self.signature(m));
+                }
+                _ => {}
+            }
+        }
+        self.companion(out, class);
+        out.push_str("}\n");
+    }
+
+    fn enumeration(&self, out: &mut String, class: &Class) {
+        let _ = writeln!(out, "enum class {} {{", short(class));
+        let mut entries = Vec::new();
+        let mut methods = Vec::new();
+        for member in &class.members {
+            match member {
+                Member::Attribute(a) => entries.push(ident(&a.name)),
+                Member::Method(m) if !m.is_static => methods.push(self.method(m, "")),
+                Member::Method(_) => {}
+            }
+        }
+        // anything after the entries, the companion included, needs the `;`
+        let members = !methods.is_empty() || class.members.iter().any(Member::is_static);
+        for (i, entry) in entries.iter().enumerate() {
+            let last = i + 1 == entries.len();
+            let end = if last && members { ";" } else { "," };
+            let _ = writeln!(out, "    {entry}{end}");
+        }
+        if entries.is_empty() && members {
+            out.push_str("    ;\n");
+        }
+        for m in &methods {
+            out.push('\n');
+            for line in m.lines() {
+                let _ = writeln!(out, "    {line}");
+            }
+        }
+        self.companion(out, class);
+        out.push_str("}\n");
+    }
+
+    fn class(&self, out: &mut String, class: &Class) {
+        let (extends, implements) = parents(self.diagram, class);
+        let abstract_ = kind(self.diagram, class) == Kind::Abstract;
+        let open = !abstract_ && self.extended(class);
+        let base = extends.first().and_then(|b| self.diagram.class(b));
+
+        // the base class's parameters are passed on, not declared again
+        let inherited: Vec<Param> = base.map(|b| self.constructor(b)).unwrap_or_default();
+        let mut params: Vec<Param> = inherited.iter().map(|p| Param { property: String::new(), name: p.name.clone(), ty: p.ty.clone(), default: p.default }).collect();
+        params.extend(self.properties(class));
+
+        let mut head = String::new();
+        if abstract_ {
+            head += "abstract ";
+        } else if open {
+            head += "open ";
+        }
+        let _ = write!(head, "class {}{}", short(class), carried_params(self.diagram, class));
+        if !params.is_empty() {
+            head += "(\n";
+            for p in &params {
+                let _ = writeln!(head, "    {},", p.declaration());
+            }
+            head += ")";
+        }
+        let mut supertypes: Vec<String> = Vec::new();
+        if let Some(base) = base {
+            let args: Vec<&str> = inherited.iter().map(|p| p.name.as_str()).collect();
+            supertypes.push(format!("{}({})", self.parent(&base.name), args.join(", ")));
+        }
+        supertypes.extend(implements.iter().map(|i| self.parent(i)));
+        if !supertypes.is_empty() {
+            let _ = write!(head, " : {}", supertypes.join(", "));
+        }
+
+        let interfaces = interfaces(self.diagram, &implements);
+        let inherited: Vec<&Member> = interfaces
+            .iter()
+            .chain(&ancestors(self.diagram, class))
+            .flat_map(|c| &c.members)
+            .filter(|m| !m.is_static())
+            .collect();
+        let overrides = |name: &str| inherited.iter().any(|m| matches!(m, Member::Method(m) if m.name == name));
+
+        let mut methods: Vec<String> = Vec::new();
+        for member in &class.members {
+            if let Member::Method(m) = member
+                && !m.is_static
+            {
+                let modifier = if m.is_abstract {
+                    "abstract "
+                } else if overrides(&m.name) {
+                    "override "
+                } else if (open || abstract_) && m.visibility != Visibility::Private {
+                    "open "
+                } else {
+                    ""
+                };
+                methods.push(self.method(m, modifier));
+            }
+        }
+        let mut required: Vec<&str> = Vec::new();
+        for member in interfaces.iter().flat_map(|i| &i.members) {
+            if let Member::Method(m) = member
+                && !m.is_static
+                && !required.contains(&m.name.as_str())
+                && !class.members.iter().any(|own| own.name() == m.name)
+            {
+                required.push(&m.name);
+                let m = Method { visibility: Visibility::Unspecified, is_abstract: false, ..m.clone() };
+                methods.push(self.method(&m, "override "));
+            }
+        }
+        // and what abstract base classes leave open
+        if kind(self.diagram, class) == Kind::Class {
+            for m in unimplemented(self.diagram, class) {
+                if !required.contains(&m.name.as_str()) {
+                    methods.push(self.method(&Method { is_abstract: false, ..m.clone() }, "override "));
+                }
+            }
+        }
+
+        let mut body = String::new();
+        for (i, m) in methods.iter().enumerate() {
+            if i > 0 {
+                body.push('\n');
+            }
+            for line in m.lines() {
+                let _ = writeln!(body, "    {line}");
+            }
+        }
+        self.companion(&mut body, class);
+        if body.is_empty() {
+            let _ = writeln!(out, "{head}");
+        } else {
+            let _ = write!(out, "{head} {{\n{}}}\n", body.strip_prefix('\n').unwrap_or(&body));
+        }
+    }
+
+    /// Static members, if there are any
+    fn companion(&self, out: &mut String, class: &Class) {
+        let statics: Vec<String> = class
+            .members
+            .iter()
+            .filter(|m| m.is_static())
+            .map(|m| match m {
+                Member::Attribute(a) => format!(
+                    "{}val {}: {}\n    get() = {STUB}",
+                    modifiers(a.visibility),
+                    ident(&a.name),
+                    self.ty(a.data_type.as_deref())
+                ),
+                Member::Method(m) => self.method(m, ""),
+            })
+            .collect();
+        if statics.is_empty() {
+            return;
+        }
+        out.push_str("\n    companion object {\n");
+        for (i, s) in statics.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            for line in s.lines() {
+                let _ = writeln!(out, "        {line}");
+            }
+        }
+        out.push_str("    }\n");
+    }
+
+    // ── members ─────────────────────────────────────────────────────────────
+
+    /// The full primary constructor of `class`: its base's, then its own
+    fn constructor(&self, class: &Class) -> Vec<Param> {
+        let mut params = Vec::new();
+        if let Some(base) = parents(self.diagram, class).0.first().and_then(|b| self.diagram.class(b))
+            && base.name != class.name
+        {
+            params.extend(self.constructor(base));
+        }
+        params.extend(self.properties(class));
+        params
+    }
+
+    /// Properties declared by `class` itself: attributes, parts and the
+    /// attributes its interfaces require
+    fn properties(&self, class: &Class) -> Vec<Param> {
+        let (_, implements) = parents(self.diagram, class);
+        let required: Vec<&Attribute> = interfaces(self.diagram, &implements)
+            .into_iter()
+            .flat_map(|i| &i.members)
+            .filter_map(|m| match m {
+                Member::Attribute(a) if !a.is_static => Some(a),
+                _ => None,
+            })
+            .collect();
+
+        let mut params: Vec<Param> = Vec::new();
+        for member in &class.members {
+            if let Member::Attribute(a) = member
+                && !a.is_static
+            {
+                let overrides = required.iter().any(|r| r.name == a.name);
+                params.push(Param {
+                    property: format!("{}var ", if overrides { "override " } else { modifiers(a.visibility) }),
+                    name: ident(&a.name),
+                    ty: self.ty(a.data_type.as_deref()),
+                    default: "",
+                });
+            }
+        }
+        for a in required {
+            if !params.iter().any(|p| p.name == ident(&a.name)) {
+                params.push(Param {
+                    property: "override var ".into(),
+                    name: ident(&a.name),
+                    ty: self.ty(a.data_type.as_deref()),
+                    default: "",
+                });
+            }
+        }
+        for part in parts(self.diagram, class) {
+            let ty = self.reference(part.class);
+            let (ty, default) = match part.multiplicity {
+                Multiplicity::One => (ty, ""),
+                Multiplicity::Optional => (format!("{ty}?"), " = null"),
+                Multiplicity::Many => (format!("List<{ty}>"), " = emptyList()"),
+            };
+            params.push(Param { property: "private var ".into(), name: ident(&camel_case(&part.field_name())), ty, default });
+        }
+        params
+    }
+
+    fn method(&self, method: &Method, modifier: &str) -> String {
+        let signature = format!("{}{modifier}{}", modifiers(method.visibility), self.signature(method));
+        if method.is_abstract {
+            signature
+        } else {
+            format!("{signature} {{\n    {STUB}\n}}")
+        }
+    }
+
+    fn signature(&self, method: &Method) -> String {
+        let params: Vec<String> = method
+            .parameters
+            .iter()
+            .map(|p| format!("{}: {}", ident(&p.name), self.ty(p.data_type.as_deref())))
+            .collect();
+        let ret = match method.return_type.as_deref().map(|t| self.ty(Some(t))) {
+            Some(ty) if ty != "Unit" => format!(": {ty}"),
+            _ => String::new(),
+        };
+        format!("fun {}({}){ret}", ident(&method.name), params.join(", "))
+    }
+
+    /// Whether another class of the diagram extends this one
+    fn extended(&self, class: &Class) -> bool {
+        self.diagram.classes().any(|c| c.name != class.name && parents(self.diagram, c).0.contains(&class.name.as_str()))
+    }
+
+    // ── types ───────────────────────────────────────────────────────────────
+
+    fn ty(&self, ty: Option<&str>) -> String {
+        let Some(ty) = ty else { return "Any?".into() };
+        match TypeExpr::parse(ty) {
+            Some(expr) => self.render(&expr),
+            None => ty.trim().to_owned(),
+        }
+    }
+
+    fn render(&self, expr: &TypeExpr) -> String {
+        let mut out = match lookup(self.diagram, expr.name, &self.package) {
+            Some(class) => self.reference(&class.name),
+            None => self.import(self.options.types.get(expr.name).unwrap_or(expr.name)),
+        };
+        if !expr.args.is_empty() {
+            let args: Vec<String> = expr.args.iter().map(|a| self.render(a)).collect();
+            out = format!("{out}<{}>", args.join(", "));
+        }
+        for _ in 0..expr.arrays {
+            out = format!("Array<{out}>");
+        }
+        out
+    }
+
+    /// A class extended or implemented, with the type parameters carried over
+    fn parent(&self, fq: &str) -> String {
+        let params = self.diagram.class(fq).map(|c| carried_params(self.diagram, c)).unwrap_or_default();
+        self.reference(fq) + &params
+    }
+
+    /// Simple name of a diagram class, imported if in another package
+    fn reference(&self, fq: &str) -> String {
+        let package = namespace_path(fq);
+        let name = split_namespace(fq).1;
+        if !package.is_empty() && package != self.package {
+            self.imports.borrow_mut().insert(format!("{}.{name}", package.join(".")));
+        }
+        name.to_owned()
+    }
+
+    /// `java.math.BigDecimal` ➜ `BigDecimal`, imported
+    fn import(&self, name: &str) -> String {
+        match name.rsplit_once('.') {
+            Some((package, simple)) if package.starts_with(|c: char| c.is_lowercase()) => {
+                self.imports.borrow_mut().insert(name.to_owned());
+                simple.to_owned()
+            }
+            _ => name.to_owned(),
+        }
+    }
+}
+
+fn modifiers(visibility: Visibility) -> &'static str {
+    match visibility {
+        Visibility::Public | Visibility::Unspecified => "",
+        Visibility::Protected => "protected ",
+        Visibility::Private => "private ",
+        Visibility::Package => "internal ",
+    }
+}
+
+/// Escape hard keywords with backticks
+fn ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "break", "class", "continue", "do", "else", "false", "for", "fun", "if", "in", "interface",
+        "is", "null", "object", "package", "return", "super", "this", "throw", "true", "try", "typealias",
+        "typeof", "val", "var", "when", "while",
+    ];
+    if KEYWORDS.contains(&name) { format!("`{name}`") } else { name.to_owned() }
+}
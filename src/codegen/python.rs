@@ -0,0 +1,486 @@
+//! Python models, one module per namespace.
+//!
+//! ```
+//! use std::path::Path;
+//! use mermaid_parser::codegen::python::{PythonOptions, PythonStyle};
+//! use mermaid_parser::types::{Diagram, Visibility};
+//!
+//! let diagram = Diagram::builder()
+//!     .class("Point", |c| c.attr(Visibility::Public, "x", "double").attr(Visibility::Public, "tags", "List~String~"))
+//!     .build()
+//!     .unwrap();
+//! let files = diagram.to_python();
+//! assert!(files[Path::new("models.py")].contains("@dataclass(kw_only=True)\nclass Point:\n    x: float\n    tags: list[str]\n"));
+//!
+//! let options = PythonOptions { style: PythonStyle::Pydantic, ..PythonOptions::default() };
+//! let files = diagram.to_python_with(&options);
+//! assert!(files[Path::new("models.py")].contains("class Point(BaseModel):\n    x: float\n"));
+//! ```
+//!
+//! | diagram                          | Python                                          |
+//! |----------------------------------|-------------------------------------------------|
+//! | class                            | `@dataclass` or `BaseModel` subclass            |
+//! | `<<abstract>>`, `<<interface>>`  | also an `ABC`; abstract methods `@abstractmethod` |
+//! | `<<enumeration>>`                | `Enum`, one `auto()` member per attribute       |
+//! | `+` / `~` or none                | `name`                                          |
+//! | `#` / `-`                        | `_name`, a Pydantic field `name` aliased `_name` |
+//! | `$`                              | `ClassVar[…]` / `@staticmethod`                 |
+//! | generic `T`                      | `T = TypeVar("T")`, `Generic[T]`                |
+//! | namespace `a::b`                 | module `a/b.py`, imported as `from a.b import …` |
+//! | extends or realizes              | base classes                                    |
+//! | composition / aggregation        | field, `list[T]` if many, `T \| None` if optional |
+//!
+//! Classes outside any namespace go in [`PythonOptions::root_module`]; a
+//! namespace that also has nested ones becomes a package `a/__init__.py`.
+//! Names are snake_case, keywords get a trailing `_`.  Dataclasses are
+//! `kw_only` so that fields with defaults may come before others.  Members
+//! of implemented interfaces are declared again, method bodies raise
+//! `NotImplementedError`, and a member without a type is `Any`.  The code
+//! targets Python 3.10 and Pydantic 2.
+//!
+//! Only base classes are imported from other modules up front, so modules
+//! may refer to each other's classes in annotations.  Dataclasses import
+//! those names under `if TYPE_CHECKING:`; Pydantic resolves annotations at
+//! runtime and imports them at the end of the module instead, which fails
+//! only if a module is imported first whose base class comes from a module
+//! that in turn refers to it.  Pydantic ignores fields starting with `_`,
+//! so private ones keep the plain name and take the `_` name as an alias.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+
+use super::{
+    bases_first, generic_names, interfaces, kind, lookup, modules, namespace_path, parents, parts, short, snake_case,
+    Kind, Multiplicity, TypeExpr, TypeMap,
+};
+use crate::types::{split_namespace, Attribute, Class, Diagram, Member, Method, Visibility};
+
+/// What a class is made of
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PythonStyle {
+    #[default]
+    Dataclass,   // `@dataclasses.dataclass`
+    Pydantic,    // `pydantic.BaseModel`
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PythonOptions {
+    pub style: PythonStyle,
+    pub types: TypeMap,
+    pub root_module: String,   // module of the classes outside any namespace
+}
+
+impl Default for PythonOptions {
+    fn default() -> Self {
+        PythonOptions {
+            style: PythonStyle::default(),
+            types: default_types(),
+            root_module: "models".into(),
+        }
+    }
+}
+
+/// Common Mermaid/UML/Java type names and their Python counterparts; a
+/// dotted target such as `decimal.Decimal` is imported
+pub fn default_types() -> TypeMap {
+    TypeMap::from_pairs(&[
+        ("Integer", "int"),
+        ("long", "int"),
+        ("Long", "int"),
+        ("short", "int"),
+        ("byte", "int"),
+        ("double", "float"),
+        ("Double", "float"),
+        ("Float", "float"),
+        ("String", "str"),
+        ("string", "str"),
+        ("char", "str"),
+        ("boolean", "bool"),
+        ("Boolean", "bool"),
+        ("void", "None"),
+        ("Object", "typing.Any"),
+        ("decimal", "decimal.Decimal"),
+        ("List", "list"),
+        ("ArrayList", "list"),
+        ("Map", "dict"),
+        ("HashMap", "dict"),
+        ("Dict", "dict"),
+        ("Set", "set"),
+        ("HashSet", "set"),
+    ])
+}
+
+impl Diagram {
+    /// Python modules with the default options, keyed by relative path.
+    pub fn to_python(&self) -> IndexMap<PathBuf, String> {
+        self.to_python_with(&PythonOptions::default())
+    }
+
+    pub fn to_python_with(&self, options: &PythonOptions) -> IndexMap<PathBuf, String> {
+        let modules = modules(self);
+        let packages: Vec<&Vec<&str>> = modules
+            .keys()
+            .filter(|p| modules.keys().any(|q| q.len() > p.len() && q.starts_with(p)))
+            .collect();
+        modules
+            .iter()
+            .map(|(path, classes)| {
+                let mut file: PathBuf = path.iter().collect();
+                if path.is_empty() {
+                    file.push(format!("{}.py", options.root_module));
+                } else if packages.contains(&path) {
+                    file.push("__init__.py");
+                } else {
+                    file.set_extension("py");
+                }
+                let emitter = Emitter {
+                    diagram: self,
+                    options,
+                    package: path.clone(),
+                    imports: Default::default(),
+                };
+                (file, emitter.module(classes))
+            })
+            .collect()
+    }
+}
+
+/// Writes one module, collecting what it has to import
+struct Emitter<'d> {
+    diagram: &'d Diagram,
+    options: &'d PythonOptions,
+    package: Vec<&'d str>,
+    imports: RefCell<Imports>,
+}
+
+/// `from module import name`, library modules apart from the diagram's own,
+/// and the diagram's classes needed in annotations only
+#[derive(Default)]
+struct Imports {
+    library: BTreeMap<String, BTreeSet<String>>,
+    local: BTreeMap<String, BTreeSet<String>>,
+    annotations: BTreeMap<String, BTreeSet<String>>,
+}
+
+const STUB: &str = "raise NotImplementedError";
+
+impl<'d> Emitter<'d> {
+    fn module(&self, classes: &[&'d Class]) -> String {
+        let mut body = String::new();
+        let mut type_vars: Vec<String> = Vec::new();
+        for class in bases_first(self.diagram, classes) {
+            body.push_str("\n\n");
+            match kind(self.diagram, class) {
+                Kind::Enum => self.enumeration(&mut body, class),
+                Kind::Interface | Kind::Abstract | Kind::Class => self.class(&mut body, class),
+            }
+            for t in generic_names(class) {
+                if !type_vars.contains(&t) {
+                    type_vars.push(t);
+                }
+            }
+        }
+        if !type_vars.is_empty() {
+            self.library("typing", "TypeVar");
+        }
+        let annotations = self.annotation_imports();
+        let deferred = !annotations.is_empty() && self.options.style == PythonStyle::Dataclass;
+        if deferred {
+            self.library("typing", "TYPE_CHECKING");
+        }
+
+        let mut out = String::from("\"\"\"Generated from a Mermaid class diagram.\"\"\"\n\nfrom __future__ import annotations\n");
+        let imports = self.imports.borrow();
+        for group in [&imports.library, &imports.local] {
+            if !group.is_empty() {
+                out.push('\n');
+                write_imports(&mut out, group, "");
+            }
+        }
+        if deferred {
+            out.push_str("\nif TYPE_CHECKING:\n");
+            write_imports(&mut out, &annotations, "    ");
+        }
+        if !type_vars.is_empty() {
+            out.push('\n');
+            for t in &type_vars {
+                let _ = writeln!(out, "{t} = TypeVar(\"{t}\")");
+            }
+        }
+        out += &body;
+        if !annotations.is_empty() && self.options.style == PythonStyle::Pydantic {
+            // after the classes, so that modules importing each other find them
+            out.push_str("\n\n");
+            write_imports(&mut out, &annotations, "");
+        }
+        out
+    }
+
+    /// Classes of other modules named only in annotations
+    fn annotation_imports(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let imports = self.imports.borrow();
+        let mut annotations = imports.annotations.clone();
+        for (module, names) in &mut annotations {
+            if let Some(imported) = imports.local.get(module) {
+                names.retain(|n| !imported.contains(n));
+            }
+        }
+        annotations.retain(|_, names| !names.is_empty());
+        annotations
+    }
+
+    // ── items ───────────────────────────────────────────────────────────────
+
+    fn enumeration(&self, out: &mut String, class: &Class) {
+        self.library("enum", "Enum");
+        self.library("enum", "auto");
+        let _ = writeln!(out, "class {}(Enum):", short(class));
+        let mut lines: Vec<String> = Vec::new();
+        for member in &class.members {
+            if let Member::Attribute(a) = member {
+                lines.push(format!("{} = auto()", ident(&a.name)));
+            }
+        }
+        for member in &class.members {
+            if let Member::Method(m) = member {
+                lines.push(String::new());
+                lines.extend(self.method(m).lines().map(str::to_owned));
+            }
+        }
+        write_body(out, &lines);
+    }
+
+    fn class(&self, out: &mut String, class: &Class) {
+        let class_kind = kind(self.diagram, class);
+        let (extends, implements) = parents(self.diagram, class);
+        let model = class_kind != Kind::Interface;
+        let mut bases: Vec<String> = extends.iter().chain(&implements).map(|p| self.base(p)).collect();
+        if model && extends.is_empty() && self.options.style == PythonStyle::Pydantic {
+            self.library("pydantic", "BaseModel");
+            bases.push("BaseModel".into());
+        }
+        let abstract_parent = extends.iter().chain(&implements).any(|p| {
+            self.diagram.class(p).is_some_and(|c| matches!(kind(self.diagram, c), Kind::Interface | Kind::Abstract))
+        });
+        if matches!(class_kind, Kind::Interface | Kind::Abstract) && !abstract_parent {
+            self.library("abc", "ABC");
+            bases.push("ABC".into());
+        }
+        let generics = generic_names(class);
+        if !generics.is_empty() {
+            self.library("typing", "Generic");
+            bases.push(format!("Generic[{}]", generics.join(", ")));
+        }
+
+        if model && self.options.style == PythonStyle::Dataclass {
+            self.library("dataclasses", "dataclass");
+            out.push_str("@dataclass(kw_only=True)\n");
+        }
+        let bases = if bases.is_empty() { String::new() } else { format!("({})", bases.join(", ")) };
+        let _ = writeln!(out, "class {}{bases}:", short(class));
+
+        let mut fields: Vec<String> = Vec::new();
+        let mut methods: Vec<String> = Vec::new();
+        for member in &class.members {
+            match member {
+                Member::Attribute(a) => fields.push(self.field(a, model)),
+                // an interface's methods are all abstract
+                Member::Method(m) if !model && !m.is_static => {
+                    methods.push(self.method(&Method { is_abstract: true, ..m.clone() }))
+                }
+                Member::Method(m) => methods.push(self.method(m)),
+            }
+        }
+        if model {
+            // what the interfaces require but the class lacks
+            let mut required: Vec<&str> = Vec::new();
+            for member in interfaces(self.diagram, &implements).into_iter().flat_map(|i| &i.members) {
+                let name = member.name();
+                if required.contains(&name) || class.members.iter().any(|m| m.name() == name) {
+                    continue;
+                }
+                required.push(name);
+                match member {
+                    Member::Attribute(a) => fields.push(self.field(a, model)),
+                    Member::Method(m) => methods.push(self.method(&Method { is_abstract: false, ..m.clone() })),
+                }
+            }
+            for part in parts(self.diagram, class) {
+                let name = ident(&snake_case(&part.field_name()));
+                let ty = self.reference(part.class);
+                fields.push(match (part.multiplicity, self.options.style) {
+                    (Multiplicity::One, _) => format!("{name}: {ty}"),
+                    (Multiplicity::Optional, _) => format!("{name}: {ty} | None = None"),
+                    (Multiplicity::Many, PythonStyle::Pydantic) => format!("{name}: list[{ty}] = []"),
+                    (Multiplicity::Many, PythonStyle::Dataclass) => {
+                        self.library("dataclasses", "field");
+                        format!("{name}: list[{ty}] = field(default_factory=list)")
+                    }
+                });
+            }
+        }
+
+        let mut lines = fields;
+        for m in methods {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.extend(m.lines().map(str::to_owned));
+        }
+        write_body(out, &lines);
+    }
+
+    // ── members ─────────────────────────────────────────────────────────────
+
+    fn field(&self, attribute: &Attribute, model: bool) -> String {
+        let ty = self.ty(attribute.data_type.as_deref());
+        let name = name(&attribute.name, attribute.visibility);
+        if attribute.is_static {
+            self.library("typing", "ClassVar");
+            return format!("{}: ClassVar[{ty}]", name.to_uppercase());
+        }
+        if model
+            && self.options.style == PythonStyle::Pydantic
+            && let Some(public) = name.strip_prefix('_')
+        {
+            self.library("pydantic", "Field");
+            return format!("{public}: {ty} = Field(alias=\"{name}\")");
+        }
+        format!("{name}: {ty}")
+    }
+
+    fn method(&self, method: &Method) -> String {
+        let mut out = String::new();
+        let mut params: Vec<String> = Vec::new();
+        if method.is_static {
+            out += "@staticmethod\n";
+        } else {
+            params.push("self".into());
+        }
+        if method.is_abstract {
+            self.library("abc", "abstractmethod");
+            out += "@abstractmethod\n";
+        }
+        params.extend(
+            method
+                .parameters
+                .iter()
+                .map(|p| format!("{}: {}", ident(&snake_case(&p.name)), self.ty(p.data_type.as_deref()))),
+        );
+        let ret = match &method.return_type {
+            Some(ty) => self.ty(Some(ty)),
+            None => "None".into(),
+        };
+        let _ = write!(out, "def {}({}) -> {ret}:\n    ", name(&method.name, method.visibility), params.join(", "));
+        out += if method.is_abstract { "..." } else { STUB };
+        out
+    }
+
+    // ── types ───────────────────────────────────────────────────────────────
+
+    fn ty(&self, ty: Option<&str>) -> String {
+        let Some(ty) = ty else {
+            self.library("typing", "Any");
+            return "Any".into();
+        };
+        match TypeExpr::parse(ty) {
+            Some(expr) => self.render(&expr),
+            None => ty.trim().to_owned(),
+        }
+    }
+
+    fn render(&self, expr: &TypeExpr) -> String {
+        let mut out = match lookup(self.diagram, expr.name, &self.package) {
+            Some(class) => self.reference(&class.name),
+            None => {
+                let name = self.options.types.get(expr.name).unwrap_or(expr.name);
+                match name.rsplit_once('.') {
+                    Some((module, name)) => {
+                        self.library(module, name);
+                        name.to_owned()
+                    }
+                    None => name.to_owned(),
+                }
+            }
+        };
+        if !expr.args.is_empty() {
+            let args: Vec<String> = expr.args.iter().map(|a| self.render(a)).collect();
+            out = format!("{out}[{}]", args.join(", "));
+        }
+        for _ in 0..expr.arrays {
+            out = format!("list[{out}]");
+        }
+        out
+    }
+
+    /// Name of a diagram class used in an annotation, imported if in
+    /// another module
+    fn reference(&self, fq: &str) -> String {
+        self.import(fq, false)
+    }
+
+    /// Name of a base class, imported up front if in another module
+    fn base(&self, fq: &str) -> String {
+        self.import(fq, true)
+    }
+
+    fn import(&self, fq: &str, runtime: bool) -> String {
+        let package = namespace_path(fq);
+        let name = split_namespace(fq).1;
+        if package != self.package {
+            let module = if package.is_empty() { self.options.root_module.clone() } else { package.join(".") };
+            let mut imports = self.imports.borrow_mut();
+            let group = if runtime { &mut imports.local } else { &mut imports.annotations };
+            group.entry(module).or_default().insert(name.to_owned());
+        }
+        name.to_owned()
+    }
+
+    fn library(&self, module: &str, name: &str) {
+        self.imports.borrow_mut().library.entry(module.to_owned()).or_default().insert(name.to_owned());
+    }
+}
+
+fn write_imports(out: &mut String, group: &BTreeMap<String, BTreeSet<String>>, indent: &str) {
+    for (module, names) in group {
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let _ = writeln!(out, "{indent}from {module} import {}", names.join(", "));
+    }
+}
+
+/// Indented class body, `pass` if empty
+fn write_body(out: &mut String, lines: &[String]) {
+    if lines.is_empty() {
+        out.push_str("    pass\n");
+    }
+    for line in lines {
+        if line.is_empty() {
+            out.push('\n');
+        } else {
+            let _ = writeln!(out, "    {line}");
+        }
+    }
+}
+
+/// Member name: snake_case, `_` prefixed unless public
+fn name(name: &str, visibility: Visibility) -> String {
+    let name = ident(&snake_case(name));
+    match visibility {
+        Visibility::Private | Visibility::Protected => format!("_{name}"),
+        Visibility::Public | Visibility::Package | Visibility::Unspecified => name,
+    }
+}
+
+/// Escape keywords with a trailing `_`
+fn ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+        "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in",
+        "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+    ];
+    if KEYWORDS.contains(&name) { format!("{name}_") } else { name.to_owned() }
+}
@@ -0,0 +1,585 @@
+//! Rust skeletons.
+//!
+//! ```
+//! use mermaid_parser::types::{Diagram, RelationKind, Visibility};
+//!
+//! let diagram = Diagram::builder()
+//!     .class("Shape", |c| c.annotation("interface").method(Visibility::Public, "area", |m| m.returns("double")))
+//!     .class("Circle", |c| c.attr(Visibility::Private, "radius", "double"))
+//!     .relation("Circle", RelationKind::Extension, "Shape")
+//!     .build()
+//!     .unwrap();
+//! let rust = diagram.to_rust();
+//! assert!(rust.contains("pub trait Shape {\n    fn area(&self) -> f64;\n}"));
+//! assert!(rust.contains("pub struct Circle {\n    radius: f64,\n}"));
+//! assert!(rust.contains("impl Shape for Circle {"));
+//! ```
+//!
+//! | diagram                          | Rust                                     |
+//! |----------------------------------|------------------------------------------|
+//! | class                            | `struct` with an inherent `impl`         |
+//! | `<<interface>>`, realized class  | `trait`; parents become supertraits      |
+//! | `<<enumeration>>`                | `enum`, one variant per attribute        |
+//! | attribute / static attribute     | field / associated `const`               |
+//! | method / static method           | `fn(&self, …)` / `fn(…)`, body `todo!()` |
+//! | `+` / `~` `#` / `-` or none      | `pub` / `pub(crate)` / private           |
+//! | namespace                        | nested `pub mod`                         |
+//! | extends a class                  | field holding the base                   |
+//! | extends or realizes an interface | `impl Trait for Type`                    |
+//! | composition                      | owned field (`Vec`/`Option` by label)    |
+//! | aggregation                      | `Rc` or borrowed field, see [`Aggregation`] |
+//!
+//! Members and fields are renamed to snake_case.  Interface types used as
+//! values become `Box<dyn Trait>`; a member without a type gets `()`.  A
+//! field holding its own class, or a class that holds it in turn, is boxed,
+//! and receiver-less trait items get `where Self: Sized` so the trait stays
+//! usable as `dyn Trait`.
+
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use super::{generic_names, kind, map_type, namespace_path, parents, parts, short, snake_case, type_params, Generics, Kind, Module, Multiplicity, TypeExpr, TypeMap};
+use crate::types::{split_namespace, Class, Diagram, Member, Method, Visibility};
+
+/// How an aggregated part is held by its whole
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Aggregation {
+    #[default]
+    Rc,        // `std::rc::Rc<Part>`
+    Borrow,    // `&'a Part`, the whole gains a lifetime `'a`
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustOptions {
+    pub types: TypeMap,
+    pub aggregation: Aggregation,
+}
+
+impl Default for RustOptions {
+    fn default() -> Self {
+        RustOptions { types: default_types(), aggregation: Aggregation::default() }
+    }
+}
+
+/// Common Mermaid/UML/Java type names and their Rust counterparts
+pub fn default_types() -> TypeMap {
+    TypeMap::from_pairs(&[
+        ("int", "i32"),
+        ("Integer", "i32"),
+        ("long", "i64"),
+        ("Long", "i64"),
+        ("short", "i16"),
+        ("byte", "u8"),
+        ("float", "f32"),
+        ("double", "f64"),
+        ("Double", "f64"),
+        ("bool", "bool"),
+        ("boolean", "bool"),
+        ("Boolean", "bool"),
+        ("char", "char"),
+        ("string", "String"),
+        ("str", "String"),
+        ("void", "()"),
+        ("List", "Vec"),
+        ("ArrayList", "Vec"),
+        ("Set", "std::collections::HashSet"),
+        ("Map", "std::collections::HashMap"),
+        ("Dict", "std::collections::HashMap"),
+        ("Optional", "Option"),
+    ])
+}
+
+impl Diagram {
+    /// Rust skeleton with the default options.
+    pub fn to_rust(&self) -> String {
+        self.to_rust_with(&RustOptions::default())
+    }
+
+    pub fn to_rust_with(&self, options: &RustOptions) -> String {
+        let root = Module::tree(self);
+        let mut out = String::from("// Generated from a Mermaid class diagram.\n");
+        let held = held(self, options);
+        let emitter = Emitter {
+            diagram: self,
+            options,
+            current: Cell::new(""),
+            field: Cell::new(false),
+            borrowing: borrowing(self, options, &held),
+            held,
+        };
+        emitter.module(&mut out, &root, &[]);
+        out
+    }
+}
+
+struct Emitter<'d> {
+    diagram: &'d Diagram,
+    options: &'d RustOptions,
+    current: Cell<&'d str>,        // class being emitted, written `Self`
+    field: Cell<bool>,             // rendering a struct field, not a signature
+    held: HashMap<&'d str, Vec<Held<'d>>>,
+    borrowing: HashSet<&'d str>,   // structs carrying the `'a` of borrowed parts
+}
+
+/// A class a struct's fields refer to
+#[derive(Debug, Clone, Copy)]
+struct Held<'d> {
+    class: &'d str,
+    by_value: bool,                // inline, not behind a `Vec`, `Rc` or `&`
+}
+
+const GENERICS: Generics = Generics {
+    open: "<",
+    close: ">",
+    array: |element| format!("Vec<{element}>"),
+};
+
+impl<'d> Emitter<'d> {
+    fn module(&self, out: &mut String, module: &Module<'d>, path: &[&'d str]) {
+        let indent = "    ".repeat(path.len());
+        for (i, class) in module.classes.iter().enumerate() {
+            let mut item = String::new();
+            self.current.set(&class.name);
+            match kind(self.diagram, class) {
+                Kind::Interface => self.interface(&mut item, class, path),
+                Kind::Enum => self.enumeration(&mut item, class, path),
+                Kind::Abstract | Kind::Class => self.structure(&mut item, class, path),
+            }
+            if i > 0 || path.is_empty() {
+                out.push('\n');
+            }
+            for line in item.lines() {
+                if line.is_empty() {
+                    out.push('\n');
+                } else {
+                    let _ = writeln!(out, "{indent}{line}");
+                }
+            }
+        }
+        for (i, (name, child)) in module.children.iter().enumerate() {
+            if i > 0 || !module.classes.is_empty() || path.is_empty() {
+                out.push('\n');
+            }
+            let _ = writeln!(out, "{indent}pub mod {} {{", ident(&snake_case(name)));
+            let mut inner = path.to_vec();
+            inner.push(name);
+            self.module(out, child, &inner);
+            let _ = writeln!(out, "{indent}}}");
+        }
+    }
+
+    // ── items ───────────────────────────────────────────────────────────────
+
+    fn interface(&self, out: &mut String, class: &Class, path: &[&str]) {
+        let (extends, implements) = parents(self.diagram, class);
+        let supertraits: Vec<String> =
+            extends.iter().chain(&implements).map(|p| self.path_to(p, path)).collect();
+        let bounds = if supertraits.is_empty() { String::new() } else { format!(": {}", supertraits.join(" + ")) };
+        let _ = writeln!(out, "pub trait {}{}{bounds} {{", short(class), type_params(class));
+        for signature in self.trait_items(class, path) {
+            let _ = writeln!(out, "    {signature};");
+        }
+        out.push_str("}\n");
+    }
+
+    fn enumeration(&self, out: &mut String, class: &Class, path: &[&str]) {
+        let _ = writeln!(out, "#[derive(Debug, Clone, Copy, PartialEq, Eq)]");
+        let _ = writeln!(out, "pub enum {} {{", short(class));
+        for member in &class.members {
+            if let Member::Attribute(a) = member {
+                let _ = writeln!(out, "    {},", pascal_case(&a.name));
+            }
+        }
+        out.push_str("}\n");
+        let methods: Vec<&Method> = methods(class).collect();
+        if !methods.is_empty() {
+            let _ = writeln!(out, "\nimpl {} {{", short(class));
+            self.inherent(out, &methods, path);
+            out.push_str("}\n");
+        }
+    }
+
+    fn structure(&self, out: &mut String, class: &Class, path: &[&str]) {
+        let (extends, implements) = parents(self.diagram, class);
+        let parts = parts(self.diagram, class);
+        let borrows = self.borrows(class);
+
+        // fields first, so unused type parameters can be spotted
+        let mut fields: Vec<String> = Vec::new();
+        self.field.set(true);
+        for parent in &extends {
+            fields.push(format!("{}: {},", ident(&snake_case(split_namespace(parent).1)), self.value_type(parent, path, true)));
+        }
+        for member in &class.members {
+            if let Member::Attribute(a) = member
+                && !a.is_static
+            {
+                let ty = self.ty(a.data_type.as_deref(), path);
+                fields.push(format!("{}{}: {ty},", visibility(a.visibility), ident(&snake_case(&a.name))));
+            }
+        }
+        for part in &parts {
+            let by_value = !part.shared && part.multiplicity != Multiplicity::Many;
+            let target = self.value_type(part.class, path, by_value);
+            let held = match (part.shared, self.options.aggregation) {
+                (false, _) => target,
+                (true, Aggregation::Rc) => format!("std::rc::Rc<{target}>"),
+                (true, Aggregation::Borrow) => format!("&'a {target}"),
+            };
+            let held = match part.multiplicity {
+                Multiplicity::One => held,
+                Multiplicity::Optional => format!("Option<{held}>"),
+                Multiplicity::Many => format!("Vec<{held}>"),
+            };
+            fields.push(format!("{}: {held},", ident(&part.field_name())));
+        }
+        self.field.set(false);
+
+        let mut params: Vec<String> = Vec::new();
+        if borrows {
+            params.push("'a".into());
+        }
+        let generics = generic_names(class);
+        params.extend(generics.iter().cloned());
+        let unused: Vec<&String> =
+            generics.iter().filter(|g| !fields.iter().any(|f| super::mentions(f, g))).collect();
+        if !unused.is_empty() {
+            let list: Vec<&str> = unused.iter().map(|g| g.as_str()).collect();
+            fields.push(format!("_marker: std::marker::PhantomData<({},)>,", list.join(", ")));
+        }
+        let params = if params.is_empty() { String::new() } else { format!("<{}>", params.join(", ")) };
+        let name = short(class);
+
+        if fields.is_empty() {
+            let _ = writeln!(out, "pub struct {name}{params};");
+        } else {
+            let _ = writeln!(out, "pub struct {name}{params} {{");
+            for f in &fields {
+                let _ = writeln!(out, "    {f}");
+            }
+            out.push_str("}\n");
+        }
+
+        // every interface, with the ones they extend, needs an impl
+        let mut traits: Vec<&str> = Vec::new();
+        let mut pending = implements;
+        while let Some(t) = pending.pop() {
+            if !traits.contains(&t) {
+                traits.push(t);
+                if let Some(iface) = self.diagram.class(t) {
+                    let (e, i) = parents(self.diagram, iface);
+                    pending.extend(e.into_iter().chain(i));
+                }
+            }
+        }
+        let trait_methods: Vec<&str> = traits
+            .iter()
+            .filter_map(|t| self.diagram.class(t))
+            .flat_map(|t| t.members.iter().map(Member::name))
+            .collect();
+
+        let consts: Vec<String> = class
+            .members
+            .iter()
+            .filter_map(|m| match m {
+                Member::Attribute(a) if a.is_static => Some(format!(
+                    "{}const {}: {} = todo!();",
+                    visibility(a.visibility),
+                    snake_case(&a.name).to_uppercase(),
+                    self.ty(a.data_type.as_deref(), path)
+                )),
+                _ => None,
+            })
+            .collect();
+        let own: Vec<&Method> = methods(class).filter(|m| !trait_methods.contains(&m.name.as_str())).collect();
+        if !consts.is_empty() || !own.is_empty() {
+            let _ = writeln!(out, "\nimpl{params} {name}{params} {{");
+            for c in &consts {
+                let _ = writeln!(out, "    {c}");
+            }
+            if !consts.is_empty() && !own.is_empty() {
+                out.push('\n');
+            }
+            self.inherent(out, &own, path);
+            out.push_str("}\n");
+        }
+
+        for t in traits.iter().rev() {
+            let Some(iface) = self.diagram.class(t) else {
+                let _ = writeln!(out, "\nimpl{params} {} for {name}{params} {{}}", self.path_to(t, path));
+                continue;
+            };
+            let mut impl_params = params.trim_matches(|c| c == '<' || c == '>').to_owned();
+            for g in generic_names(iface) {
+                if !generics.contains(&g) {
+                    if !impl_params.is_empty() {
+                        impl_params += ", ";
+                    }
+                    impl_params += &g;
+                }
+            }
+            let impl_params = if impl_params.is_empty() { String::new() } else { format!("<{impl_params}>") };
+            let _ = writeln!(
+                out,
+                "\nimpl{impl_params} {}{} for {name}{params} {{",
+                self.path_to(t, path),
+                type_params(iface)
+            );
+            for (i, signature) in self.trait_items(iface, path).iter().enumerate() {
+                if i > 0 {
+                    out.push('\n');
+                }
+                let _ = writeln!(out, "    {signature} {{\n        todo!()\n    }}");
+            }
+            out.push_str("}\n");
+        }
+    }
+
+    /// Bodies of an inherent `impl`
+    fn inherent(&self, out: &mut String, methods: &[&Method], path: &[&str]) {
+        for (i, m) in methods.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let _ = writeln!(
+                out,
+                "    {}{} {{\n        todo!()\n    }}",
+                visibility(m.visibility),
+                self.signature(m, path)
+            );
+        }
+    }
+
+    /// Trait items: methods, and attributes as getters.  Items without a
+    /// receiver are kept out of the vtable, or `dyn Trait` would not exist.
+    fn trait_items(&self, class: &Class, path: &[&str]) -> Vec<String> {
+        class
+            .members
+            .iter()
+            .map(|m| match m {
+                Member::Attribute(a) if a.is_static => {
+                    format!("fn {}() -> {} where Self: Sized", ident(&snake_case(&a.name)), self.ty(a.data_type.as_deref(), path))
+                }
+                Member::Attribute(a) => {
+                    format!("fn {}(&self) -> {}", ident(&snake_case(&a.name)), self.ty(a.data_type.as_deref(), path))
+                }
+                Member::Method(m) if m.is_static => format!("{} where Self: Sized", self.signature(m, path)),
+                Member::Method(m) => self.signature(m, path),
+            })
+            .collect()
+    }
+
+    fn signature(&self, method: &Method, path: &[&str]) -> String {
+        let mut params: Vec<String> = Vec::new();
+        if !method.is_static {
+            params.push("&self".into());
+        }
+        for p in &method.parameters {
+            params.push(format!("{}: {}", ident(&snake_case(&p.name)), self.ty(p.data_type.as_deref(), path)));
+        }
+        let mut signature = format!("fn {}({})", ident(&snake_case(&method.name)), params.join(", "));
+        let ret = self.ty(method.return_type.as_deref(), path);
+        if ret != "()" {
+            let _ = write!(signature, " -> {ret}");
+        }
+        signature
+    }
+
+    // ── types ───────────────────────────────────────────────────────────────
+
+    /// A member type; interfaces of the diagram become trait objects
+    fn ty(&self, ty: Option<&str>, path: &[&str]) -> String {
+        let Some(ty) = ty else { return "()".into() };
+        match TypeExpr::parse(ty) {
+            Some(expr) => self.render(&expr, path, true),
+            None => map_type(ty, &self.options.types, &GENERICS),
+        }
+    }
+
+    /// `by_value`: the type is stored inline, so a cycle through it needs a box
+    fn render(&self, expr: &TypeExpr, path: &[&str], by_value: bool) -> String {
+        let by_value = by_value && expr.arrays == 0;
+        let mut out = match super::lookup(self.diagram, expr.name, path) {
+            Some(class) if expr.args.is_empty() => self.value_type(&class.name, path, by_value),
+            _ => self.options.types.get(expr.name).unwrap_or(expr.name).to_owned(),
+        };
+        if !expr.args.is_empty() {
+            // only an `Option` keeps its argument inline
+            let inline = by_value && out == "Option";
+            let args: Vec<String> = expr.args.iter().map(|a| self.render(a, path, inline)).collect();
+            out = format!("{out}<{}>", args.join(", "));
+        }
+        for _ in 0..expr.arrays {
+            out = format!("Vec<{out}>");
+        }
+        out
+    }
+
+    /// How a value of the class is written; in a field, `by_value` marks it
+    /// as stored inline
+    fn value_type(&self, fq: &str, path: &[&str], by_value: bool) -> String {
+        let name = self.path_to(fq, path);
+        let lifetime = if self.field.get() { "'a" } else { "'_" };
+        let ty = match self.diagram.class(fq).map(|c| (c, kind(self.diagram, c))) {
+            Some((_, Kind::Interface)) => return format!("Box<dyn {name}>"),
+            _ if fq == self.current.get() => "Self".into(),
+            Some((class, Kind::Abstract | Kind::Class)) if self.borrows(class) => format!("{name}<{lifetime}>"),
+            _ => name,
+        };
+        if self.field.get() && by_value && self.reaches(fq, self.current.get()) {
+            format!("Box<{ty}>")
+        } else {
+            ty
+        }
+    }
+
+    /// Whether the struct for `class` carries the `'a` of borrowed parts,
+    /// its own or those of a struct it holds
+    fn borrows(&self, class: &Class) -> bool {
+        self.borrowing.contains(class.name.as_str())
+    }
+
+    /// Whether `from` is `to` or holds it inline, directly or through
+    /// other inline fields – i.e. would have infinite size
+    fn reaches(&self, from: &str, to: &str) -> bool {
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut pending = vec![from];
+        while let Some(class) = pending.pop() {
+            if class == to {
+                return true;
+            }
+            if seen.insert(class) {
+                let inline = self.held.get(class).into_iter().flatten().filter(|h| h.by_value);
+                pending.extend(inline.map(|h| h.class));
+            }
+        }
+        false
+    }
+
+    /// Path of a class relative to module `path`
+    fn path_to(&self, fq: &str, path: &[&str]) -> String {
+        let (ns, name) = split_namespace(fq);
+        let target: Vec<&str> = ns.split("::").filter(|s| !s.is_empty()).collect();
+        if target == path {
+            return name.to_owned();
+        }
+        let mut out = "super::".repeat(path.len());
+        for segment in target {
+            out += &ident(&snake_case(segment));
+            out += "::";
+        }
+        out + name
+    }
+}
+
+/// Classes each struct's fields refer to, and whether inline
+fn held<'d>(diagram: &'d Diagram, options: &RustOptions) -> HashMap<&'d str, Vec<Held<'d>>> {
+    fn walk<'d>(diagram: &'d Diagram, types: &TypeMap, expr: &TypeExpr, path: &[&str], by_value: bool, out: &mut Vec<Held<'d>>) {
+        let by_value = by_value && expr.arrays == 0;
+        if let Some(class) = super::lookup(diagram, expr.name, path)
+            && expr.args.is_empty()
+        {
+            out.push(Held { class: &class.name, by_value });
+        }
+        let inline = by_value && types.get(expr.name).unwrap_or(expr.name) == "Option";
+        for arg in &expr.args {
+            walk(diagram, types, arg, path, inline, out);
+        }
+    }
+
+    let mut held = HashMap::new();
+    for class in diagram.classes() {
+        if !matches!(kind(diagram, class), Kind::Abstract | Kind::Class) {
+            continue;
+        }
+        let path = namespace_path(&class.name);
+        let mut out: Vec<Held> = parents(diagram, class).0.into_iter().map(|c| Held { class: c, by_value: true }).collect();
+        for member in &class.members {
+            if let Member::Attribute(a) = member
+                && !a.is_static
+                && let Some(expr) = a.data_type.as_deref().and_then(TypeExpr::parse)
+            {
+                walk(diagram, &options.types, &expr, &path, true, &mut out);
+            }
+        }
+        out.extend(parts(diagram, class).into_iter().map(|p| Held {
+            class: p.class,
+            by_value: !p.shared && p.multiplicity != Multiplicity::Many,
+        }));
+        held.insert(class.name.as_str(), out);
+    }
+    held
+}
+
+/// Structs that need a lifetime: those with borrowed parts, and every
+/// struct holding one of them
+fn borrowing<'d>(diagram: &'d Diagram, options: &RustOptions, held: &HashMap<&'d str, Vec<Held<'d>>>) -> HashSet<&'d str> {
+    let mut borrowing: HashSet<&str> = HashSet::new();
+    if options.aggregation != Aggregation::Borrow {
+        return borrowing;
+    }
+    for class in diagram.classes() {
+        if held.contains_key(class.name.as_str()) && parts(diagram, class).iter().any(|p| p.shared) {
+            borrowing.insert(&class.name);
+        }
+    }
+    loop {
+        let before = borrowing.len();
+        for (class, refs) in held {
+            if refs.iter().any(|h| borrowing.contains(h.class)) {
+                borrowing.insert(class);
+            }
+        }
+        if borrowing.len() == before {
+            return borrowing;
+        }
+    }
+}
+
+fn methods(class: &Class) -> impl Iterator<Item = &Method> {
+    class.members.iter().filter_map(|m| match m {
+        Member::Method(m) => Some(m),
+        Member::Attribute(_) => None,
+    })
+}
+
+fn visibility(visibility: Visibility) -> &'static str {
+    match visibility {
+        Visibility::Public => "pub ",
+        Visibility::Package | Visibility::Protected => "pub(crate) ",
+        Visibility::Private | Visibility::Unspecified => "",
+    }
+}
+
+/// `RED`/`dark_red` ➜ `Red`/`DarkRed`
+fn pascal_case(name: &str) -> String {
+    if !name.contains('_') && !name.chars().all(|c| !c.is_lowercase()) {
+        let mut chars = name.chars();
+        return chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default();
+    }
+    name.split('_')
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase()).into_iter();
+            first.chain(chars.flat_map(char::to_lowercase)).collect::<String>()
+        })
+        .collect()
+}
+
+/// Escape keywords: raw identifiers where allowed, a trailing `_` otherwise
+fn ident(name: &str) -> String {
+    const RAW: &[&str] = &[
+        "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+        "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+        "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+        "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv", "try",
+        "typeof", "unsized", "virtual", "yield",
+    ];
+    match name {
+        "self" | "Self" | "super" | "crate" | "_" => format!("{name}_"),
+        n if RAW.contains(&n) => format!("r#{n}"),
+        n => n.to_owned(),
+    }
+}
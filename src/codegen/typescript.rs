@@ -0,0 +1,405 @@
+//! TypeScript skeletons.
+//!
+//! ```
+//! use mermaid_parser::types::{Diagram, RelationKind, Visibility};
+//!
+//! let diagram = Diagram::builder()
+//!     .class("Shape", |c| c.annotation("interface").method(Visibility::Public, "area", |m| m.returns("double")))
+//!     .class("Circle", |c| c.attr(Visibility::Private, "radius", "double"))
+//!     .relation("Circle", RelationKind::Extension, "Shape")
+//!     .build()
+//!     .unwrap();
+//! let ts = diagram.to_typescript();
+//! assert!(ts.contains("export interface Shape {\n    area(): number;\n}"));
+//! assert!(ts.contains("export class Circle implements Shape {\n    private radius!: number;\n"));
+//! ```
+//!
+//! | diagram                          | TypeScript                                  |
+//! |----------------------------------|---------------------------------------------|
+//! | class / `<<abstract>>`           | `export class` / `export abstract class`    |
+//! | `<<interface>>`, realized class  | `export interface`                          |
+//! | `<<enumeration>>`                | `export enum`, one member per attribute     |
+//! | `+` / `#` / `-` / `~` or none    | `public` / `protected` / `private` / none   |
+//! | `$` / `*`                        | `static` / `abstract`                       |
+//! | namespace                        | nested `export namespace`                   |
+//! | extends a class                  | `extends` (any further parent: `implements`) |
+//! | extends or realizes an interface | `implements`, members copied in as stubs    |
+//! | composition / aggregation        | field, `T[]` or optional `?` by label       |
+//!
+//! Generic parameters and arguments are kept, and a class takes on those of
+//! generic parents: `class UserRepo<T> implements Repo<T>`.  A concrete
+//! class implements the abstract methods it inherits.  A base class is
+//! declared before its subclasses, and a namespace holding a base before the
+//! class or namespace extending it; namespaces extending each other's
+//! classes both ways cannot be ordered and keep diagram order.  Fields use
+//! `!` so strict mode accepts them without a constructor; method bodies
+//! throw.  A member without a type is `unknown`.  Static members of
+//! interfaces and methods of enumerations have no TypeScript counterpart and
+//! are left out.
+
+use std::fmt::Write;
+
+use super::{camel_case, carried_params, interfaces, kind, lookup, parents, parts, short, unimplemented, Generics, Kind, Module, Multiplicity, TypeExpr, TypeMap};
+use crate::types::{split_namespace, Attribute, Class, Diagram, Member, Method, Visibility};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeScriptOptions {
+    pub types: TypeMap,
+}
+
+impl Default for TypeScriptOptions {
+    fn default() -> Self {
+        TypeScriptOptions { types: default_types() }
+    }
+}
+
+/// Common Mermaid/UML/Java type names and their TypeScript counterparts
+pub fn default_types() -> TypeMap {
+    TypeMap::from_pairs(&[
+        ("int", "number"),
+        ("Integer", "number"),
+        ("long", "number"),
+        ("Long", "number"),
+        ("short", "number"),
+        ("byte", "number"),
+        ("float", "number"),
+        ("Float", "number"),
+        ("double", "number"),
+        ("Double", "number"),
+        ("decimal", "number"),
+        ("String", "string"),
+        ("str", "string"),
+        ("char", "string"),
+        ("bool", "boolean"),
+        ("Boolean", "boolean"),
+        ("Object", "unknown"),
+        ("List", "Array"),
+        ("ArrayList", "Array"),
+        ("Dict", "Map"),
+        ("HashMap", "Map"),
+        ("HashSet", "Set"),
+    ])
+}
+
+impl Diagram {
+    /// TypeScript skeleton with the default options.
+    pub fn to_typescript(&self) -> String {
+        self.to_typescript_with(&TypeScriptOptions::default())
+    }
+
+    pub fn to_typescript_with(&self, options: &TypeScriptOptions) -> String {
+        let mut out = String::from("// Generated from a Mermaid class diagram.\n");
+        let emitter = Emitter { diagram: self, options };
+        emitter.module(&mut out, &Module::tree(self), &[]);
+        out
+    }
+}
+
+struct Emitter<'d> {
+    diagram: &'d Diagram,
+    options: &'d TypeScriptOptions,
+}
+
+/// What a namespace body declares, in emission order
+enum Item<'m, 'd> {
+    Class(&'d Class),
+    Namespace(&'d str, &'m Module<'d>),
+}
+
+const GENERICS: Generics = Generics {
+    open: "<",
+    close: ">",
+    array: |element| format!("{element}[]"),
+};
+
+const STUB: &str = "throw new Error(\"not implemented\");";
+
+impl<'d> Emitter<'d> {
+    fn module(&self, out: &mut String, module: &Module<'d>, path: &[&'d str]) {
+        let indent = "    ".repeat(path.len());
+        for (i, item) in self.bases_first(module).into_iter().enumerate() {
+            if i > 0 || path.is_empty() {
+                out.push('\n');
+            }
+            let class = match item {
+                Item::Class(class) => class,
+                Item::Namespace(name, child) => {
+                    let _ = writeln!(out, "{indent}export namespace {name} {{");
+                    let mut inner = path.to_vec();
+                    inner.push(name);
+                    self.module(out, child, &inner);
+                    let _ = writeln!(out, "{indent}}}");
+                    continue;
+                }
+            };
+            let mut item = String::new();
+            match kind(self.diagram, class) {
+                Kind::Interface => self.interface(&mut item, class, path),
+                Kind::Enum => self.enumeration(&mut item, class),
+                Kind::Abstract | Kind::Class => self.class(&mut item, class, path),
+            }
+            for line in item.lines() {
+                if line.is_empty() {
+                    out.push('\n');
+                } else {
+                    let _ = writeln!(out, "{indent}{line}");
+                }
+            }
+        }
+    }
+
+    /// Classes, then nested namespaces, reordered so that whatever declares
+    /// the base class of an item comes before it: `extends` is evaluated at
+    /// runtime, and namespaces are plain objects filled in order
+    fn bases_first<'m>(&self, module: &'m Module<'d>) -> Vec<Item<'m, 'd>> {
+        let mut items: Vec<Item<'m, 'd>> = module.classes.iter().map(|c| Item::Class(c)).collect();
+        items.extend(module.children.iter().map(|(name, child)| Item::Namespace(name, child)));
+        let declared: Vec<Vec<&Class>> = items
+            .iter()
+            .map(|item| match item {
+                Item::Class(class) => vec![*class],
+                Item::Namespace(_, child) => nested(child),
+            })
+            .collect();
+        // positions of the items declaring the base classes of each item
+        let bases: Vec<Vec<usize>> = declared
+            .iter()
+            .enumerate()
+            .map(|(i, classes)| {
+                classes
+                    .iter()
+                    .filter_map(|c| parents(self.diagram, c).0.first().copied())
+                    .filter_map(|base| declared.iter().position(|d| d.iter().any(|c| c.name == base)))
+                    .filter(|&j| j != i)
+                    .collect()
+            })
+            .collect();
+
+        fn visit(i: usize, bases: &[Vec<usize>], seen: &mut [bool], order: &mut Vec<usize>) {
+            if seen[i] {
+                return;
+            }
+            seen[i] = true;   // set first, so cycles end here
+            for &base in &bases[i] {
+                visit(base, bases, seen, order);
+            }
+            order.push(i);
+        }
+        let mut seen = vec![false; items.len()];
+        let mut order = Vec::with_capacity(items.len());
+        for i in 0..items.len() {
+            visit(i, &bases, &mut seen, &mut order);
+        }
+        let mut items: Vec<Option<Item>> = items.into_iter().map(Some).collect();
+        order.into_iter().filter_map(|i| items[i].take()).collect()
+    }
+
+    // ── items ───────────────────────────────────────────────────────────────
+
+    fn interface(&self, out: &mut String, class: &Class, path: &[&str]) {
+        let (extends, implements) = parents(self.diagram, class);
+        let parents: Vec<String> = extends.iter().chain(&implements).map(|p| self.parent(p, path)).collect();
+        let extends = if parents.is_empty() { String::new() } else { format!(" extends {}", parents.join(", ")) };
+        let _ = writeln!(out, "export interface {}{}{extends} {{", short(class), carried_params(self.diagram, class));
+        for member in &class.members {
+            match member {
+                Member::Attribute(a) if !a.is_static => {
+                    let _ = writeln!(out, "    {}: {};", a.name, self.ty(a.data_type.as_deref(), path));
+                }
+                Member::Method(m) if !m.is_static => {
+                    let _ = writeln!(out, "    {};", self.signature(m, path));
+                }
+                _ => {}
+            }
+        }
+        out.push_str("}\n");
+    }
+
+    fn enumeration(&self, out: &mut String, class: &Class) {
+        let _ = writeln!(out, "export enum {} {{", short(class));
+        for member in &class.members {
+            if let Member::Attribute(a) = member {
+                let _ = writeln!(out, "    {},", a.name);
+            }
+        }
+        out.push_str("}\n");
+    }
+
+    fn class(&self, out: &mut String, class: &Class, path: &[&str]) {
+        let (extends, implements) = parents(self.diagram, class);
+        let mut head = String::from("export ");
+        if kind(self.diagram, class) == Kind::Abstract {
+            head += "abstract ";
+        }
+        let _ = write!(head, "class {}{}", short(class), carried_params(self.diagram, class));
+        // a class extends at most one class; the shape of any other is implemented
+        let (base, others) = extends.split_first().map_or((None, &[][..]), |(b, o)| (Some(b), o));
+        if let Some(base) = base {
+            let _ = write!(head, " extends {}", self.parent(base, path));
+        }
+        let implemented: Vec<String> = others.iter().chain(&implements).map(|p| self.parent(p, path)).collect();
+        if !implemented.is_empty() {
+            let _ = write!(head, " implements {}", implemented.join(", "));
+        }
+        let _ = writeln!(out, "{head} {{");
+
+        // members an implemented interface requires but the class lacks
+        let mut required: Vec<&Member> = Vec::new();
+        for iface in interfaces(self.diagram, &implements) {
+            for member in &iface.members {
+                let declared = class.members.iter().chain(required.iter().copied()).any(|m| m.name() == member.name());
+                if !declared && !member.is_static() {
+                    required.push(member);
+                }
+            }
+        }
+
+        let mut fields: Vec<String> = Vec::new();
+        let mut methods: Vec<String> = Vec::new();
+        for member in class.members.iter().chain(required.iter().copied()) {
+            match member {
+                Member::Attribute(a) => fields.push(self.field(a, path)),
+                Member::Method(m) => methods.push(self.method(m, path)),
+            }
+        }
+        // and what abstract base classes leave open
+        if kind(self.diagram, class) == Kind::Class {
+            for m in unimplemented(self.diagram, class) {
+                if !required.iter().any(|r| r.name() == m.name) {
+                    methods.push(self.method(&Method { is_abstract: false, ..m.clone() }, path));
+                }
+            }
+        }
+        for part in parts(self.diagram, class) {
+            let ty = self.path_to(part.class, path);
+            let name = camel_case(&part.field_name());
+            fields.push(match part.multiplicity {
+                Multiplicity::One => format!("{name}!: {ty};"),
+                Multiplicity::Optional => format!("{name}?: {ty};"),
+                Multiplicity::Many => format!("{name}!: {ty}[];"),
+            });
+        }
+
+        for f in &fields {
+            let _ = writeln!(out, "    {f}");
+        }
+        for (i, m) in methods.iter().enumerate() {
+            if i > 0 || !fields.is_empty() {
+                out.push('\n');
+            }
+            for line in m.lines() {
+                let _ = writeln!(out, "    {line}");
+            }
+        }
+        out.push_str("}\n");
+    }
+
+    // ── members ─────────────────────────────────────────────────────────────
+
+    fn field(&self, attribute: &Attribute, path: &[&str]) -> String {
+        let ty = self.ty(attribute.data_type.as_deref(), path);
+        let mut modifiers = modifiers(attribute.visibility);
+        if attribute.is_static {
+            modifiers += "static ";
+            format!("{modifiers}{}: {ty};", attribute.name)
+        } else {
+            format!("{modifiers}{}!: {ty};", attribute.name)
+        }
+    }
+
+    fn method(&self, method: &Method, path: &[&str]) -> String {
+        let mut modifiers = modifiers(method.visibility);
+        if method.is_static {
+            modifiers += "static ";
+        }
+        if method.is_abstract {
+            format!("{modifiers}abstract {};", self.signature(method, path))
+        } else {
+            format!("{modifiers}{} {{\n    {STUB}\n}}", self.signature(method, path))
+        }
+    }
+
+    fn signature(&self, method: &Method, path: &[&str]) -> String {
+        let params: Vec<String> = method
+            .parameters
+            .iter()
+            .map(|p| format!("{}: {}", parameter(&p.name), self.ty(p.data_type.as_deref(), path)))
+            .collect();
+        let ret = match &method.return_type {
+            Some(ty) => self.ty(Some(ty), path),
+            None => "void".into(),
+        };
+        format!("{}({}): {ret}", method.name, params.join(", "))
+    }
+
+    // ── types ───────────────────────────────────────────────────────────────
+
+    fn ty(&self, ty: Option<&str>, path: &[&str]) -> String {
+        let Some(ty) = ty else { return "unknown".into() };
+        match TypeExpr::parse(ty) {
+            Some(expr) => self.render(&expr, path),
+            None => ty.trim().to_owned(),
+        }
+    }
+
+    fn render(&self, expr: &TypeExpr, path: &[&str]) -> String {
+        let mut out = match lookup(self.diagram, expr.name, path) {
+            Some(class) => self.path_to(&class.name, path),
+            None => self.options.types.get(expr.name).unwrap_or(expr.name).to_owned(),
+        };
+        if !expr.args.is_empty() {
+            let args: Vec<String> = expr.args.iter().map(|a| self.render(a, path)).collect();
+            out = format!("{out}{}{}{}", GENERICS.open, args.join(", "), GENERICS.close);
+        }
+        for _ in 0..expr.arrays {
+            out = (GENERICS.array)(out);
+        }
+        out
+    }
+
+    /// Dotted path of a class as seen from the namespace at `path`
+    /// A class extended or implemented, with the type parameters carried over
+    fn parent(&self, fq: &str, path: &[&str]) -> String {
+        let params = self.diagram.class(fq).map(|c| carried_params(self.diagram, c)).unwrap_or_default();
+        self.path_to(fq, path) + &params
+    }
+
+    fn path_to(&self, fq: &str, path: &[&str]) -> String {
+        let (ns, name) = split_namespace(fq);
+        let target: Vec<&str> = ns.split("::").filter(|s| !s.is_empty()).collect();
+        if target == path {
+            return name.to_owned();
+        }
+        target.into_iter().chain([name]).collect::<Vec<_>>().join(".")
+    }
+}
+
+fn modifiers(visibility: Visibility) -> String {
+    match visibility {
+        Visibility::Public => "public ",
+        Visibility::Protected => "protected ",
+        Visibility::Private => "private ",
+        Visibility::Package | Visibility::Unspecified => "",
+    }
+    .to_owned()
+}
+
+/// Parameter names may not be reserved words
+fn parameter(name: &str) -> String {
+    const RESERVED: &[&str] = &[
+        "break", "case", "catch", "class", "const", "continue", "debugger", "default", "delete", "do",
+        "else", "enum", "export", "extends", "false", "finally", "for", "function", "if", "import", "in",
+        "instanceof", "new", "null", "return", "super", "switch", "this", "throw", "true", "try",
+        "typeof", "var", "void", "while", "with",
+    ];
+    if RESERVED.contains(&name) { format!("{name}_") } else { name.to_owned() }
+}
+
+/// Classes declared in a namespace and the namespaces inside it
+fn nested<'d>(module: &Module<'d>) -> Vec<&'d Class> {
+    let mut classes = module.classes.clone();
+    for child in module.children.values() {
+        classes.extend(nested(child));
+    }
+    classes
+}
@@ -0,0 +1,376 @@
+//! Lossless concrete syntax tree.
+//!
+//! [`parser::parse`](crate::parser::parse) folds the source into a typed
+//! [`Diagram`](crate::types::Diagram) and forgets everything that carries no
+//! meaning: whitespace, blank lines and `%%` comments.  The tree in this module
+//! keeps *all* of it.  Every byte of the input belongs to exactly one
+//! [`SyntaxToken`], so printing the tree reproduces the file verbatim, and a
+//! [`Rewriter`] can patch individual nodes while leaving the rest untouched.
+
+use std::fmt;
+use std::ops::Range;
+
+use pest::iterators::Pair;
+use thiserror::Error;
+
+use crate::parser::Rule;
+
+/// Byte range into the original source
+pub type Span = Range<usize>;
+
+/// What a leaf token holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Spaces and tabs
+    Whitespace,
+    /// `\n`, `\r\n` or `\r`
+    Newline,
+    /// `%% …` up to (not including) the line break
+    Comment,
+    /// Fixed grammar text such as `classDiagram`, `class`, `:` or `(`
+    Literal,
+    /// The text of a leaf rule (identifier, arrow, visibility …)
+    Text,
+}
+
+impl TokenKind {
+    /// Whitespace, line breaks and comments
+    pub fn is_trivia(self) -> bool {
+        matches!(self, TokenKind::Whitespace | TokenKind::Newline | TokenKind::Comment)
+    }
+}
+
+/// A leaf of the tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxToken {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+/// An inner node, one per non-silent grammar rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxNode {
+    pub rule: Rule,
+    pub span: Span,
+    pub children: Vec<SyntaxElement>,
+}
+
+/// Either a node or a token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxElement {
+    pub fn span(&self) -> &Span {
+        match self {
+            SyntaxElement::Node(n) => &n.span,
+            SyntaxElement::Token(t) => &t.span,
+        }
+    }
+}
+
+/// Source text plus the tree that covers it
+#[derive(Debug, Clone)]
+pub struct SyntaxTree {
+    source: String,
+    root: SyntaxNode,
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Construction
+// ────────────────────────────────────────────────────────────────────────────────
+
+impl SyntaxTree {
+    /// Build the tree from the `diagram` pair produced by the pest parser.
+    pub(crate) fn build(src: &str, diagram: Pair<Rule>) -> Self {
+        let mut root = build_node(src, diagram);
+        // The root always spans the whole file, even leading/trailing trivia.
+        if root.span.start > 0 {
+            let mut head = Vec::new();
+            push_gap(src, 0..root.span.start, &mut head);
+            root.children.splice(0..0, head);
+            root.span.start = 0;
+        }
+        if root.span.end < src.len() {
+            push_gap(src, root.span.end..src.len(), &mut root.children);
+            root.span.end = src.len();
+        }
+        SyntaxTree {
+            source: src.to_owned(),
+            root,
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn root(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    /// Source text covered by a node, token or span
+    pub fn text(&self, span: &Span) -> &str {
+        &self.source[span.clone()]
+    }
+
+    /// Top-level statements (`class`, `member_stmt`, `relation_stmt`) in source order
+    pub fn statements(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.root.child_nodes()
+    }
+
+    /// All comments, in source order
+    pub fn comments(&self) -> impl Iterator<Item = &SyntaxToken> {
+        self.root
+            .tokens()
+            .filter(|t| t.kind == TokenKind::Comment)
+    }
+
+    /// Start collecting edits against this tree.
+    pub fn rewriter(&self) -> Rewriter<'_> {
+        Rewriter {
+            tree: self,
+            edits: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for SyntaxTree {
+    /// Re-emits the file by concatenating every token; identical to the input.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for token in self.root.tokens() {
+            f.write_str(self.text(&token.span))?;
+        }
+        Ok(())
+    }
+}
+
+fn build_node(src: &str, pair: Pair<Rule>) -> SyntaxNode {
+    let rule = pair.as_rule();
+    let span = pair.as_span().start()..pair.as_span().end();
+    let mut children = Vec::new();
+    let mut cursor = span.start;
+
+    for inner in pair.into_inner() {
+        let inner_span = inner.as_span().start()..inner.as_span().end();
+        if inner.as_rule() == Rule::EOI {
+            continue;
+        }
+        push_gap(src, cursor..inner_span.start, &mut children);
+        if inner.as_rule() == Rule::comment {
+            children.push(SyntaxElement::Token(SyntaxToken {
+                kind: TokenKind::Comment,
+                span: inner_span.clone(),
+            }));
+        } else {
+            children.push(SyntaxElement::Node(build_node(src, inner)));
+        }
+        cursor = inner_span.end;
+    }
+
+    if children.is_empty() && !span.is_empty() {
+        // leaf rule – its whole text is one token
+        children.push(SyntaxElement::Token(SyntaxToken {
+            kind: TokenKind::Text,
+            span: span.clone(),
+        }));
+    } else {
+        push_gap(src, cursor..span.end, &mut children);
+    }
+
+    SyntaxNode {
+        rule,
+        span,
+        children,
+    }
+}
+
+/// Split text the grammar matched implicitly into whitespace, newline and literal tokens.
+fn push_gap(src: &str, gap: Span, out: &mut Vec<SyntaxElement>) {
+    let text = &src[gap.clone()];
+    let mut start = 0;
+    while start < text.len() {
+        let rest = &text[start..];
+        let first = rest.chars().next().unwrap();
+        let (kind, len) = match first {
+            ' ' | '\t' => (
+                TokenKind::Whitespace,
+                rest.find(|c| c != ' ' && c != '\t').unwrap_or(rest.len()),
+            ),
+            '\r' if rest.starts_with("\r\n") => (TokenKind::Newline, 2),
+            '\r' | '\n' => (TokenKind::Newline, 1),
+            _ => (
+                TokenKind::Literal,
+                rest.find([' ', '\t', '\r', '\n']).unwrap_or(rest.len()),
+            ),
+        };
+        out.push(SyntaxElement::Token(SyntaxToken {
+            kind,
+            span: gap.start + start..gap.start + start + len,
+        }));
+        start += len;
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Navigation
+// ────────────────────────────────────────────────────────────────────────────────
+
+impl SyntaxNode {
+    /// Direct child nodes, skipping tokens
+    pub fn child_nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.children.iter().filter_map(|c| match c {
+            SyntaxElement::Node(n) => Some(n),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// First direct child node of the given rule
+    pub fn child(&self, rule: Rule) -> Option<&SyntaxNode> {
+        self.child_nodes().find(|n| n.rule == rule)
+    }
+
+    /// This node and every node below it, depth-first in source order
+    pub fn descendants(&self) -> Vec<&SyntaxNode> {
+        let mut out = vec![self];
+        for child in self.child_nodes() {
+            out.extend(child.descendants());
+        }
+        out
+    }
+
+    /// Every token below this node, in source order
+    pub fn tokens(&self) -> impl Iterator<Item = &SyntaxToken> {
+        let mut out = Vec::new();
+        collect_tokens(self, &mut out);
+        out.into_iter()
+    }
+
+    /// Innermost node of the given rule that covers `offset`
+    pub fn find_at(&self, offset: usize, rule: Rule) -> Option<&SyntaxNode> {
+        if !self.span.contains(&offset) {
+            return None;
+        }
+        self.child_nodes()
+            .find_map(|c| c.find_at(offset, rule))
+            .or(Some(self).filter(|n| n.rule == rule))
+    }
+}
+
+fn collect_tokens<'a>(node: &'a SyntaxNode, out: &mut Vec<&'a SyntaxToken>) {
+    for child in &node.children {
+        match child {
+            SyntaxElement::Node(n) => collect_tokens(n, out),
+            SyntaxElement::Token(t) => out.push(t),
+        }
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Editing
+// ────────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum EditError {
+    #[error("edit {0:?} overlaps an earlier edit")]
+    Overlap(Span),
+    #[error("edit {0:?} is outside the source")]
+    OutOfBounds(Span),
+}
+
+/// Replace `range` of the source with `replacement`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Span,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Span, replacement: impl Into<String>) -> Self {
+        TextEdit {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+}
+
+/// Collects non-overlapping edits and applies them to the source in one go.
+///
+/// Bytes outside the edited ranges are copied unchanged, so comments and
+/// layout survive any rewrite.
+#[derive(Debug)]
+pub struct Rewriter<'a> {
+    tree: &'a SyntaxTree,
+    edits: Vec<TextEdit>,
+}
+
+impl Rewriter<'_> {
+    /// Record an arbitrary edit.
+    pub fn push(&mut self, edit: TextEdit) -> Result<(), EditError> {
+        if edit.range.start > edit.range.end || edit.range.end > self.tree.source.len() {
+            return Err(EditError::OutOfBounds(edit.range));
+        }
+        let overlaps = self.edits.iter().any(|e| {
+            // two insertions at the same point are ambiguous as well
+            (e.range.start < edit.range.end && edit.range.start < e.range.end)
+                || (e.range == edit.range && e.range.is_empty())
+        });
+        if overlaps {
+            return Err(EditError::Overlap(edit.range));
+        }
+        self.edits.push(edit);
+        Ok(())
+    }
+
+    pub fn replace(&mut self, node: &SyntaxNode, text: impl Into<String>) -> Result<(), EditError> {
+        self.push(TextEdit::new(node.span.clone(), text))
+    }
+
+    pub fn replace_token(&mut self, token: &SyntaxToken, text: impl Into<String>) -> Result<(), EditError> {
+        self.push(TextEdit::new(token.span.clone(), text))
+    }
+
+    pub fn insert_before(&mut self, node: &SyntaxNode, text: impl Into<String>) -> Result<(), EditError> {
+        self.push(TextEdit::new(node.span.start..node.span.start, text))
+    }
+
+    pub fn insert_after(&mut self, node: &SyntaxNode, text: impl Into<String>) -> Result<(), EditError> {
+        self.push(TextEdit::new(node.span.end..node.span.end, text))
+    }
+
+    /// Remove a node; a statement takes its whole line (indentation and line break) with it.
+    pub fn remove(&mut self, node: &SyntaxNode) -> Result<(), EditError> {
+        let src = &self.tree.source;
+        let (mut start, mut end) = (node.span.start, node.span.end);
+        if self.tree.root.child_nodes().any(|n| n == node) {
+            let line_start = src[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+            if src[line_start..start].trim().is_empty() {
+                start = line_start;
+            }
+            end += src[end..].find('\n').map(|i| i + 1).unwrap_or(src.len() - end);
+        }
+        self.push(TextEdit::new(start..end, ""))
+    }
+
+    /// Edits recorded so far, in insertion order
+    pub fn edits(&self) -> &[TextEdit] {
+        &self.edits
+    }
+
+    /// Apply the edits and return the new source.
+    pub fn finish(mut self) -> String {
+        self.edits.sort_by_key(|e| (e.range.start, e.range.end));
+        let src = &self.tree.source;
+        let mut out = String::with_capacity(src.len());
+        let mut cursor = 0;
+        for edit in &self.edits {
+            out.push_str(&src[cursor..edit.range.start]);
+            out.push_str(&edit.replacement);
+            cursor = edit.range.end;
+        }
+        out.push_str(&src[cursor..]);
+        out
+    }
+}
@@ -0,0 +1,245 @@
+//! Semantic diff between two diagrams.
+//!
+//! ```
+//! let old = mermaid_parser::parser::parse("classDiagram\nOrder *-- Item\n").unwrap();
+//! let new = mermaid_parser::parser::parse("classDiagram\nOrder o-- Item\nOrder : +cancel()\n").unwrap();
+//!
+//! let diff = mermaid_parser::diff::diff(&old, &new);
+//! assert_eq!(
+//!     diff.to_string(),
+//!     "class `Order` added method `+cancel()`\n\
+//!      relation `Order *-- Item` changed to aggregation: `Order o-- Item`\n"
+//! );
+//! ```
+//!
+//! Classes are matched by fully-qualified name.  Members are matched by
+//! name for attributes and by name plus parameter types for methods; a method
+//! whose parameter types changed still pairs up with its old version when its
+//! name is unique in both diagrams.  Relations are matched by their two ends,
+//! in either direction.
+
+use std::fmt;
+
+use crate::types::{Class, Diagram, LineStyle, Member, Relation, RelationKind};
+
+/// One semantic change from the old diagram to the new one
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "change", rename_all = "snake_case"))]
+pub enum Change {
+    NamespaceAdded { namespace: String },
+    NamespaceRemoved { namespace: String },
+    ClassAdded { class: String },
+    ClassRemoved { class: String },
+    GenericChanged { class: String, old: Option<String>, new: Option<String> },
+    AnnotationAdded { class: String, annotation: String },
+    AnnotationRemoved { class: String, annotation: String },
+    MemberAdded { class: String, member: Member },
+    MemberRemoved { class: String, member: Member },
+    MemberChanged { class: String, old: Member, new: Member },
+    RelationAdded { relation: Relation },
+    RelationRemoved { relation: Relation },
+    RelationChanged { old: Relation, new: Relation },
+}
+
+/// Result of [`diff`]; empty when the diagrams are semantically equal
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiagramDiff {
+    pub changes: Vec<Change>,
+}
+
+impl DiagramDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Compare `old` with `new`.
+pub fn diff(old: &Diagram, new: &Diagram) -> DiagramDiff {
+    let mut changes = Vec::new();
+
+    // ── namespaces ───────────────────────────────────────────────────────────
+    for ns in old.namespaces.keys().filter(|ns| !new.namespaces.contains_key(*ns)) {
+        changes.push(Change::NamespaceRemoved { namespace: ns.clone() });
+    }
+    for ns in new.namespaces.keys().filter(|ns| !old.namespaces.contains_key(*ns)) {
+        changes.push(Change::NamespaceAdded { namespace: ns.clone() });
+    }
+
+    // ── classes ──────────────────────────────────────────────────────────────
+    for class in old.classes().filter(|c| new.class(&c.name).is_none()) {
+        changes.push(Change::ClassRemoved { class: class.name.clone() });
+    }
+    for class in new.classes() {
+        match old.class(&class.name) {
+            None => {
+                changes.push(Change::ClassAdded { class: class.name.clone() });
+                diff_class(&Class::new(&class.name), class, &mut changes);
+            }
+            Some(before) => diff_class(before, class, &mut changes),
+        }
+    }
+
+    // ── relations ────────────────────────────────────────────────────────────
+    diff_relations(&old.relations, &new.relations, &mut changes);
+
+    DiagramDiff { changes }
+}
+
+fn diff_class(old: &Class, new: &Class, out: &mut Vec<Change>) {
+    let class = &new.name;
+    if old.generic != new.generic {
+        out.push(Change::GenericChanged {
+            class: class.clone(),
+            old: old.generic.clone(),
+            new: new.generic.clone(),
+        });
+    }
+    for a in old.annotations.iter().filter(|a| !new.annotations.contains(a)) {
+        out.push(Change::AnnotationRemoved { class: class.clone(), annotation: a.clone() });
+    }
+    for a in new.annotations.iter().filter(|a| !old.annotations.contains(a)) {
+        out.push(Change::AnnotationAdded { class: class.clone(), annotation: a.clone() });
+    }
+
+    // pair members: identical first, then same signature, then same method name
+    let mut old_left: Vec<Option<&Member>> = old.members.iter().map(Some).collect();
+    let mut pairs: Vec<(usize, Option<&Member>)> = Vec::new(); // new index ➜ old match
+    for (i, m) in new.members.iter().enumerate() {
+        pairs.push((i, take(&mut old_left, |o| o == m)));
+    }
+    for (i, matched) in pairs.iter_mut() {
+        if matched.is_none() {
+            *matched = take(&mut old_left, |o| o.same_signature(&new.members[*i]));
+        }
+    }
+    for (i, matched) in pairs.iter_mut() {
+        let m = &new.members[*i];
+        if matched.is_none() && matches!(m, Member::Method(_)) {
+            let by_name = |o: &Member| matches!(o, Member::Method(_)) && o.name() == m.name();
+            let unique_new = new.members.iter().filter(|n| by_name(n)).count() == 1;
+            if unique_new && old_left.iter().flatten().filter(|o| by_name(o)).count() == 1 {
+                *matched = take(&mut old_left, by_name);
+            }
+        }
+    }
+
+    for m in old_left.into_iter().flatten() {
+        out.push(Change::MemberRemoved { class: class.clone(), member: m.clone() });
+    }
+    for (i, matched) in pairs {
+        let m = &new.members[i];
+        match matched {
+            None => out.push(Change::MemberAdded { class: class.clone(), member: m.clone() }),
+            Some(o) if o != m => out.push(Change::MemberChanged {
+                class: class.clone(),
+                old: o.clone(),
+                new: m.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+}
+
+fn diff_relations(old: &[Relation], new: &[Relation], out: &mut Vec<Change>) {
+    let mut old_left: Vec<Option<&Relation>> = old.iter().map(Some).collect();
+    let mut pairs: Vec<(&Relation, Option<&Relation>)> =
+        new.iter().map(|r| (r, take(&mut old_left, |o| o == r))).collect();
+    for (r, matched) in pairs.iter_mut() {
+        if matched.is_none() {
+            *matched = take(&mut old_left, |o| o.from == r.from && o.to == r.to)
+                .or_else(|| take(&mut old_left, |o| o.from == r.to && o.to == r.from));
+        }
+    }
+
+    for r in old_left.into_iter().flatten() {
+        out.push(Change::RelationRemoved { relation: r.clone() });
+    }
+    for (r, matched) in pairs {
+        match matched {
+            None => out.push(Change::RelationAdded { relation: r.clone() }),
+            Some(o) if o != r => out.push(Change::RelationChanged { old: o.clone(), new: r.clone() }),
+            Some(_) => {}
+        }
+    }
+}
+
+/// Remove and return the first remaining item matching `pred`
+fn take<'a, T: ?Sized>(items: &mut [Option<&'a T>], pred: impl Fn(&T) -> bool) -> Option<&'a T> {
+    items.iter_mut().find(|i| i.is_some_and(&pred)).and_then(Option::take)
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Human-readable rendering
+// ────────────────────────────────────────────────────────────────────────────────
+
+fn member_kind(m: &Member) -> &'static str {
+    match m {
+        Member::Attribute(_) => "attribute",
+        Member::Method(_) => "method",
+    }
+}
+
+fn relation_kind(r: &Relation) -> &'static str {
+    match (r.kind, r.line) {
+        (RelationKind::Extension, LineStyle::Solid) => "inheritance",
+        (RelationKind::Extension, LineStyle::Dotted) => "realization",
+        (RelationKind::Composition, _) => "composition",
+        (RelationKind::Aggregation, _) => "aggregation",
+        (RelationKind::Dependency, LineStyle::Solid) => "association",
+        (RelationKind::Dependency, LineStyle::Dotted) => "dependency",
+        (RelationKind::Lollipop, _) => "lollipop interface",
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::NamespaceAdded { namespace } => write!(f, "namespace `{namespace}` added"),
+            Change::NamespaceRemoved { namespace } => write!(f, "namespace `{namespace}` removed"),
+            Change::ClassAdded { class } => write!(f, "class `{class}` added"),
+            Change::ClassRemoved { class } => write!(f, "class `{class}` removed"),
+            Change::GenericChanged { class, old, new } => write!(
+                f,
+                "class `{class}` changed generic from `{}` to `{}`",
+                old.as_deref().unwrap_or(""),
+                new.as_deref().unwrap_or("")
+            ),
+            Change::AnnotationAdded { class, annotation } => {
+                write!(f, "class `{class}` added annotation `<<{annotation}>>`")
+            }
+            Change::AnnotationRemoved { class, annotation } => {
+                write!(f, "class `{class}` removed annotation `<<{annotation}>>`")
+            }
+            Change::MemberAdded { class, member } => {
+                write!(f, "class `{class}` added {} `{member}`", member_kind(member))
+            }
+            Change::MemberRemoved { class, member } => {
+                write!(f, "class `{class}` removed {} `{member}`", member_kind(member))
+            }
+            Change::MemberChanged { class, old, new } => {
+                write!(f, "class `{class}` changed {} `{old}` to `{new}`", member_kind(new))
+            }
+            Change::RelationAdded { relation } => write!(f, "relation `{relation}` added"),
+            Change::RelationRemoved { relation } => write!(f, "relation `{relation}` removed"),
+            Change::RelationChanged { old, new } => {
+                if relation_kind(old) != relation_kind(new) {
+                    write!(f, "relation `{old}` changed to {}: `{new}`", relation_kind(new))
+                } else {
+                    write!(f, "relation `{old}` changed to `{new}`")
+                }
+            }
+        }
+    }
+}
+
+/// One change per line
+impl fmt::Display for DiagramDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
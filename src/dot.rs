@@ -0,0 +1,147 @@
+//! Graphviz DOT export.
+//!
+//! ```
+//! let diagram = mermaid_parser::parser::parse("classDiagram\nShape : +area() : f64\nShape <|-- Circle\n").unwrap();
+//! let dot = diagram.to_dot();
+//! assert!(dot.contains(r#""Shape" [label="{Shape||+area() : f64\l}"];"#));
+//! assert!(dot.contains(r#""Circle" -> "Shape" [arrowhead=empty];"#));
+//! ```
+//!
+//! Every class is a `record` node with three compartments – name (with its
+//! annotations and generic), attributes and methods, each member written as
+//! in Mermaid, visibility symbol first.  Namespaces become nested
+//! `cluster_` subgraphs.  Edges point `from` ➜ `to` like [`Relation`]; with
+//! `rankdir=BT` parents end up above their children:
+//!
+//! | relation            | arrowhead  | style  |
+//! |---------------------|------------|--------|
+//! | inheritance         | `empty`    | solid  |
+//! | realization         | `empty`    | dashed |
+//! | composition         | `diamond`  |        |
+//! | aggregation         | `odiamond` |        |
+//! | association         | `vee`      | solid  |
+//! | dependency          | `vee`      | dashed |
+//! | lollipop            | `odot`     |        |
+//!
+//! Multiplicity labels become `taillabel`/`headlabel`; notes are `note`
+//! shaped nodes, tied to their class by a dotted line.
+
+use std::fmt::Write;
+
+use crate::types::{split_namespace, Class, Diagram, LineStyle, Member, Namespace, Relation, RelationKind};
+
+impl Diagram {
+    /// Render as a Graphviz `digraph`.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph classes {\n");
+        out += "  rankdir=BT;\n";
+        out += "  node [shape=record, fontname=\"Helvetica\", fontsize=10];\n";
+        out += "  edge [fontname=\"Helvetica\", fontsize=9];\n";
+
+        let mut cluster = 0;
+        for namespace in self.namespaces.values() {
+            write_namespace(&mut out, namespace, 1, &mut cluster);
+        }
+
+        for relation in &self.relations {
+            let _ = writeln!(
+                out,
+                "  {} -> {} [{}];",
+                quote(&relation.from),
+                quote(&relation.to),
+                edge_attributes(relation).join(", ")
+            );
+        }
+
+        for (i, note) in self.notes.iter().enumerate() {
+            let _ = writeln!(out, "  \"note {i}\" [shape=note, label={}];", quote(&note.text));
+            if let Some(class) = &note.for_class {
+                let _ = writeln!(out, "  \"note {i}\" -> {} [style=dotted, arrowhead=none];", quote(class));
+            }
+        }
+
+        out += "}\n";
+        out
+    }
+}
+
+fn write_namespace(out: &mut String, namespace: &Namespace, depth: usize, cluster: &mut usize) {
+    let mut indent = "  ".repeat(depth);
+    let clustered = !namespace.name.is_empty();
+    if clustered {
+        let _ = writeln!(out, "{indent}subgraph cluster_{cluster} {{");
+        *cluster += 1;
+        let _ = writeln!(out, "{indent}  label={};", quote(&namespace.name));
+        indent += "  ";
+    }
+    for class in namespace.classes.values() {
+        let _ = writeln!(out, "{indent}{} [label=\"{}\"];", quote(&class.name), record(class));
+    }
+    for child in namespace.children.values() {
+        write_namespace(out, child, depth + usize::from(clustered), cluster);
+    }
+    if clustered {
+        let _ = writeln!(out, "{}}}", "  ".repeat(depth));
+    }
+}
+
+/// `{«interface»\nShape\<T\>|attributes|methods}`
+fn record(class: &Class) -> String {
+    let mut title = String::new();
+    for annotation in &class.annotations {
+        let _ = write!(title, "«{}»\\n", escape(annotation));
+    }
+    title += &escape(split_namespace(&class.name).1);
+    if let Some(generic) = &class.generic {
+        let _ = write!(title, "\\<{}\\>", escape(generic));
+    }
+
+    let (mut attributes, mut methods) = (String::new(), String::new());
+    for member in &class.members {
+        let target = match member {
+            Member::Attribute(_) => &mut attributes,
+            Member::Method(_) => &mut methods,
+        };
+        let _ = write!(target, "{}\\l", escape(&member.to_string()));
+    }
+    format!("{{{title}|{attributes}|{methods}}}")
+}
+
+fn edge_attributes(relation: &Relation) -> Vec<String> {
+    let arrowhead = match relation.kind {
+        RelationKind::Extension => "empty",
+        RelationKind::Composition => "diamond",
+        RelationKind::Aggregation => "odiamond",
+        RelationKind::Dependency => "vee",
+        RelationKind::Lollipop => "odot",
+    };
+    let mut attributes = vec![format!("arrowhead={arrowhead}")];
+    if relation.line == LineStyle::Dotted {
+        attributes.push("style=dashed".into());
+    }
+    if let Some(label) = &relation.label_from {
+        attributes.push(format!("taillabel={}", quote(label)));
+    }
+    if let Some(label) = &relation.label_to {
+        attributes.push(format!("headlabel={}", quote(label)));
+    }
+    attributes
+}
+
+/// DOT double-quoted string; backslashes pass through, so a note's `\n`
+/// stays a line break
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\\\""))
+}
+
+/// Text inside a record label, where `{}|<>` delimit fields
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>' | '"' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
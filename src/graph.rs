@@ -0,0 +1,69 @@
+//! [`petgraph`] interop (`graph` feature).
+//!
+//! Classes become nodes and relations become edges pointing `from` ➜ `to`
+//! (see [`Relation`] for the direction convention), so petgraph’s algorithms
+//! – `toposort`, `is_cyclic_directed`, `tarjan_scc`, `dijkstra` … – run
+//! directly on a diagram.
+
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+
+use crate::types::{Class, Diagram, Relation};
+
+/// Directed graph with classes as nodes and relations as typed edges
+pub type ClassGraph = DiGraph<Class, Relation>;
+
+impl Diagram {
+    /// Build a [`ClassGraph`].
+    ///
+    /// Node `i` is class `i` of [`Diagram::classes`], so it lines up with
+    /// [`ClassId::index`](crate::resolve::ClassId::index).  Relation ends are
+    /// looked up with [`Diagram::resolve`]; an end naming no class gets an
+    /// implicit node of its own.
+    pub fn to_graph(&self) -> ClassGraph {
+        let mut graph = ClassGraph::with_capacity(self.classes().count(), self.relations.len());
+        for class in self.classes() {
+            graph.add_node(class.clone());
+        }
+
+        let resolution = self.resolve();
+        let node = |graph: &mut ClassGraph, id: Option<usize>, name: &str| match id {
+            Some(i) => NodeIndex::new(i),
+            None => graph
+                .node_indices()
+                .find(|&n| graph[n].name == name)
+                .unwrap_or_else(|| {
+                    let mut class = Class::new(name);
+                    class.implicit = true;
+                    graph.add_node(class)
+                }),
+        };
+        for (i, relation) in self.relations.iter().enumerate() {
+            let (from, to) = resolution.relation_ends(i);
+            let from = node(&mut graph, from.map(|id| id.index()), &relation.from);
+            let to = node(&mut graph, to.map(|id| id.index()), &relation.to);
+            graph.add_edge(from, to, relation.clone());
+        }
+        graph
+    }
+
+    /// Rebuild a diagram from a [`ClassGraph`].
+    ///
+    /// Each edge’s `from`/`to` are taken from the nodes it connects, so edges
+    /// added or rewired on the graph come back correctly.  Notes are not part
+    /// of the graph and are lost.
+    pub fn from_graph(graph: &ClassGraph) -> Diagram {
+        let mut diagram = Diagram::default();
+        for class in graph.node_weights() {
+            *diagram.class_entry(&class.name) = class.clone();
+        }
+        for edge in graph.edge_references() {
+            diagram.relations.push(Relation {
+                from: graph[edge.source()].name.clone(),
+                to: graph[edge.target()].name.clone(),
+                ..edge.weight().clone()
+            });
+        }
+        diagram
+    }
+}
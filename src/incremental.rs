@@ -0,0 +1,295 @@
+//! Incremental reparsing for editors.
+//!
+//! A [`Document`] keeps the source together with every parsed statement.
+//! Class diagrams are line-oriented, so an edit only re-parses the lines it
+//! touches.  The [`Diagram`] is then patched rather than rebuilt: the
+//! relations and notes of the edited statements are spliced in place, and
+//! only the classes those statements name are rebuilt from the cached
+//! statements that mention them.
+//!
+//! ```
+//! use mermaid_parser::cst::TextEdit;
+//! use mermaid_parser::incremental::Document;
+//!
+//! let mut doc = Document::new("classDiagram\nclass Shape\nShape <|-- Circle\n").unwrap();
+//! let changes = doc.edit(TextEdit::new(19..24, "Figure")).unwrap();
+//!
+//! assert_eq!(changes.classes, ["Shape", "Figure"]);
+//! assert!(doc.diagram().class("Figure").is_some());
+//! assert_eq!(doc.source(), "classDiagram\nclass Figure\nShape <|-- Circle\n");
+//! ```
+//!
+//! Edits on the `classDiagram` header line fall back to a full reparse.
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::cst::{EditError, Span, TextEdit};
+use crate::parser::{self, ParseError, Stmt};
+use crate::types::{split_namespace, Class, Diagram, Member, Namespace, Note, Relation};
+
+#[derive(Debug, Error)]
+pub enum IncrementalError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Edit(#[from] EditError),
+}
+
+/// Owned copy of one parsed statement
+#[derive(Debug, Clone)]
+enum Statement {
+    Class(Class),
+    Member { target: String, member: Member },
+    Relation(Relation),
+    Note(Note),
+}
+
+impl From<Stmt<'_>> for Statement {
+    fn from(stmt: Stmt<'_>) -> Self {
+        match stmt {
+            Stmt::Class(c) => Statement::Class(c.into()),
+            Stmt::Member { target, member } => Statement::Member {
+                target: target.to_owned(),
+                member: member.into(),
+            },
+            Stmt::Relation(r) => Statement::Relation(r.into()),
+            Stmt::Note(n) => Statement::Note(n.into()),
+        }
+    }
+}
+
+impl Statement {
+    /// Classes the statement contributes to; a note only names one
+    fn classes(&self) -> Vec<&str> {
+        match self {
+            Statement::Class(c) => vec![&c.name],
+            Statement::Member { target, .. } => vec![target],
+            Statement::Relation(r) => vec![&r.from, &r.to],
+            Statement::Note(_) => Vec::new(),
+        }
+    }
+
+    fn apply(&self, diagram: &mut Diagram) {
+        match self {
+            Statement::Class(c) => {
+                diagram.declare_class(&c.name).members.extend(c.members.iter().cloned())
+            }
+            Statement::Member { target, member } => {
+                diagram.declare_class(target).members.push(member.clone())
+            }
+            Statement::Relation(r) => diagram.add_relation(r.clone()),
+            Statement::Note(n) => diagram.notes.push(n.clone()),
+        }
+    }
+}
+
+/// Statements touched by one [`Document::edit`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changes {
+    pub removed: Vec<Span>,   // statement spans in the old source
+    pub inserted: Vec<Span>,  // statement spans in the new source
+    pub classes: Vec<String>, // fully-qualified, first mention first
+}
+
+/// A source file kept in sync with its parsed [`Diagram`]
+#[derive(Debug)]
+pub struct Document {
+    src: String,
+    statements: Vec<(Span, Statement)>, // source order
+    diagram: Diagram,
+}
+
+impl Document {
+    pub fn new(src: impl Into<String>) -> Result<Self, ParseError> {
+        let src = src.into();
+        let statements = scan_all(&src)?;
+        let diagram = build(&statements);
+        Ok(Document { src, statements, diagram })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.src
+    }
+
+    pub fn diagram(&self) -> &Diagram {
+        &self.diagram
+    }
+
+    pub fn into_diagram(self) -> Diagram {
+        self.diagram
+    }
+
+    /// Apply `edit` and re-parse the lines it touches.
+    ///
+    /// On error the document is left unchanged; parse errors carry positions
+    /// in the edited source.
+    pub fn edit(&mut self, edit: TextEdit) -> Result<Changes, IncrementalError> {
+        let TextEdit { range, replacement } = edit;
+        if range.start > range.end
+            || range.end > self.src.len()
+            || !self.src.is_char_boundary(range.start)
+            || !self.src.is_char_boundary(range.end)
+        {
+            return Err(EditError::OutOfBounds(range).into());
+        }
+
+        let mut src = self.src.clone();
+        src.replace_range(range.clone(), &replacement);
+
+        // widen to whole lines of the old source
+        let start = self.src[..range.start].rfind('\n').map_or(0, |i| i + 1);
+        let end = self.src[range.end..].find('\n').map_or(self.src.len(), |i| range.end + i + 1);
+        let new_end = end - range.len() + replacement.len();
+
+        let header_end = self.src.find('\n').map_or(self.src.len(), |i| i + 1);
+        let fresh = if start < header_end {
+            None
+        } else {
+            parser::scan_statements(&src[start..new_end]).ok()
+        };
+        let fresh: Vec<(Span, Statement)> = match fresh {
+            Some(stmts) => stmts
+                .into_iter()
+                .map(|(span, stmt)| (span.start + start..span.end + start, stmt.into()))
+                .collect(),
+            // header edited, or the chunk alone does not parse: let the whole
+            // file decide, which also gives the error its real position
+            None => {
+                let all = scan_all(&src)?;
+                let changes = Changes {
+                    removed: self.statements.iter().map(|(s, _)| s.clone()).collect(),
+                    inserted: all.iter().map(|(s, _)| s.clone()).collect(),
+                    classes: touched(self.statements.iter().chain(&all)),
+                };
+                self.diagram = build(&all);
+                self.statements = all;
+                self.src = src;
+                return Ok(changes);
+            }
+        };
+
+        let first = self.statements.partition_point(|(s, _)| s.start < start);
+        let last = self.statements.partition_point(|(s, _)| s.start < end);
+        let shift = |s: &Span| s.start + new_end - end..s.end + new_end - end;
+
+        let removed: Vec<(Span, Statement)> =
+            self.statements.splice(first..last, fresh.iter().cloned()).collect();
+        for (span, _) in &mut self.statements[first + fresh.len()..] {
+            *span = shift(span);
+        }
+
+        let changes = Changes {
+            removed: removed.iter().map(|(s, _)| s.clone()).collect(),
+            inserted: fresh.iter().map(|(s, _)| s.clone()).collect(),
+            classes: touched(removed.iter().chain(&fresh)),
+        };
+        self.patch(first, &removed, fresh.len(), &changes.classes);
+        self.src = src;
+        Ok(changes)
+    }
+
+    /// Bring the diagram in line with `self.statements`, where `removed`
+    /// were replaced by the `inserted` statements from index `first` on;
+    /// `classes` are the classes any of them name.
+    fn patch(&mut self, first: usize, removed: &[(Span, Statement)], inserted: usize, classes: &[String]) {
+        let fresh = &self.statements[first..first + inserted];
+
+        // relations and notes: the statements' own run, in source order
+        let before = &self.statements[..first];
+        let at = before.iter().filter(|(_, s)| matches!(s, Statement::Relation(_))).count();
+        let gone = removed.iter().filter(|(_, s)| matches!(s, Statement::Relation(_))).count();
+        let relations = fresh.iter().filter_map(|(_, s)| match s {
+            Statement::Relation(r) => Some(r.clone()),
+            _ => None,
+        });
+        self.diagram.relations.splice(at..at + gone, relations);
+        let at = before.iter().filter(|(_, s)| matches!(s, Statement::Note(_))).count();
+        let gone = removed.iter().filter(|(_, s)| matches!(s, Statement::Note(_))).count();
+        let notes = fresh.iter().filter_map(|(_, s)| match s {
+            Statement::Note(n) => Some(n.clone()),
+            _ => None,
+        });
+        self.diagram.notes.splice(at..at + gone, notes);
+
+        // touched classes, from the statements that mention them
+        let mut rebuilt: HashMap<&str, Class> = HashMap::new();
+        let mut mentioned: HashMap<&str, usize> = HashMap::new(); // first mention
+        for (i, (_, stmt)) in self.statements.iter().enumerate() {
+            for name in stmt.classes() {
+                mentioned.entry(name).or_insert(i);
+                if classes.iter().any(|c| c == name) {
+                    rebuilt.entry(name).or_insert_with(|| Class { implicit: true, ..Class::new(name) });
+                }
+            }
+            match stmt {
+                Statement::Class(c) => {
+                    if let Some(class) = rebuilt.get_mut(c.name.as_str()) {
+                        class.implicit = false;
+                        class.members.extend(c.members.iter().cloned());
+                    }
+                }
+                Statement::Member { target, member } => {
+                    if let Some(class) = rebuilt.get_mut(target.as_str()) {
+                        class.implicit = false;
+                        class.members.push(member.clone());
+                    }
+                }
+                Statement::Relation(_) | Statement::Note(_) => {}
+            }
+        }
+        for name in classes {
+            match rebuilt.remove(name.as_str()) {
+                Some(class) => *self.diagram.class_entry(name) = class,
+                None => {
+                    let (ns, short) = split_namespace(name);
+                    if let Some(namespace) = self.diagram.namespaces.get_mut(ns) {
+                        namespace.classes.shift_remove(short);
+                        if namespace.classes.is_empty() {
+                            self.diagram.namespaces.shift_remove(ns);
+                        }
+                    }
+                }
+            }
+        }
+
+        // classes, and namespaces by their first class, stay in order of
+        // first mention; new ones were appended, so re-sort if needed
+        let rank = |class: &Class| mentioned.get(class.name.as_str()).copied().unwrap_or(usize::MAX);
+        for namespace in self.diagram.namespaces.values_mut() {
+            if !namespace.classes.values().map(rank).is_sorted() {
+                namespace.classes.sort_by_cached_key(|_, class| rank(class));
+            }
+        }
+        let first_class = |namespace: &Namespace| namespace.classes.values().map(rank).min();
+        if !self.diagram.namespaces.values().map(first_class).is_sorted() {
+            self.diagram.namespaces.sort_by_cached_key(|_, namespace| first_class(namespace));
+        }
+    }
+}
+
+fn scan_all(src: &str) -> Result<Vec<(Span, Statement)>, ParseError> {
+    Ok(parser::scan_diagram(src)?
+        .into_iter()
+        .map(|(span, stmt)| (span, stmt.into()))
+        .collect())
+}
+
+fn build(statements: &[(Span, Statement)]) -> Diagram {
+    let mut diagram = Diagram::default();
+    for (_, stmt) in statements {
+        stmt.apply(&mut diagram);
+    }
+    diagram
+}
+
+fn touched<'a>(statements: impl Iterator<Item = &'a (Span, Statement)>) -> Vec<String> {
+    let mut classes: Vec<String> = Vec::new();
+    for name in statements.flat_map(|(_, stmt)| stmt.classes()) {
+        if !classes.iter().any(|c| c == name) {
+            classes.push(name.to_owned());
+        }
+    }
+    classes
+}
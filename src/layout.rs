@@ -0,0 +1,566 @@
+//! Layered graph layout shared by the renderers.
+//!
+//! A small Sugiyama pipeline: break cycles, assign layers by longest path,
+//! thread long edges through dummy nodes, order each layer by barycenter
+//! sweeps, then place nodes and route every edge orthogonally between the
+//! layers.  All work happens top-to-bottom; other [`Direction`]s transpose
+//! or mirror the result.
+//!
+//! Edges are given as `(from, to)` and `to` is ranked *before* `from` – for
+//! a class diagram that puts the arrow head end (superclass, whole,
+//! dependency) above its source, as Mermaid does.
+
+use std::collections::HashMap;
+
+use crate::types::{Class, Diagram, Note, Relation};
+
+/// Flow of the layers, as in Mermaid’s `direction` statement
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    TopToBottom,   // TB
+    BottomToTop,   // BT
+    LeftToRight,   // LR
+    RightToLeft,   // RL
+}
+
+impl Direction {
+    fn is_horizontal(self) -> bool {
+        matches!(self, Direction::LeftToRight | Direction::RightToLeft)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Axis-aligned box; `x`/`y` is the top-left corner
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutOptions {
+    pub direction: Direction,
+    pub node_gap: f64,   // between neighbours of one layer
+    pub layer_gap: f64,  // between consecutive layers
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        LayoutOptions {
+            direction: Direction::TopToBottom,
+            node_gap: 40.0,
+            layer_gap: 60.0,
+        }
+    }
+}
+
+/// Result of [`layered`]; everything lies within `0..width` × `0..height`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+    pub nodes: Vec<Rect>,          // same order as the input sizes
+    pub edges: Vec<Vec<Point>>,    // polyline per input edge, `from` ➜ `to`
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Lay out nodes of the given `(width, height)` connected by `(from, to)` edges.
+pub fn layered(sizes: &[(f64, f64)], edges: &[(usize, usize)], options: &LayoutOptions) -> Layout {
+    let horizontal = options.direction.is_horizontal();
+    // work top-to-bottom; a horizontal flow swaps the axes of every box
+    let sizes: Vec<(f64, f64)> = sizes
+        .iter()
+        .map(|&(w, h)| if horizontal { (h, w) } else { (w, h) })
+        .collect();
+
+    let mut graph = Graph::new(&sizes, edges);
+    graph.order_layers();
+    graph.place(options);
+    let mut layout = graph.route(edges, options);
+
+    transform(&mut layout, options.direction);
+    layout
+}
+
+/// The boxes and edges a renderer lays out for a diagram.
+///
+/// Nodes are the classes in [`Diagram::classes`] order followed by the
+/// notes; `edges` and `links` run in parallel.  Relations whose ends name no
+/// class, and notes for no class, get no edge.
+pub(crate) struct Scene<'d> {
+    pub classes: Vec<&'d Class>,
+    pub notes: Vec<&'d Note>,               // node `classes.len() + i`
+    pub edges: Vec<(usize, usize)>,
+    pub links: Vec<Option<&'d Relation>>,   // `None` ties a note to its class
+}
+
+impl<'d> Scene<'d> {
+    pub fn new(diagram: &'d Diagram) -> Self {
+        let classes: Vec<&Class> = diagram.classes().collect();
+        let index: HashMap<&str, usize> =
+            classes.iter().enumerate().map(|(i, c)| (c.name.as_str(), i)).collect();
+
+        let mut edges = Vec::new();
+        let mut links = Vec::new();
+        for relation in &diagram.relations {
+            if let (Some(&from), Some(&to)) = (index.get(relation.from.as_str()), index.get(relation.to.as_str())) {
+                edges.push((from, to));
+                links.push(Some(relation));
+            }
+        }
+        let notes: Vec<&Note> = diagram.notes.iter().collect();
+        for (i, note) in notes.iter().enumerate() {
+            if let Some(&class) = note.for_class.as_deref().and_then(|c| index.get(c)) {
+                edges.push((classes.len() + i, class));
+                links.push(None);
+            }
+        }
+        Scene { classes, notes, edges, links }
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Layering
+// ────────────────────────────────────────────────────────────────────────────────
+
+struct Graph {
+    width: Vec<f64>,               // per node, dummies included
+    height: Vec<f64>,
+    rank: Vec<usize>,
+    real: usize,                   // nodes `0..real` are the caller's
+    chains: Vec<Option<Chain>>,    // per input edge; `None` for self-loops
+    layers: Vec<Vec<usize>>,       // node ids in left-to-right order
+    x: Vec<f64>,                   // center of each node
+    layer_top: Vec<f64>,
+    layer_height: Vec<f64>,
+}
+
+/// The nodes one edge passes through, upper end first
+struct Chain {
+    nodes: Vec<usize>,
+    reversed: bool,                // upper end is `from` (edge broke a cycle)
+}
+
+impl Graph {
+    fn new(sizes: &[(f64, f64)], edges: &[(usize, usize)]) -> Self {
+        let n = sizes.len();
+        let reversed = break_cycles(n, edges);
+
+        // longest path from the sources, in topological order
+        let mut rank = vec![0; n];
+        let mut indegree = vec![0; n];
+        let mut down: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (i, &(from, to)) in edges.iter().enumerate() {
+            if from == to {
+                continue;
+            }
+            let (upper, lower) = if reversed[i] { (from, to) } else { (to, from) };
+            down[upper].push(lower);
+            indegree[lower] += 1;
+        }
+        let mut queue: Vec<usize> = (0..n).filter(|&v| indegree[v] == 0).collect();
+        let mut head = 0;
+        while head < queue.len() {
+            let v = queue[head];
+            head += 1;
+            for &w in &down[v] {
+                rank[w] = rank[w].max(rank[v] + 1);
+                indegree[w] -= 1;
+                if indegree[w] == 0 {
+                    queue.push(w);
+                }
+            }
+        }
+
+        let mut graph = Graph {
+            width: sizes.iter().map(|s| s.0).collect(),
+            height: sizes.iter().map(|s| s.1).collect(),
+            rank,
+            real: n,
+            chains: Vec::new(),
+            layers: Vec::new(),
+            x: Vec::new(),
+            layer_top: Vec::new(),
+            layer_height: Vec::new(),
+        };
+
+        // thread long edges through zero-sized dummies, one per layer crossed
+        for (i, &(from, to)) in edges.iter().enumerate() {
+            if from == to {
+                graph.chains.push(None);
+                continue;
+            }
+            let (upper, lower) = if reversed[i] { (from, to) } else { (to, from) };
+            let mut nodes = vec![upper];
+            for r in graph.rank[upper] + 1..graph.rank[lower] {
+                graph.width.push(0.0);
+                graph.height.push(0.0);
+                graph.rank.push(r);
+                nodes.push(graph.rank.len() - 1);
+            }
+            nodes.push(lower);
+            graph.chains.push(Some(Chain { nodes, reversed: reversed[i] }));
+        }
+
+        let depth = graph.rank.iter().max().map_or(0, |r| r + 1);
+        graph.layers = vec![Vec::new(); depth];
+        for (v, &r) in graph.rank.iter().enumerate() {
+            graph.layers[r].push(v);
+        }
+        graph
+    }
+
+    /// Pairs `(upper, lower)` of adjacent chain nodes
+    fn segments(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.chains
+            .iter()
+            .flatten()
+            .flat_map(|c| c.nodes.windows(2).map(|w| (w[0], w[1])))
+    }
+
+    // ── crossing reduction ──────────────────────────────────────────────────
+
+    fn order_layers(&mut self) {
+        let segments: Vec<(usize, usize)> = self.segments().collect();
+        let mut best = self.layers.clone();
+        let mut best_crossings = self.crossings(&segments);
+        for sweep in 0..8 {
+            let downward = sweep % 2 == 0;
+            let position = self.positions();
+            let range: Vec<usize> = if downward {
+                (1..self.layers.len()).collect()
+            } else {
+                (0..self.layers.len().saturating_sub(1)).rev().collect()
+            };
+            let mut position = position;
+            for r in range {
+                let mut keyed: Vec<(f64, usize)> = self.layers[r]
+                    .iter()
+                    .map(|&v| {
+                        let neighbours: Vec<f64> = segments
+                            .iter()
+                            .filter_map(|&(u, l)| match downward {
+                                true if l == v => Some(position[u] as f64),
+                                false if u == v => Some(position[l] as f64),
+                                _ => None,
+                            })
+                            .collect();
+                        let key = if neighbours.is_empty() {
+                            position[v] as f64
+                        } else {
+                            neighbours.iter().sum::<f64>() / neighbours.len() as f64
+                        };
+                        (key, v)
+                    })
+                    .collect();
+                keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+                self.layers[r] = keyed.into_iter().map(|(_, v)| v).collect();
+                for (i, &v) in self.layers[r].iter().enumerate() {
+                    position[v] = i;
+                }
+            }
+            let crossings = self.crossings(&segments);
+            if crossings < best_crossings {
+                best_crossings = crossings;
+                best = self.layers.clone();
+            }
+        }
+        self.layers = best;
+    }
+
+    /// Index of every node within its layer
+    fn positions(&self) -> Vec<usize> {
+        let mut position = vec![0; self.rank.len()];
+        for layer in &self.layers {
+            for (i, &v) in layer.iter().enumerate() {
+                position[v] = i;
+            }
+        }
+        position
+    }
+
+    fn crossings(&self, segments: &[(usize, usize)]) -> usize {
+        let position = self.positions();
+        let mut count = 0;
+        for (i, &(u1, l1)) in segments.iter().enumerate() {
+            for &(u2, l2) in &segments[i + 1..] {
+                if self.rank[u1] == self.rank[u2] {
+                    let upper = position[u1].cmp(&position[u2]);
+                    let lower = position[l1].cmp(&position[l2]);
+                    if upper.is_ne() && lower.is_ne() && upper != lower {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    // ── coordinates ─────────────────────────────────────────────────────────
+
+    fn place(&mut self, options: &LayoutOptions) {
+        let gap = options.node_gap;
+        self.x = vec![0.0; self.rank.len()];
+        for layer in &self.layers {
+            let mut left = 0.0;
+            for &v in layer {
+                self.x[v] = left + self.width[v] / 2.0;
+                left += self.width[v] + gap;
+            }
+        }
+
+        // pull nodes towards their neighbours, keeping order and spacing
+        let segments: Vec<(usize, usize)> = self.segments().collect();
+        for pass in 0..8 {
+            let downward = pass % 2 == 0;
+            let range: Vec<usize> = if downward {
+                (1..self.layers.len()).collect()
+            } else {
+                (0..self.layers.len().saturating_sub(1)).rev().collect()
+            };
+            for r in range {
+                let desired: Vec<f64> = self.layers[r]
+                    .iter()
+                    .map(|&v| {
+                        let neighbours: Vec<f64> = segments
+                            .iter()
+                            .filter_map(|&(u, l)| match downward {
+                                true if l == v => Some(self.x[u]),
+                                false if u == v => Some(self.x[l]),
+                                _ => None,
+                            })
+                            .collect();
+                        if neighbours.is_empty() {
+                            self.x[v]
+                        } else {
+                            neighbours.iter().sum::<f64>() / neighbours.len() as f64
+                        }
+                    })
+                    .collect();
+                self.spread(r, &desired, gap);
+            }
+        }
+
+        let left = (0..self.rank.len())
+            .map(|v| self.x[v] - self.width[v] / 2.0)
+            .fold(f64::INFINITY, f64::min);
+        if left.is_finite() {
+            for x in &mut self.x {
+                *x -= left;
+            }
+        }
+
+        let mut top = 0.0;
+        for layer in &self.layers {
+            let height = layer.iter().map(|&v| self.height[v]).fold(0.0, f64::max);
+            self.layer_top.push(top);
+            self.layer_height.push(height);
+            top += height + options.layer_gap;
+        }
+    }
+
+    /// Move layer `r` as close to `desired` as the spacing allows: push right
+    /// from the left, then pull back from the right, and average the two.
+    fn spread(&mut self, r: usize, desired: &[f64], gap: f64) {
+        let layer = &self.layers[r];
+        let min_distance = |i: usize| (self.width[layer[i - 1]] + self.width[layer[i]]) / 2.0 + gap;
+
+        let mut pushed = desired.to_vec();
+        for i in 1..layer.len() {
+            pushed[i] = pushed[i].max(pushed[i - 1] + min_distance(i));
+        }
+        let mut pulled = desired.to_vec();
+        for i in (1..layer.len()).rev() {
+            pulled[i - 1] = pulled[i - 1].min(pulled[i] - min_distance(i));
+        }
+        let mut x: Vec<f64> = pushed.iter().zip(&pulled).map(|(a, b)| (a + b) / 2.0).collect();
+        for i in 1..layer.len() {
+            x[i] = x[i].max(x[i - 1] + min_distance(i));
+        }
+        for (i, &v) in layer.iter().enumerate() {
+            self.x[v] = x[i];
+        }
+    }
+
+    // ── edges ───────────────────────────────────────────────────────────────
+
+    fn route(&self, edges: &[(usize, usize)], options: &LayoutOptions) -> Layout {
+        let node = |v: usize| Rect {
+            x: self.x[v] - self.width[v] / 2.0,
+            y: self.layer_top[self.rank[v]],
+            width: self.width[v],
+            height: self.height[v],
+        };
+
+        // spread the edges leaving one side of a node over its width, ordered
+        // by where they are heading so they do not cross at the node
+        let mut bottom: Vec<Vec<(f64, usize)>> = vec![Vec::new(); self.real];
+        let mut top: Vec<Vec<(f64, usize)>> = vec![Vec::new(); self.real];
+        for (i, chain) in self.chains.iter().enumerate() {
+            if let Some(chain) = chain {
+                let n = chain.nodes.len();
+                bottom[chain.nodes[0]].push((self.x[chain.nodes[1]], i));
+                top[chain.nodes[n - 1]].push((self.x[chain.nodes[n - 2]], i));
+            }
+        }
+        let port = |ports: &mut Vec<(f64, usize)>, v: usize| -> Vec<(usize, f64)> {
+            ports.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            let r = node(v);
+            let m = ports.len() as f64;
+            ports
+                .iter()
+                .enumerate()
+                .map(|(k, &(_, e))| (e, r.x + r.width * (k as f64 + 1.0) / (m + 1.0)))
+                .collect()
+        };
+        let mut start_x = vec![0.0; edges.len()];
+        let mut end_x = vec![0.0; edges.len()];
+        for v in 0..self.real {
+            for (e, x) in port(&mut bottom[v], v) {
+                start_x[e] = x;
+            }
+            for (e, x) in port(&mut top[v], v) {
+                end_x[e] = x;
+            }
+        }
+
+        let mut routed = Vec::with_capacity(edges.len());
+        for (i, chain) in self.chains.iter().enumerate() {
+            let Some(chain) = chain else {
+                routed.push(self_loop(node(edges[i].0), options.node_gap));
+                continue;
+            };
+            let n = chain.nodes.len();
+            let upper = node(chain.nodes[0]);
+            let mut points = vec![Point { x: start_x[i], y: upper.y + upper.height }];
+            let mut x = start_x[i];
+            for k in 1..n {
+                let r = self.rank[chain.nodes[k]];
+                let channel = self.layer_top[r] - options.layer_gap / 2.0;
+                let next_x = if k == n - 1 { end_x[i] } else { self.x[chain.nodes[k]] };
+                points.push(Point { x, y: channel });
+                points.push(Point { x: next_x, y: channel });
+                x = next_x;
+            }
+            let lower = node(chain.nodes[n - 1]);
+            points.push(Point { x, y: lower.y });
+            simplify(&mut points);
+            // chains run upper ➜ lower; callers want `from` ➜ `to`
+            if !chain.reversed {
+                points.reverse();
+            }
+            routed.push(points);
+        }
+
+        let nodes: Vec<Rect> = (0..self.real).map(node).collect();
+        let mut width: f64 = nodes.iter().map(|r| r.x + r.width).fold(0.0, f64::max);
+        let height = nodes.iter().map(|r| r.y + r.height).fold(0.0, f64::max);
+        for p in routed.iter().flatten() {
+            width = width.max(p.x);
+        }
+        Layout { nodes, edges: routed, width, height }
+    }
+}
+
+/// Edges closing a cycle, found by depth-first search over `to ➜ from`
+fn break_cycles(n: usize, edges: &[(usize, usize)]) -> Vec<bool> {
+    let mut out: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+    for (i, &(from, to)) in edges.iter().enumerate() {
+        if from != to {
+            out[to].push((from, i));
+        }
+    }
+    // 0 = unvisited, 1 = on the stack, 2 = done
+    let mut state = vec![0u8; n];
+    let mut reversed = vec![false; edges.len()];
+    for root in 0..n {
+        if state[root] != 0 {
+            continue;
+        }
+        let mut stack = vec![(root, 0)];
+        state[root] = 1;
+        while let Some(&mut (v, ref mut next)) = stack.last_mut() {
+            if let Some(&(w, e)) = out[v].get(*next) {
+                *next += 1;
+                match state[w] {
+                    0 => {
+                        state[w] = 1;
+                        stack.push((w, 0));
+                    }
+                    1 => reversed[e] = true,
+                    _ => {}
+                }
+            } else {
+                state[v] = 2;
+                stack.pop();
+            }
+        }
+    }
+    reversed
+}
+
+/// A loop out of the right side of `node` and back in
+fn self_loop(node: Rect, gap: f64) -> Vec<Point> {
+    let right = node.x + node.width;
+    let (y1, y2) = (node.y + node.height / 3.0, node.y + node.height * 2.0 / 3.0);
+    let out = right + gap / 2.0;
+    vec![
+        Point { x: right, y: y1 },
+        Point { x: out, y: y1 },
+        Point { x: out, y: y2 },
+        Point { x: right, y: y2 },
+    ]
+}
+
+/// Drop repeated points and the middle of straight runs
+fn simplify(points: &mut Vec<Point>) {
+    points.dedup_by(|b, a| (a.x - b.x).abs() < 1e-9 && (a.y - b.y).abs() < 1e-9);
+    let mut i = 1;
+    while i + 1 < points.len() {
+        let (a, b, c) = (points[i - 1], points[i], points[i + 1]);
+        let straight = ((a.x - b.x).abs() < 1e-9 && (b.x - c.x).abs() < 1e-9)
+            || ((a.y - b.y).abs() < 1e-9 && (b.y - c.y).abs() < 1e-9);
+        if straight {
+            points.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Map top-to-bottom coordinates onto `direction`
+fn transform(layout: &mut Layout, direction: Direction) {
+    let (width, height) = (layout.width, layout.height);
+    let map = |p: Point| match direction {
+        Direction::TopToBottom => p,
+        Direction::BottomToTop => Point { x: p.x, y: height - p.y },
+        Direction::LeftToRight => Point { x: p.y, y: p.x },
+        Direction::RightToLeft => Point { x: height - p.y, y: p.x },
+    };
+    for rect in &mut layout.nodes {
+        let corner = map(Point { x: rect.x, y: rect.y });
+        let far = map(Point { x: rect.x + rect.width, y: rect.y + rect.height });
+        *rect = Rect {
+            x: corner.x.min(far.x),
+            y: corner.y.min(far.y),
+            width: (far.x - corner.x).abs(),
+            height: (far.y - corner.y).abs(),
+        };
+    }
+    for edge in &mut layout.edges {
+        for p in edge.iter_mut() {
+            *p = map(*p);
+        }
+    }
+    if direction.is_horizontal() {
+        layout.width = height;
+        layout.height = width;
+    }
+}
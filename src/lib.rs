@@ -1,2 +1,24 @@
 pub mod types;
-pub mod parser;
\ No newline at end of file
+pub mod parser;
+pub mod borrowed;
+pub mod cst;
+pub mod builder;
+pub mod query;
+pub mod resolve;
+pub mod diff;
+pub mod merge;
+pub mod visit;
+pub mod refactor;
+pub mod incremental;
+pub mod plantuml;
+pub mod dot;
+pub mod layout;
+pub mod svg;
+pub mod text;
+pub mod codegen;
+#[cfg(feature = "graph")]
+pub mod graph;
+#[cfg(feature = "json-schema")]
+pub mod schema;
+#[cfg(feature = "rust-source")]
+pub mod rust_source;
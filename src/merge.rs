@@ -0,0 +1,184 @@
+//! Merge several diagrams into one.
+//!
+//! Namespaces and classes are unioned by fully-qualified name, member lists of
+//! the same class are concatenated without duplicates, and identical relations
+//! and notes are kept once.  A *conflict* arises when two diagrams disagree
+//! about the same thing – an attribute of the same name, or a method with the
+//! same signature, declared differently, or a class with different generics.
+//! Every conflict is reported; [`MergeStrategy`] decides which side wins.
+
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::types::{Class, Diagram, Member, Namespace};
+
+/// What to do when two diagrams disagree
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Keep what was seen first, report the conflict
+    #[default]
+    KeepFirst,
+    /// Let later diagrams override earlier ones, report the conflict
+    KeepLast,
+    /// Abort with [`MergeError::Conflict`]
+    Fail,
+}
+
+/// The two sides of a disagreement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictKind {
+    Member { existing: Member, incoming: Member },
+    Generic { existing: Option<String>, incoming: Option<String> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub class: String,   // fully-qualified
+    pub source: usize,   // index of the diagram that introduced the incoming side
+    pub kind: ConflictKind,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ConflictKind::Member { existing, incoming } => write!(
+                f,
+                "class `{}`: `{existing}` conflicts with `{incoming}` from diagram #{}",
+                self.class, self.source
+            ),
+            ConflictKind::Generic { existing, incoming } => write!(
+                f,
+                "class `{}`: generic `{}` conflicts with `{}` from diagram #{}",
+                self.class,
+                existing.as_deref().unwrap_or(""),
+                incoming.as_deref().unwrap_or(""),
+                self.source
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum MergeError {
+    #[error("merge conflict: {0}")]
+    Conflict(Box<Conflict>),
+}
+
+/// Merged diagram plus every conflict that was resolved along the way
+#[derive(Debug, Default)]
+pub struct MergeOutcome {
+    pub diagram: Diagram,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Merge `diagrams` in order.
+pub fn merge<'a>(
+    diagrams: impl IntoIterator<Item = &'a Diagram>,
+    strategy: MergeStrategy,
+) -> Result<MergeOutcome, MergeError> {
+    let mut out = MergeOutcome::default();
+    for (source, diagram) in diagrams.into_iter().enumerate() {
+        for ns in diagram.namespaces.keys() {
+            out.diagram.namespaces.entry(ns.clone()).or_insert_with(|| Namespace {
+                name: ns.clone(),
+                ..Default::default()
+            });
+        }
+        for class in diagram.classes() {
+            let is_new = out.diagram.class(&class.name).is_none();
+            let target = out.diagram.class_entry(&class.name);
+            if is_new {
+                *target = class.clone();
+                continue;
+            }
+            merge_class(target, class, source, strategy, &mut out.conflicts)?;
+        }
+        for relation in &diagram.relations {
+            if !out.diagram.relations.contains(relation) {
+                out.diagram.relations.push(relation.clone());
+            }
+        }
+        for note in &diagram.notes {
+            if !out.diagram.notes.contains(note) {
+                out.diagram.notes.push(note.clone());
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn merge_class(
+    target: &mut Class,
+    incoming: &Class,
+    source: usize,
+    strategy: MergeStrategy,
+    conflicts: &mut Vec<Conflict>,
+) -> Result<(), MergeError> {
+    // an explicit declaration anywhere wins over an implicit one
+    target.implicit &= incoming.implicit;
+
+    if incoming.generic.is_some() && target.generic != incoming.generic {
+        if target.generic.is_none() {
+            target.generic = incoming.generic.clone();
+        } else {
+            let conflict = Conflict {
+                class: target.name.clone(),
+                source,
+                kind: ConflictKind::Generic {
+                    existing: target.generic.clone(),
+                    incoming: incoming.generic.clone(),
+                },
+            };
+            if resolve(conflict, strategy, conflicts)? {
+                target.generic = incoming.generic.clone();
+            }
+        }
+    }
+
+    for annotation in &incoming.annotations {
+        if !target.annotations.contains(annotation) {
+            target.annotations.push(annotation.clone());
+        }
+    }
+
+    for member in &incoming.members {
+        match target.members.iter().position(|m| m.same_signature(member)) {
+            None => target.members.push(member.clone()),
+            Some(i) if target.members[i] == *member => {}
+            Some(i) => {
+                let conflict = Conflict {
+                    class: target.name.clone(),
+                    source,
+                    kind: ConflictKind::Member {
+                        existing: target.members[i].clone(),
+                        incoming: member.clone(),
+                    },
+                };
+                if resolve(conflict, strategy, conflicts)? {
+                    target.members[i] = member.clone();
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Record the conflict; `true` if the incoming side should win
+fn resolve(
+    conflict: Conflict,
+    strategy: MergeStrategy,
+    conflicts: &mut Vec<Conflict>,
+) -> Result<bool, MergeError> {
+    match strategy {
+        MergeStrategy::Fail => Err(MergeError::Conflict(Box::new(conflict))),
+        MergeStrategy::KeepFirst => {
+            conflicts.push(conflict);
+            Ok(false)
+        }
+        MergeStrategy::KeepLast => {
+            conflicts.push(conflict);
+            Ok(true)
+        }
+    }
+}
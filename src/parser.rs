@@ -3,10 +3,9 @@ use pest::Parser;
 use pest_derive::Parser;
 use thiserror::Error;
 
-use crate::types::{
-    Attribute, Class, Diagram, LineStyle, Member, Method, Namespace, Parameter, Relation,
-    RelationKind, Visibility, DEFAULT_NAMESPACE,
-};
+use crate::borrowed::{Attribute, Class, Diagram, Member, Method, Note, Parameter, Relation};
+use crate::cst::{Span, SyntaxTree};
+use crate::types::{self, LineStyle, RelationKind, Visibility};
 
 #[derive(Parser)]
 #[grammar = "grammar/mermaid.pest"]
@@ -15,52 +14,108 @@ struct MermaidParser;
 #[derive(Debug, Error)]
 pub enum ParseError {
     #[error("pest: {0}")]
-    Pest(#[from] pest::error::Error<Rule>),
+    Pest(Box<pest::error::Error<Rule>>),
     #[error("{0}")]
     Custom(String),
 }
 
+impl From<pest::error::Error<Rule>> for ParseError {
+    fn from(e: pest::error::Error<Rule>) -> Self {
+        ParseError::Pest(Box::new(e))
+    }
+}
+
 /// Minimal typed AST node per top‑level statement
-enum Stmt {
-    Class(Class),
-    Member { target: String, member: Member },
-    Relation(Relation),
+pub(crate) enum Stmt<'a> {
+    Class(Class<'a>),
+    Member { target: &'a str, member: Member<'a> },
+    Relation(Relation<'a>),
+    Note(Note<'a>),
 }
 
 // ────────────────────────────────────────────────────────────────────────────────
 // Public entry point                                                             
 // ────────────────────────────────────────────────────────────────────────────────
 
-pub fn parse(src: &str) -> Result<Diagram, ParseError> {
+pub fn parse(src: &str) -> Result<types::Diagram, ParseError> {
+    Ok(parse_borrowed(src)?.into_owned())
+}
+
+/// Like [`parse`], but every string in the result borrows from `src`.
+pub fn parse_borrowed(src: &str) -> Result<Diagram<'_>, ParseError> {
+    build_diagram(parse_pair(src)?)
+}
+
+/// Like [`parse`], but also returns the lossless [`SyntaxTree`] of the source
+/// (comments, blank lines and spacing included).
+pub fn parse_lossless(src: &str) -> Result<(types::Diagram, SyntaxTree), ParseError> {
+    let diagram_pair = parse_pair(src)?;
+    let tree = SyntaxTree::build(src, diagram_pair.clone());
+    Ok((build_diagram(diagram_pair)?.into_owned(), tree))
+}
+
+/// Whether `text` is exactly one `rule` token, e.g. a name the grammar accepts
+pub(crate) fn is_token(rule: Rule, text: &str) -> bool {
+    MermaidParser::parse(rule, text)
+        .is_ok_and(|mut pairs| pairs.next().is_some_and(|p| p.as_str().len() == text.len()))
+}
+
+fn parse_pair(src: &str) -> Result<Pair<'_, Rule>, ParseError> {
     // 1) let Pest build a rich tree (inc. all tokens)
     let mut outer = MermaidParser::parse(Rule::diagram, src)?;
-    let diagram_pair = outer
+    outer
         .next()
-        .ok_or_else(|| ParseError::Custom("diagram pair missing".into()))?;
+        .ok_or_else(|| ParseError::Custom("diagram pair missing".into()))
+}
 
+fn build_diagram(diagram_pair: Pair<'_, Rule>) -> Result<Diagram<'_>, ParseError> {
     // 2) fold every top‑level pair into a Stmt enum – zero manual slicing
-    let mut stmts = Vec::<Stmt>::new();
+    let mut stmts = Vec::new();
     for pair in diagram_pair.into_inner() {
         collect_stmt(pair, &mut stmts)?;
     }
 
     // 3) second pass – build the final Diagram
     let mut diagram = Diagram::default();
-    for stmt in stmts {
+    for (_, stmt) in stmts {
         apply_stmt(stmt, &mut diagram);
     }
     Ok(diagram)
 }
 
+/// Every statement of a whole diagram, with its span
+pub(crate) fn scan_diagram(src: &str) -> Result<Vec<(Span, Stmt<'_>)>, ParseError> {
+    let mut stmts = Vec::new();
+    for pair in parse_pair(src)?.into_inner() {
+        collect_stmt(pair, &mut stmts)?;
+    }
+    Ok(stmts)
+}
+
+/// Every statement of `chunk` – whole lines of a diagram body, without the
+/// `classDiagram` header – with spans relative to `chunk`
+pub(crate) fn scan_statements(chunk: &str) -> Result<Vec<(Span, Stmt<'_>)>, ParseError> {
+    let mut stmts = Vec::new();
+    for pair in MermaidParser::parse(Rule::statements, chunk)? {
+        collect_stmt(pair, &mut stmts)?;
+    }
+    Ok(stmts)
+}
+
 // ────────────────────────────────────────────────────────────────────────────────
 // First pass: build lightweight statement enums                                  
 // ────────────────────────────────────────────────────────────────────────────────
 
-fn collect_stmt(pair: Pair<Rule>, out: &mut Vec<Stmt>) -> Result<(), ParseError> {
+fn collect_stmt<'a>(
+    pair: Pair<'a, Rule>,
+    out: &mut Vec<(Span, Stmt<'a>)>,
+) -> Result<(), ParseError> {
+    let span = pair.as_span().start()..pair.as_span().end();
     match pair.as_rule() {
-        Rule::class => out.push(Stmt::Class(scan_class(pair)?)),
-        Rule::member_stmt => out.push(scan_member_stmt(pair)?),
-        Rule::relation_stmt => out.push(Stmt::Relation(scan_relation(pair)?)),
+        Rule::class => out.push((span, Stmt::Class(scan_class(pair)?))),
+        Rule::member_stmt => out.push((span, scan_member_stmt(pair)?)),
+        Rule::relation_stmt => out.push((span, Stmt::Relation(scan_relation(pair)?))),
+        Rule::note => out.push((span, Stmt::Note(scan_note(pair)))),
         _ => {
             for inner in pair.into_inner() {
                 collect_stmt(inner, out)?;
@@ -74,13 +129,13 @@ fn collect_stmt(pair: Pair<Rule>, out: &mut Vec<Stmt>) -> Result<(), ParseError>
 // Class                                                                          
 // ────────────────────────────────────────────────────────────────────────────────
 
-fn scan_class(pair: Pair<Rule>) -> Result<Class, ParseError> {
-    let mut id: Option<String> = None;
+fn scan_class(pair: Pair<'_, Rule>) -> Result<Class<'_>, ParseError> {
+    let mut id: Option<&str> = None;
     let mut members = Vec::<Member>::new();
 
     for inner in pair.into_inner() {
         match inner.as_rule() {
-            Rule::class_identifier => id = Some(inner.as_str().to_owned()),
+            Rule::class_identifier => id = Some(inner.as_str()),
             Rule::member_stmt => {
                 if let Stmt::Member { member, .. } = scan_member_stmt(inner)? {
                     members.push(member)
@@ -91,14 +146,10 @@ fn scan_class(pair: Pair<Rule>) -> Result<Class, ParseError> {
     }
 
     let fq_name = id.ok_or_else(|| ParseError::Custom("class id missing".into()))?;
-    let (ns, _) = split_namespace(&fq_name);
 
     Ok(Class {
-        name: fq_name.clone(),
-        generic: None,
-        annotations: Vec::new(),
         members,
-        namespace: ns.to_owned(),
+        ..Class::new(fq_name)
     })
 }
 
@@ -106,15 +157,14 @@ fn scan_class(pair: Pair<Rule>) -> Result<Class, ParseError> {
 // Member statement                                                               
 // ────────────────────────────────────────────────────────────────────────────────
 
-fn scan_member_stmt(pair: Pair<Rule>) -> Result<Stmt, ParseError> {
+fn scan_member_stmt(pair: Pair<'_, Rule>) -> Result<Stmt<'_>, ParseError> {
     // grammar: class_identifier ':' member_decl
     let mut inner = pair.into_inner();
     let target = inner
         .next()
         .ok_or_else(|| ParseError::Custom("member: target missing".into()))?
         .as_str()
-        .trim()
-        .to_owned();
+        .trim();
     let member_decl = inner
         .next()
         .ok_or_else(|| ParseError::Custom("member: decl missing".into()))?;
@@ -124,27 +174,19 @@ fn scan_member_stmt(pair: Pair<Rule>) -> Result<Stmt, ParseError> {
     Ok(Stmt::Member { target, member })
 }
 
-fn build_member(decl: Pair<Rule>) -> Result<Member, ParseError> {
-    let mut is_static = false;
-    let mut is_abstract = false;
+fn build_member(decl: Pair<'_, Rule>) -> Result<Member<'_>, ParseError> {
     let mut core: Option<Member> = None;
 
+    // `classifier` is silent: its `static`/`abstract` pairs sit inside the cores
     for part in decl.into_inner() {
         match part.as_rule() {
-            // classifier
-            Rule::classifier => match part.as_str() {
-                "$" => is_static = true,
-                "*" => is_abstract = true,
-                _ => {}
-            },
-
             // attribute vs method
             Rule::class_property_decl => {
-                let attribute = parse_attribute(part, is_static)?;
+                let attribute = parse_attribute(part)?;
                 core = Some(Member::Attribute(attribute));
             }
             Rule::class_method_decl => {
-                let method = parse_method(part, is_static, is_abstract)?;
+                let method = parse_method(part)?;
                 core = Some(Member::Method(method));
             }
             _ => {}
@@ -157,18 +199,17 @@ fn build_member(decl: Pair<Rule>) -> Result<Member, ParseError> {
 // Attribute                                                                     
 // -----------------------------------------------------------------------------
 
-fn parse_attribute(
-    attr: Pair<Rule>,
-    is_static: bool,
-) -> Result<Attribute, ParseError> {
+fn parse_attribute(attr: Pair<'_, Rule>) -> Result<Attribute<'_>, ParseError> {
     let mut visibility = Visibility::Unspecified;
-    let mut name: Option<String> = None;
-    let mut ty: Option<String> = None;
+    let mut is_static = false;
+    let mut name: Option<&str> = None;
+    let mut ty: Option<&str> = None;
     for p in attr.into_inner() {
         match p.as_rule() {
             Rule::visibility => visibility = Visibility::from(p.as_str().chars().next().unwrap()),
-            Rule::variable_identifier => name = Some(p.as_str().to_owned()),
-            Rule::class_identifier => ty = Some(p.as_str().to_owned()),
+            Rule::r#static => is_static = true,
+            Rule::variable_identifier => name = Some(p.as_str()),
+            Rule::class_identifier => ty = Some(p.as_str()),
             _ => {}
         }
     }
@@ -184,22 +225,22 @@ fn parse_attribute(
 // Method                                                                        
 // -----------------------------------------------------------------------------
 
-fn parse_method(
-    meth: Pair<Rule>,
-    is_static: bool,
-    is_abstract: bool,
-) -> Result<Method, ParseError> {
+fn parse_method(meth: Pair<'_, Rule>) -> Result<Method<'_>, ParseError> {
     let mut visibility = Visibility::Unspecified;
-    let mut name: Option<String> = None;
+    let mut is_static = false;
+    let mut is_abstract = false;
+    let mut name: Option<&str> = None;
     let mut params: Vec<Parameter> = Vec::new();
-    let mut return_type: Option<String> = None;
+    let mut return_type: Option<&str> = None;
 
     for p in meth.into_inner() {
         match p.as_rule() {
             Rule::visibility => visibility = Visibility::from(p.as_str().chars().next().unwrap()),
-            Rule::method_identifier => name = Some(p.as_str().to_owned()),
+            Rule::r#static => is_static = true,
+            Rule::r#abstract => is_abstract = true,
+            Rule::method_identifier => name = Some(p.as_str()),
             Rule::method_parameter => params = parse_parameters(p)?,
-            Rule::class_identifier => return_type = Some(p.as_str().to_owned()),
+            Rule::class_identifier => return_type = Some(p.as_str()),
             _ => {}
         }
     }
@@ -214,9 +255,10 @@ fn parse_method(
     })
 }
 
-fn parse_parameters(list: Pair<Rule>) -> Result<Vec<Parameter>, ParseError> {
+fn parse_parameters(list: Pair<'_, Rule>) -> Result<Vec<Parameter<'_>>, ParseError> {
     let mut v = Vec::<Parameter>::new();
-    for p in list.into_inner() { // parameter_list → many parameter
+    // method_parameter → parameter_list? → many parameter
+    for p in list.into_inner().flat_map(|l| l.into_inner()) {
         if p.as_rule() == Rule::parameter {
             v.push(parse_parameter(p)?);
         }
@@ -224,13 +266,13 @@ fn parse_parameters(list: Pair<Rule>) -> Result<Vec<Parameter>, ParseError> {
     Ok(v)
 }
 
-fn parse_parameter(p: Pair<Rule>) -> Result<Parameter, ParseError> {
-    let mut ty: Option<String> = None;
-    let mut name: Option<String> = None;
+fn parse_parameter(p: Pair<'_, Rule>) -> Result<Parameter<'_>, ParseError> {
+    let mut ty: Option<&str> = None;
+    let mut name: Option<&str> = None;
     for part in p.into_inner() {
         match part.as_rule() {
-            Rule::class_identifier => ty = Some(part.as_str().to_owned()),
-            Rule::variable_identifier => name = Some(part.as_str().to_owned()),
+            Rule::class_identifier => ty = Some(part.as_str()),
+            Rule::variable_identifier => name = Some(part.as_str()),
             _ => {}
         }
     }
@@ -244,14 +286,13 @@ fn parse_parameter(p: Pair<Rule>) -> Result<Parameter, ParseError> {
 // Relation statement                                                             
 // ────────────────────────────────────────────────────────────────────────────────
 
-fn scan_relation(pair: Pair<Rule>) -> Result<Relation, ParseError> {
+fn scan_relation(pair: Pair<'_, Rule>) -> Result<Relation<'_>, ParseError> {
     let mut inner = pair.into_inner();
     let from = inner
         .next()
         .ok_or_else(|| ParseError::Custom("relation: from missing".into()))?
         .as_str()
-        .trim()
-        .to_owned();
+        .trim();
     let arrow = inner
         .next()
         .ok_or_else(|| ParseError::Custom("relation: arrow missing".into()))?;
@@ -259,19 +300,22 @@ fn scan_relation(pair: Pair<Rule>) -> Result<Relation, ParseError> {
         .next()
         .ok_or_else(|| ParseError::Custom("relation: to missing".into()))?
         .as_str()
-        .trim()
-        .to_owned();
+        .trim();
 
     let (kind, line) = match arrow.as_rule() {
         Rule::aggregation => (RelationKind::Aggregation, LineStyle::Solid),
         Rule::composition => (RelationKind::Composition, LineStyle::Solid),
         Rule::inheritance => (RelationKind::Extension, LineStyle::Solid),
         Rule::dependency => (RelationKind::Dependency, LineStyle::Dotted),
-        Rule::realization => (RelationKind::Dependency, LineStyle::Dotted),
+        Rule::realization => (RelationKind::Extension, LineStyle::Dotted),
         Rule::link | Rule::association => (RelationKind::Dependency, LineStyle::Solid),
         _ => (RelationKind::Dependency, LineStyle::Solid),
     };
 
+    // `to` is always the end with the arrow head: `A <|-- B` ≡ `B --|> A`
+    let head_on_left = arrow.as_str().starts_with(['<', 'o', '*']);
+    let (from, to) = if head_on_left { (to, from) } else { (from, to) };
+
     Ok(Relation {
         from,
         to,
@@ -283,53 +327,34 @@ fn scan_relation(pair: Pair<Rule>) -> Result<Relation, ParseError> {
 }
 
 // ────────────────────────────────────────────────────────────────────────────────
-// Second pass: apply                                                             
+// Note statement                                                                 
 // ────────────────────────────────────────────────────────────────────────────────
 
-fn apply_stmt(stmt: Stmt, diagram: &mut Diagram) {
-    match stmt {
-        Stmt::Class(c) => {
-            let (ns, name) = split_namespace(&c.name);
-            diagram
-                .namespaces
-                .entry(ns.to_owned())
-                .or_insert_with(|| Namespace {
-                    name: ns.to_owned(),
-                    ..Default::default()
-                })
-                .classes
-                .insert(name.to_owned(), c);
-        }
-        Stmt::Member { target, member } => {
-            let (ns, name) = split_namespace(&target);
-            let class = diagram
-                .namespaces
-                .entry(ns.to_owned())
-                .or_insert_with(|| Namespace {
-                    name: ns.to_owned(),
-                    ..Default::default()
-                })
-                .classes
-                .entry(name.to_owned())
-                .or_insert_with(|| Class {
-                    name: target.clone(),
-                    generic: None,
-                    annotations: Vec::new(),
-                    members: Vec::new(),
-                    namespace: ns.to_owned(),
-                });
-            class.members.push(member);
+fn scan_note(pair: Pair<'_, Rule>) -> Note<'_> {
+    let mut note = Note {
+        text: "",
+        for_class: None,
+    };
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::class_identifier => note.for_class = Some(inner.as_str()),
+            Rule::quoted_text => note.text = inner.as_str().trim_matches('"'),
+            _ => {}
         }
-        Stmt::Relation(r) => diagram.relations.push(r),
     }
+    note
 }
 
 // ────────────────────────────────────────────────────────────────────────────────
-// Helpers                                                                        
+// Second pass: apply                                                             
 // ────────────────────────────────────────────────────────────────────────────────
 
-fn split_namespace(fq: &str) -> (&str, &str) {
-    fq.rfind("::")
-        .map(|idx| (&fq[..idx], &fq[idx + 2..]))
-        .unwrap_or((DEFAULT_NAMESPACE, fq))
+fn apply_stmt<'a>(stmt: Stmt<'a>, diagram: &mut Diagram<'a>) {
+    match stmt {
+        // a later `class X` must not drop members declared earlier
+        Stmt::Class(c) => diagram.declare_class(c.name).members.extend(c.members),
+        Stmt::Member { target, member } => diagram.declare_class(target).members.push(member),
+        Stmt::Relation(r) => diagram.add_relation(r),
+        Stmt::Note(n) => diagram.notes.push(n),
+    }
 }
@@ -0,0 +1,608 @@
+//! PlantUML class diagrams: export with [`Diagram::to_plantuml`], import with
+//! [`parse`].
+//!
+//! ```
+//! let diagram = mermaid_parser::parser::parse(
+//!     "classDiagram\nclass Shape\nShape : +*area() : f64\nShape <|-- Circle\n",
+//! )
+//! .unwrap();
+//! let puml = diagram.to_plantuml();
+//! assert_eq!(
+//!     puml,
+//!     "@startuml\nclass Shape {\n  {abstract} +area() : f64\n}\nShape <|-- Circle\n@enduml\n"
+//! );
+//!
+//! let imported = mermaid_parser::plantuml::parse(&puml).unwrap();
+//! assert!(imported.diagnostics.is_empty());
+//! assert!(mermaid_parser::diff::diff(&diagram, &imported.diagram).is_empty());
+//! ```
+//!
+//! Mapping:
+//! * classes of a namespace go into a `package` block; names are joined with
+//!   `set separator ::`, so relations keep their fully-qualified names
+//! * `<<interface>>`, `<<abstract>>` and `<<enumeration>>` select the
+//!   PlantUML element keyword, every other annotation stays a stereotype
+//! * `$` and `*` become `{static}` and `{abstract}`
+//! * arrows are written head first, like Mermaid: `<|--`, `<|..`, `*--`,
+//!   `o--`, `<--`, `<..`, `()--`
+//! * implicit classes are left for PlantUML to create from the relations
+//!
+//! The importer reads the same mapping back.  Anything it cannot express in
+//! a [`Diagram`] – layout directives, skin parameters, relation labels, other
+//! element kinds – is skipped or approximated and reported as a
+//! [`Diagnostic`].
+
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use indexmap::{IndexMap, IndexSet};
+use pest::iterators::{Pair, Pairs};
+use pest::Parser;
+use pest_derive::Parser;
+use thiserror::Error;
+
+use crate::cst::Span;
+use crate::types::{
+    split_namespace, Attribute, Class, Diagram, LineStyle, Member, Method, Note, Parameter,
+    Relation, RelationKind, Visibility, DEFAULT_NAMESPACE,
+};
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Export
+// ────────────────────────────────────────────────────────────────────────────────
+
+impl Diagram {
+    /// Render as a PlantUML `@startuml … @enduml` document.
+    pub fn to_plantuml(&self) -> String {
+        let mut packages: IndexMap<&str, Vec<&Class>> = IndexMap::new();
+        for class in self.classes().filter(|c| !c.implicit) {
+            packages.entry(&class.namespace).or_default().push(class);
+        }
+
+        let mut out = String::from("@startuml\n");
+        if self.classes().any(|c| c.namespace != DEFAULT_NAMESPACE) {
+            out += "set separator ::\n";
+        }
+        if let Some(classes) = packages.shift_remove(DEFAULT_NAMESPACE) {
+            for class in classes {
+                write_class(&mut out, class, "");
+            }
+        }
+        for (namespace, classes) in packages {
+            let _ = writeln!(out, "package {namespace} {{");
+            for class in classes {
+                write_class(&mut out, class, "  ");
+            }
+            out += "}\n";
+        }
+
+        for relation in &self.relations {
+            // Mermaid's head-first notation is valid PlantUML as is
+            let _ = writeln!(out, "{relation}");
+        }
+
+        let mut floating = 0;
+        for note in &self.notes {
+            let _ = match &note.for_class {
+                Some(class) => writeln!(out, "note top of {class} : {}", note.text),
+                None => {
+                    floating += 1;
+                    writeln!(out, "note \"{}\" as N{floating}", note.text)
+                }
+            };
+        }
+
+        out += "@enduml\n";
+        out
+    }
+}
+
+/// Element keyword and the annotations left over as stereotypes
+fn keyword(class: &Class) -> (&'static str, Vec<&str>) {
+    let mut keyword = "class";
+    let mut stereotypes = Vec::new();
+    for annotation in &class.annotations {
+        match annotation.to_ascii_lowercase().as_str() {
+            "interface" if keyword == "class" => keyword = "interface",
+            "abstract" if keyword == "class" => keyword = "abstract class",
+            "enumeration" | "enum" if keyword == "class" => keyword = "enum",
+            _ => stereotypes.push(annotation.as_str()),
+        }
+    }
+    (keyword, stereotypes)
+}
+
+fn write_class(out: &mut String, class: &Class, indent: &str) {
+    let (keyword, stereotypes) = keyword(class);
+    let name = split_namespace(&class.name).1;
+    let _ = write!(out, "{indent}{keyword} {name}");
+    if let Some(generic) = &class.generic {
+        let _ = write!(out, "<{generic}>");
+    }
+    for stereotype in stereotypes {
+        let _ = write!(out, " <<{stereotype}>>");
+    }
+    if class.members.is_empty() {
+        out.push('\n');
+        return;
+    }
+    out.push_str(" {\n");
+    for member in &class.members {
+        let _ = writeln!(out, "{indent}  {}", member_line(member));
+    }
+    let _ = writeln!(out, "{indent}}}");
+}
+
+/// `{static} +count : int`, `{abstract} #area(scale : int) : f64`
+fn member_line(member: &Member) -> String {
+    let mut line = String::new();
+    if member.is_static() {
+        line += "{static} ";
+    }
+    if let Member::Method(m) = member
+        && m.is_abstract
+    {
+        line += "{abstract} ";
+    }
+    line += member.visibility().symbol();
+    line += member.name();
+    let ty = match member {
+        Member::Attribute(a) => a.data_type.as_deref(),
+        Member::Method(m) => {
+            let params: Vec<String> = m
+                .parameters
+                .iter()
+                .map(|p| match &p.data_type {
+                    Some(ty) => format!("{} : {ty}", p.name),
+                    None => p.name.clone(),
+                })
+                .collect();
+            let _ = write!(line, "({})", params.join(", "));
+            m.return_type.as_deref()
+        }
+    };
+    if let Some(ty) = ty {
+        let _ = write!(line, " : {ty}");
+    }
+    line
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Import
+// ────────────────────────────────────────────────────────────────────────────────
+
+#[derive(Parser)]
+#[grammar = "grammar/plantuml.pest"]
+struct PlantUmlParser;
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("pest: {0}")]
+    Pest(Box<pest::error::Error<Rule>>),
+}
+
+impl From<pest::error::Error<Rule>> for ImportError {
+    fn from(e: pest::error::Error<Rule>) -> Self {
+        ImportError::Pest(Box::new(e))
+    }
+}
+
+/// A construct that was skipped or only partly imported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize, // 1-based
+    pub span: Span,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Result of [`parse`]
+#[derive(Debug, Default)]
+pub struct Import {
+    pub diagram: Diagram,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Parse a PlantUML `@startuml … @enduml` class diagram.
+///
+/// Only malformed structure – a missing `@startuml`, an unclosed `{` – is an
+/// error; unsupported lines end up in [`Import::diagnostics`].
+pub fn parse(src: &str) -> Result<Import, ImportError> {
+    let document = PlantUmlParser::parse(Rule::document, src)?
+        .next()
+        .expect("document rule always yields one pair");
+    let mut scan = Scan {
+        separator: ".".into(),
+        ..Default::default()
+    };
+    scan.elements(document.into_inner(), DEFAULT_NAMESPACE);
+    Ok(scan.finish())
+}
+
+/// First pass: statements in source order, names still unresolved
+enum Stmt {
+    Class(Class),
+    Member { target: String, scope: String, member: Member },
+    Relation { relation: Relation, scope: String },
+    Note { note: Note, scope: String },
+}
+
+#[derive(Default)]
+struct Scan {
+    separator: String,                  // "" for `set separator none`
+    stmts: Vec<Stmt>,
+    notes: HashMap<String, usize>,      // note alias ➜ index into `stmts`
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Scan {
+    fn diagnose(&mut self, pair: &Pair<Rule>, message: String) {
+        self.diagnostics.push(Diagnostic {
+            line: pair.line_col().0,
+            span: pair.as_span().start()..pair.as_span().end(),
+            message,
+        });
+    }
+
+    /// Spell a PlantUML name with `::`
+    fn name(&self, raw: &str) -> String {
+        match self.separator.as_str() {
+            "." => raw.replace('.', "::"),
+            _ => raw.to_owned(),
+        }
+    }
+
+    fn elements(&mut self, pairs: Pairs<Rule>, scope: &str) {
+        for pair in pairs {
+            match pair.as_rule() {
+                Rule::package_block => self.package(pair, scope),
+                Rule::class_block | Rule::class_decl => self.class(pair, scope),
+                Rule::note | Rule::note_block => self.note(pair, scope),
+                Rule::set_separator => self.separator(pair),
+                Rule::relation => self.relation(pair, scope),
+                Rule::member_stmt => self.member_stmt(pair, scope),
+                Rule::unsupported | Rule::unsupported_block => {
+                    let first = pair.as_str().lines().next().unwrap_or("").trim();
+                    self.diagnose(&pair, format!("unsupported `{first}`, ignored"));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn package(&mut self, pair: Pair<Rule>, scope: &str) {
+        let mut inner = pair.into_inner();
+        let name = inner.find(|p| p.as_rule() == Rule::ident).map(|p| self.name(p.as_str()));
+        let path = qualify(scope, &name.unwrap_or_default());
+        self.elements(inner, &path);
+    }
+
+    fn separator(&mut self, pair: Pair<Rule>) {
+        let value = pair.clone().into_inner().last().map_or("", |p| p.as_str());
+        match value {
+            "." | "::" => self.separator = value.to_owned(),
+            "none" => self.separator.clear(),
+            _ => self.diagnose(&pair, format!("separator `{value}` is not supported, ignored")),
+        }
+    }
+
+    fn class(&mut self, pair: Pair<Rule>, scope: &str) {
+        let mut class = Class::new("");
+        let mut relations = Vec::new();
+        for p in pair.into_inner() {
+            match p.as_rule() {
+                Rule::class_kind => {
+                    let kind = p.as_str().split_whitespace().collect::<Vec<_>>().join(" ");
+                    let annotation = match kind.as_str() {
+                        "class" => None,
+                        "interface" => Some("interface".to_owned()),
+                        "abstract" | "abstract class" => Some("abstract".to_owned()),
+                        "enum" => Some("enumeration".to_owned()),
+                        other => {
+                            self.diagnose(
+                                &p,
+                                format!("`{other}` imported as a class annotated `<<{other}>>`"),
+                            );
+                            Some(other.to_owned())
+                        }
+                    };
+                    class.annotations.extend(annotation);
+                }
+                Rule::ident => class = Class {
+                    annotations: class.annotations,
+                    ..Class::new(&qualify(scope, &self.name(p.as_str())))
+                },
+                Rule::generic => class.generic = p.into_inner().next().map(|g| g.as_str().trim().to_owned()),
+                Rule::stereotype => {
+                    let text = p.into_inner().next().map_or("", |t| t.as_str()).trim();
+                    class.annotations.push(text.to_owned());
+                }
+                Rule::extends | Rule::implements => {
+                    let line = match p.as_rule() {
+                        Rule::extends => LineStyle::Solid,
+                        _ => LineStyle::Dotted,
+                    };
+                    for target in p.into_inner().filter(|t| t.as_rule() == Rule::ident) {
+                        relations.push(Relation {
+                            from: class.name.clone(),
+                            to: self.name(target.as_str()),
+                            kind: RelationKind::Extension,
+                            line,
+                            label_from: None,
+                            label_to: None,
+                        });
+                    }
+                }
+                Rule::member => {
+                    let member = self.member(p);
+                    class.members.extend(member);
+                }
+                Rule::body_unsupported => {
+                    let message = format!("unsupported member `{}` in `{}`, ignored", p.as_str().trim(), class.name);
+                    self.diagnose(&p, message);
+                }
+                _ => {}
+            }
+        }
+        self.stmts.push(Stmt::Class(class));
+        for relation in relations {
+            self.stmts.push(Stmt::Relation { relation, scope: scope.to_owned() });
+        }
+    }
+
+    fn member_stmt(&mut self, pair: Pair<Rule>, scope: &str) {
+        let mut target = String::new();
+        for p in pair.into_inner() {
+            match p.as_rule() {
+                Rule::ident => target = self.name(p.as_str()),
+                Rule::member => {
+                    if let Some(member) = self.member(p) {
+                        let scope = scope.to_owned();
+                        self.stmts.push(Stmt::Member { target: target.clone(), scope, member });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn member(&mut self, pair: Pair<Rule>) -> Option<Member> {
+        let mut visibility = Visibility::Unspecified;
+        let (mut is_static, mut is_abstract) = (false, false);
+        for p in pair.into_inner() {
+            match p.as_rule() {
+                Rule::modifier => match p.as_str().trim_matches(['{', '}']) {
+                    "static" | "classifier" => is_static = true,
+                    "abstract" => is_abstract = true,
+                    "field" | "method" => {}
+                    other => self.diagnose(&p, format!("modifier `{{{other}}}` ignored")),
+                },
+                Rule::visibility => visibility = Visibility::from(p.as_str().chars().next().unwrap()),
+                Rule::attribute => {
+                    let (name, data_type) = typed_name(p);
+                    return Some(Member::Attribute(Attribute { visibility, name, data_type, is_static }));
+                }
+                Rule::method => {
+                    let mut method = Method {
+                        visibility,
+                        name: String::new(),
+                        parameters: Vec::new(),
+                        return_type: None,
+                        is_static,
+                        is_abstract,
+                    };
+                    for part in p.into_inner() {
+                        match part.as_rule() {
+                            Rule::member_name => method.name = part.as_str().to_owned(),
+                            Rule::type_ref => method.return_type = Some(part.as_str().to_owned()),
+                            Rule::parameter => {
+                                let (name, data_type) = typed_name(part);
+                                method.parameters.push(Parameter { name, data_type });
+                            }
+                            _ => {}
+                        }
+                    }
+                    return Some(Member::Method(method));
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn relation(&mut self, pair: Pair<Rule>, scope: &str) {
+        let mut ends: Vec<String> = Vec::new();
+        let mut labels: [Option<String>; 2] = [None, None];
+        let (mut head_left, mut head_right, mut dotted) = (None, None, false);
+        let mut past_arrow = false;
+        for p in pair.clone().into_inner() {
+            match p.as_rule() {
+                Rule::ident => ends.push(p.as_str().to_owned()),
+                Rule::cardinality => {
+                    let text = p.into_inner().next().map(|t| t.as_str().to_owned());
+                    labels[usize::from(past_arrow)] = text;
+                }
+                Rule::arrow => {
+                    past_arrow = true;
+                    for part in p.into_inner() {
+                        match part.as_rule() {
+                            Rule::head_left => head_left = Some(part.as_str().to_owned()),
+                            Rule::head_right => head_right = Some(part.as_str().to_owned()),
+                            Rule::arrow_line => dotted = part.as_str().contains('.'),
+                            _ => {}
+                        }
+                    }
+                }
+                Rule::relation_label => {
+                    let message = format!("relation label `{}` dropped", p.as_str().trim());
+                    self.diagnose(&p, message);
+                }
+                _ => {}
+            }
+        }
+        let [left, right] = [ends[0].clone(), ends[1].clone()];
+
+        // `N1 .. Class` attaches a floating note instead of relating classes
+        for (alias, other) in [(&left, &right), (&right, &left)] {
+            if let Some(&i) = self.notes.get(alias) {
+                let target = self.name(other);
+                if let Stmt::Note { note, .. } = &mut self.stmts[i]
+                    && note.for_class.is_none()
+                {
+                    note.for_class = Some(target);
+                } else {
+                    self.diagnose(&pair, format!("note `{alias}` is already attached, link ignored"));
+                }
+                return;
+            }
+        }
+
+        let (head, head_on_left) = match (head_left, head_right) {
+            (Some(l), Some(r)) => {
+                self.diagnose(&pair, format!("two-headed arrow `{l}…{r}` is not supported, ignored"));
+                return;
+            }
+            (Some(h), None) => (Some(h), true),
+            (None, h) => (h, false),
+        };
+        let kind = match head.as_deref() {
+            None | Some("<" | ">") => RelationKind::Dependency,
+            Some("<|" | "|>" | "^") => RelationKind::Extension,
+            Some("*") => RelationKind::Composition,
+            Some("o") => RelationKind::Aggregation,
+            Some("()") => RelationKind::Lollipop,
+            Some(other) => {
+                self.diagnose(&pair, format!("arrow head `{other}` is not supported, ignored"));
+                return;
+            }
+        };
+
+        // `to` is the end with the arrow head, as in `parser::parse`
+        let [label_left, label_right] = labels;
+        let ((from, label_from), (to, label_to)) = if head_on_left {
+            ((right, label_right), (left, label_left))
+        } else {
+            ((left, label_left), (right, label_right))
+        };
+        let relation = Relation {
+            from: self.name(&from),
+            to: self.name(&to),
+            kind,
+            line: if dotted { LineStyle::Dotted } else { LineStyle::Solid },
+            label_from,
+            label_to,
+        };
+        self.stmts.push(Stmt::Relation { relation, scope: scope.to_owned() });
+    }
+
+    fn note(&mut self, pair: Pair<Rule>, scope: &str) {
+        let mut note = Note { text: String::new(), for_class: None };
+        let (mut alias, mut attached) = (None, false);
+        let mut lines = Vec::new();
+        for p in pair.into_inner() {
+            match p.as_rule() {
+                Rule::of_kw => attached = true,
+                Rule::ident if attached => note.for_class = Some(self.name(p.as_str())),
+                Rule::ident => alias = Some(p.as_str().to_owned()),
+                Rule::note_quoted => note.text = p.into_inner().next().map_or("", |t| t.as_str()).to_owned(),
+                Rule::note_text => note.text = p.as_str().trim().to_owned(),
+                Rule::note_line => lines.push(p.as_str().trim()),
+                _ => {}
+            }
+        }
+        if !lines.is_empty() {
+            // Mermaid spells line breaks inside a note as `\n`
+            note.text = lines.join("\\n");
+        }
+        if let Some(alias) = alias {
+            self.notes.insert(alias, self.stmts.len());
+        }
+        self.stmts.push(Stmt::Note { note, scope: scope.to_owned() });
+    }
+
+    /// Second pass: resolve names against every declared class and build the
+    /// diagram exactly like `parser::parse` does
+    fn finish(self) -> Import {
+        let declared: IndexSet<String> = self
+            .stmts
+            .iter()
+            .filter_map(|s| match s {
+                Stmt::Class(c) => Some(c.name.clone()),
+                _ => None,
+            })
+            .collect();
+        let resolve = |name: &str, scope: &str| resolve(name, scope, &declared);
+
+        let mut diagram = Diagram::default();
+        for stmt in self.stmts {
+            match stmt {
+                Stmt::Class(c) => {
+                    let class = diagram.declare_class(&c.name);
+                    if c.generic.is_some() {
+                        class.generic = c.generic;
+                    }
+                    for annotation in c.annotations {
+                        if !class.annotations.contains(&annotation) {
+                            class.annotations.push(annotation);
+                        }
+                    }
+                    class.members.extend(c.members);
+                }
+                Stmt::Member { target, scope, member } => {
+                    diagram.declare_class(&resolve(&target, &scope)).members.push(member)
+                }
+                Stmt::Relation { mut relation, scope } => {
+                    relation.from = resolve(&relation.from, &scope);
+                    relation.to = resolve(&relation.to, &scope);
+                    diagram.add_relation(relation);
+                }
+                Stmt::Note { mut note, scope } => {
+                    note.for_class = note.for_class.map(|c| resolve(&c, &scope));
+                    diagram.notes.push(note);
+                }
+            }
+        }
+        Import { diagram, diagnostics: self.diagnostics }
+    }
+}
+
+/// `name` declared inside the package `scope`
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() || name.contains("::") {
+        name.to_owned()
+    } else {
+        format!("{scope}::{name}")
+    }
+}
+
+/// A reference to `name` from inside `scope`: the class in that package if
+/// there is one, else the only declared class of that name anywhere
+fn resolve(name: &str, scope: &str, declared: &IndexSet<String>) -> String {
+    let local = qualify(scope, name);
+    if name.contains("::") || declared.contains(&local) {
+        return local;
+    }
+    let mut by_name = declared.iter().filter(|fq| split_namespace(fq).1 == name);
+    match (by_name.next(), by_name.next()) {
+        (Some(only), None) => only.clone(),
+        _ => local,
+    }
+}
+
+/// `member_name` and optional `type_ref` of an attribute or parameter
+fn typed_name(pair: Pair<Rule>) -> (String, Option<String>) {
+    let (mut name, mut ty) = (String::new(), None);
+    for p in pair.into_inner() {
+        match p.as_rule() {
+            Rule::member_name => name = p.as_str().to_owned(),
+            Rule::type_ref => ty = Some(p.as_str().to_owned()),
+            _ => {}
+        }
+    }
+    (name, ty)
+}
@@ -0,0 +1,156 @@
+//! Lookups over a parsed [`Diagram`].
+//!
+//! All class arguments are fully-qualified names as stored in
+//! `Class::name` and `Relation::from`/`to`.  Results keep source order and
+//! contain every class at most once.  See [`RelationKind`] for how each
+//! arrow is read.
+
+use std::collections::VecDeque;
+
+use crate::types::{split_namespace, Class, Diagram, LineStyle, Namespace, Relation, RelationKind};
+
+impl Diagram {
+    /// Class by fully-qualified name
+    pub fn class(&self, fq_name: &str) -> Option<&Class> {
+        let (ns, name) = split_namespace(fq_name);
+        self.namespaces.get(ns)?.classes.get(name)
+    }
+
+    /// Mutable class by fully-qualified name
+    pub fn class_mut(&mut self, fq_name: &str) -> Option<&mut Class> {
+        let (ns, name) = split_namespace(fq_name);
+        self.namespaces.get_mut(ns)?.classes.get_mut(name)
+    }
+
+    /// Every class in every namespace (nested ones included)
+    pub fn classes(&self) -> impl Iterator<Item = &Class> {
+        fn walk<'a>(ns: &'a Namespace, out: &mut Vec<&'a Class>) {
+            out.extend(ns.classes.values());
+            for child in ns.children.values() {
+                walk(child, out);
+            }
+        }
+        let mut out = Vec::new();
+        for ns in self.namespaces.values() {
+            walk(ns, &mut out);
+        }
+        out.into_iter()
+    }
+
+    /// Relations whose `from` end is the class
+    pub fn relations_from<'a>(&'a self, fq_name: &'a str) -> impl Iterator<Item = &'a Relation> {
+        self.relations.iter().filter(move |r| r.from == fq_name)
+    }
+
+    /// Relations whose `to` end is the class
+    pub fn relations_to<'a>(&'a self, fq_name: &'a str) -> impl Iterator<Item = &'a Relation> {
+        self.relations.iter().filter(move |r| r.to == fq_name)
+    }
+
+    // ── inheritance ──────────────────────────────────────────────────────────
+
+    /// Direct parents (`Self --|> Parent`)
+    pub fn superclasses(&self, fq_name: &str) -> Vec<&str> {
+        self.targets(fq_name, is_inheritance)
+    }
+
+    /// Parents, grand-parents, … nearest first
+    pub fn ancestors(&self, fq_name: &str) -> Vec<&str> {
+        self.closure(fq_name, |d, c| d.superclasses(c))
+    }
+
+    /// Direct children (`Child --|> Self`)
+    pub fn subclasses(&self, fq_name: &str) -> Vec<&str> {
+        self.sources(fq_name, is_inheritance)
+    }
+
+    /// Children, grand-children, … nearest first
+    pub fn descendants(&self, fq_name: &str) -> Vec<&str> {
+        self.closure(fq_name, |d, c| d.subclasses(c))
+    }
+
+    // ── interfaces ───────────────────────────────────────────────────────────
+
+    /// Interfaces realized (`..|>`) or provided as lollipops
+    pub fn implemented_interfaces(&self, fq_name: &str) -> Vec<&str> {
+        self.targets(fq_name, is_realization)
+    }
+
+    /// Classes realizing or providing the interface
+    pub fn implementors(&self, fq_name: &str) -> Vec<&str> {
+        self.sources(fq_name, is_realization)
+    }
+
+    // ── whole / part ─────────────────────────────────────────────────────────
+
+    /// Parts the class is composed of (`Part --* Self`)
+    pub fn composed_of(&self, fq_name: &str) -> Vec<&str> {
+        self.sources(fq_name, |r| r.kind == RelationKind::Composition)
+    }
+
+    /// Parts the class aggregates (`Part --o Self`)
+    pub fn aggregates(&self, fq_name: &str) -> Vec<&str> {
+        self.sources(fq_name, |r| r.kind == RelationKind::Aggregation)
+    }
+
+    /// Wholes the class is a part of, by composition or aggregation
+    pub fn part_of(&self, fq_name: &str) -> Vec<&str> {
+        self.targets(fq_name, |r| {
+            matches!(r.kind, RelationKind::Composition | RelationKind::Aggregation)
+        })
+    }
+
+    // ── usage ────────────────────────────────────────────────────────────────
+
+    /// Classes this class depends on or is associated with
+    pub fn dependencies(&self, fq_name: &str) -> Vec<&str> {
+        self.targets(fq_name, |r| r.kind == RelationKind::Dependency)
+    }
+
+    /// Classes that depend on or are associated with this class
+    pub fn dependents(&self, fq_name: &str) -> Vec<&str> {
+        self.sources(fq_name, |r| r.kind == RelationKind::Dependency)
+    }
+
+    // ── helpers ──────────────────────────────────────────────────────────────
+
+    fn targets(&self, fq_name: &str, pred: impl Fn(&Relation) -> bool) -> Vec<&str> {
+        dedup(self.relations.iter().filter(|r| r.from == fq_name && pred(r)).map(|r| r.to.as_str()))
+    }
+
+    fn sources(&self, fq_name: &str, pred: impl Fn(&Relation) -> bool) -> Vec<&str> {
+        dedup(self.relations.iter().filter(|r| r.to == fq_name && pred(r)).map(|r| r.from.as_str()))
+    }
+
+    /// Breadth-first transitive closure of `step`, excluding the start class
+    fn closure<'a>(&'a self, fq_name: &str, step: impl Fn(&'a Self, &str) -> Vec<&'a str>) -> Vec<&'a str> {
+        let mut seen = Vec::<&str>::new();
+        let mut queue: VecDeque<&str> = step(self, fq_name).into();
+        while let Some(next) = queue.pop_front() {
+            if next == fq_name || seen.contains(&next) {
+                continue;
+            }
+            seen.push(next);
+            queue.extend(step(self, next));
+        }
+        seen
+    }
+}
+
+fn is_inheritance(r: &Relation) -> bool {
+    r.kind == RelationKind::Extension && r.line == LineStyle::Solid
+}
+
+fn is_realization(r: &Relation) -> bool {
+    (r.kind == RelationKind::Extension && r.line == LineStyle::Dotted) || r.kind == RelationKind::Lollipop
+}
+
+fn dedup<'a>(names: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut out = Vec::new();
+    for name in names {
+        if !out.contains(&name) {
+            out.push(name);
+        }
+    }
+    out
+}
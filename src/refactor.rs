@@ -0,0 +1,386 @@
+//! Reference-preserving refactorings.
+//!
+//! Each [`Refactoring`] can be applied to a [`Diagram`] – every relation end,
+//! note and member type naming the class is updated along with it – or turned
+//! into minimal [`TextEdit`]s against the original source, so comments and
+//! layout survive:
+//!
+//! ```
+//! use mermaid_parser::refactor::{apply_to_source, Refactoring};
+//!
+//! let src = "classDiagram\n%% shapes\nclass Shape\nShape <|-- Circle\nCircle : +Shape parent\n";
+//! let out = apply_to_source(src, &Refactoring::RenameClass {
+//!     class: "Shape".into(),
+//!     new_name: "Figure".into(),
+//! })
+//! .unwrap();
+//! assert_eq!(out, "classDiagram\n%% shapes\nclass Figure\nFigure <|-- Circle\nCircle : +Figure parent\n");
+//! ```
+//!
+//! References are found with [`Diagram::resolve`], so namespace-relative
+//! names are followed and re-spelled relative to their scope when possible.
+
+use thiserror::Error;
+
+use crate::cst::{EditError, SyntaxTree, TextEdit};
+use crate::parser::{is_token, parse_lossless, ParseError, Rule};
+use crate::resolve::RefSite;
+use crate::types::{
+    split_namespace, Class, Diagram, LineStyle, Member, Relation, RelationKind, Visibility,
+    DEFAULT_NAMESPACE,
+};
+
+#[derive(Debug, Error)]
+pub enum RefactorError {
+    #[error("unknown class `{0}`")]
+    UnknownClass(String),
+    #[error("class `{0}` already exists")]
+    ClassExists(String),
+    #[error("class `{class}` has no member `{member}`")]
+    UnknownMember { class: String, member: String },
+    #[error("class `{class}` already has a member `{member}`")]
+    MemberExists { class: String, member: String },
+    #[error("class `{0}` has no public methods to extract")]
+    NothingToExtract(String),
+    #[error("{0} cannot be expressed in Mermaid source")]
+    NotRepresentable(&'static str),
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Edit(#[from] EditError),
+}
+
+/// A single refactoring; class names are fully-qualified
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refactoring {
+    /// Rename a class, keeping its namespace; `new_name` is the bare name
+    RenameClass { class: String, new_name: String },
+    /// Move a class into another namespace (`""` for the default one)
+    MoveClass { class: String, namespace: String },
+    /// Rename every member of the class called `member` (all overloads)
+    RenameMember { class: String, member: String, new_name: String },
+    /// New `<<interface>>` with the class’s public instance methods, realized by the class
+    ExtractInterface { class: String, interface: String },
+}
+
+impl Refactoring {
+    /// Apply to a diagram, keeping all references consistent.
+    pub fn apply(&self, diagram: &mut Diagram) -> Result<(), RefactorError> {
+        match self {
+            Refactoring::RenameClass { class, new_name } => {
+                let (ns, _) = split_namespace(class);
+                relocate(diagram, class, &qualify(ns, new_name))
+            }
+            Refactoring::MoveClass { class, namespace } => {
+                let (_, name) = split_namespace(class);
+                relocate(diagram, class, &qualify(namespace, name))
+            }
+            Refactoring::RenameMember { class, member, new_name } => {
+                let c = diagram
+                    .class_mut(class)
+                    .ok_or_else(|| RefactorError::UnknownClass(class.clone()))?;
+                check_member_rename(c, member, new_name)?;
+                for m in &mut c.members {
+                    match m {
+                        Member::Attribute(a) if a.name == *member => a.name = new_name.clone(),
+                        Member::Method(m) if m.name == *member => m.name = new_name.clone(),
+                        _ => {}
+                    }
+                }
+                Ok(())
+            }
+            Refactoring::ExtractInterface { class, interface } => {
+                let extracted = extract(diagram, class, interface)?;
+                *diagram.declare_class(interface) = extracted;
+                diagram.add_relation(realization(class, interface));
+                Ok(())
+            }
+        }
+    }
+
+    /// Minimal edits turning `tree`’s source into the refactored source.
+    ///
+    /// `tree` must be the syntax tree of the source `diagram` was parsed
+    /// from.  Moving a class is rejected: the grammar has no namespaces, so
+    /// a new class name must be a plain identifier as well.
+    pub fn text_edits(&self, diagram: &Diagram, tree: &SyntaxTree) -> Result<Vec<TextEdit>, RefactorError> {
+        let mut edits = Vec::new();
+        match self {
+            Refactoring::RenameClass { class, new_name } => {
+                if diagram.class(class).is_none() {
+                    return Err(RefactorError::UnknownClass(class.clone()));
+                }
+                check_source_name(new_name)?;
+                if diagram.class(&qualify(split_namespace(class).0, new_name)).is_some() {
+                    return Err(RefactorError::ClassExists(new_name.clone()));
+                }
+                for node in tree.root().descendants() {
+                    if node.rule == Rule::class_identifier && tree.text(&node.span) == class {
+                        edits.push(TextEdit::new(node.span.clone(), new_name.clone()));
+                    }
+                }
+            }
+            Refactoring::MoveClass { .. } => {
+                return Err(RefactorError::NotRepresentable("moving a class between namespaces"));
+            }
+            Refactoring::RenameMember { class, member, new_name } => {
+                let c = diagram
+                    .class(class)
+                    .ok_or_else(|| RefactorError::UnknownClass(class.clone()))?;
+                check_member_rename(c, member, new_name)?;
+                for stmt in tree.statements().filter(|s| s.rule == Rule::member_stmt) {
+                    let target = stmt.child(Rule::class_identifier).map(|t| tree.text(&t.span));
+                    if target != Some(class.as_str()) {
+                        continue;
+                    }
+                    // member_decl ➜ class_method_decl | class_property_decl ➜ name
+                    let decl = stmt.child(Rule::member_decl).and_then(|d| d.child_nodes().next());
+                    let names = decl.into_iter().flat_map(|d| d.child_nodes()).filter(|n| {
+                        matches!(n.rule, Rule::variable_identifier | Rule::method_identifier)
+                    });
+                    for name in names.filter(|n| tree.text(&n.span) == member) {
+                        edits.push(TextEdit::new(name.span.clone(), new_name.clone()));
+                    }
+                }
+            }
+            Refactoring::ExtractInterface { class, interface } => {
+                let extracted = extract(diagram, class, interface)?;
+                check_source_name(interface)?;
+                let src = tree.source();
+                let mut text = String::new();
+                if !src.is_empty() && !src.ends_with('\n') {
+                    text.push('\n');
+                }
+                // `<<interface>>` has no syntax here; the realization arrow carries the meaning
+                text.push_str(&format!("class {interface}\n"));
+                for member in &extracted.members {
+                    text.push_str(&format!("{interface} : {member}\n"));
+                }
+                text.push_str(&format!("{}\n", realization(class, interface)));
+                edits.push(TextEdit::new(src.len()..src.len(), text));
+            }
+        }
+        Ok(edits)
+    }
+}
+
+/// Parse `src`, apply the refactoring as text edits and return the new source.
+pub fn apply_to_source(src: &str, refactoring: &Refactoring) -> Result<String, RefactorError> {
+    let (diagram, tree) = parse_lossless(src)?;
+    let mut rewriter = tree.rewriter();
+    for edit in refactoring.text_edits(&diagram, &tree)? {
+        rewriter.push(edit)?;
+    }
+    Ok(rewriter.finish())
+}
+
+impl Diagram {
+    pub fn rename_class(&mut self, class: &str, new_name: &str) -> Result<(), RefactorError> {
+        Refactoring::RenameClass {
+            class: class.to_owned(),
+            new_name: new_name.to_owned(),
+        }
+        .apply(self)
+    }
+
+    pub fn move_class(&mut self, class: &str, namespace: &str) -> Result<(), RefactorError> {
+        Refactoring::MoveClass {
+            class: class.to_owned(),
+            namespace: namespace.to_owned(),
+        }
+        .apply(self)
+    }
+
+    pub fn rename_member(&mut self, class: &str, member: &str, new_name: &str) -> Result<(), RefactorError> {
+        Refactoring::RenameMember {
+            class: class.to_owned(),
+            member: member.to_owned(),
+            new_name: new_name.to_owned(),
+        }
+        .apply(self)
+    }
+
+    pub fn extract_interface(&mut self, class: &str, interface: &str) -> Result<(), RefactorError> {
+        Refactoring::ExtractInterface {
+            class: class.to_owned(),
+            interface: interface.to_owned(),
+        }
+        .apply(self)
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ────────────────────────────────────────────────────────────────────────────────
+
+fn qualify(namespace: &str, name: &str) -> String {
+    if namespace == DEFAULT_NAMESPACE {
+        name.to_owned()
+    } else {
+        format!("{namespace}::{name}")
+    }
+}
+
+/// Class names written into source must parse back as one identifier
+fn check_source_name(name: &str) -> Result<(), RefactorError> {
+    if is_token(Rule::class_identifier, name) {
+        Ok(())
+    } else {
+        Err(RefactorError::NotRepresentable("a class name that is not a plain identifier"))
+    }
+}
+
+/// Rename/move `old` to `new` and re-point every reference to it.
+fn relocate(diagram: &mut Diagram, old: &str, new: &str) -> Result<(), RefactorError> {
+    if diagram.class(old).is_none() {
+        return Err(RefactorError::UnknownClass(old.to_owned()));
+    }
+    if old == new {
+        return Ok(());
+    }
+    if diagram.class(new).is_some() {
+        return Err(RefactorError::ClassExists(new.to_owned()));
+    }
+    let (new_ns, new_name) = split_namespace(new);
+
+    // 1) rewrite references while the old name still resolves
+    let resolution = diagram.resolve();
+    let id = resolution.id(old);
+    for reference in resolution.references.iter().filter(|r| r.target == id) {
+        match reference.site {
+            RefSite::RelationFrom { relation } => diagram.relations[relation].from = new.to_owned(),
+            RefSite::RelationTo { relation } => diagram.relations[relation].to = new.to_owned(),
+            RefSite::Attribute { class, member }
+            | RefSite::Parameter { class, member, .. }
+            | RefSite::ReturnType { class, member } => {
+                let owner = diagram.class_mut(resolution.name(class)).expect("resolved class exists");
+                let scope = if Some(class) == id { new_ns } else { owner.namespace.as_str() };
+                let spelled = if reference.name == old { new.to_owned() } else { spell(new, scope) };
+                let ty = match (&mut owner.members[member], reference.site) {
+                    (Member::Attribute(a), _) => a.data_type.as_mut(),
+                    (Member::Method(m), RefSite::Parameter { param, .. }) => m.parameters[param].data_type.as_mut(),
+                    (Member::Method(m), _) => m.return_type.as_mut(),
+                };
+                if let Some(ty) = ty {
+                    *ty = replace_ident(ty, &reference.name, &spelled);
+                }
+            }
+        }
+    }
+    for note in &mut diagram.notes {
+        if note.for_class.as_deref() == Some(old) {
+            note.for_class = Some(new.to_owned());
+        }
+    }
+
+    // 2) move the class itself; a rename keeps its position
+    let (old_ns, old_name) = split_namespace(old);
+    let namespace = &mut diagram.namespaces[old_ns];
+    let (index, _, mut class) = namespace.classes.shift_remove_full(old_name).expect("checked above");
+    class.name = new.to_owned();
+    class.namespace = new_ns.to_owned();
+    if old_ns == new_ns {
+        namespace.classes.shift_insert(index, new_name.to_owned(), class);
+    } else {
+        // a namespace exists only while it holds something
+        if namespace.classes.is_empty() && namespace.children.is_empty() {
+            diagram.namespaces.shift_remove(old_ns);
+        }
+        *diagram.class_entry(new) = class;
+    }
+    Ok(())
+}
+
+/// Shortest spelling of `fq` that still resolves from `scope`
+fn spell(fq: &str, scope: &str) -> String {
+    let (ns, name) = split_namespace(fq);
+    let mut s = scope;
+    loop {
+        if s == ns {
+            return if ns == DEFAULT_NAMESPACE {
+                name.to_owned()
+            } else {
+                fq[ns.len() + 2..].to_owned()
+            };
+        }
+        if s == DEFAULT_NAMESPACE {
+            return fq.to_owned();
+        }
+        s = split_namespace(s).0;
+    }
+}
+
+/// Replace whole-identifier occurrences of `from` in a type expression
+fn replace_ident(ty: &str, from: &str, to: &str) -> String {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '-' || c == ':';
+    let mut out = String::with_capacity(ty.len());
+    let mut word_start = None;
+    let flush = |out: &mut String, word: &str| out.push_str(if word == from { to } else { word });
+    for (i, c) in ty.char_indices() {
+        if is_ident(c) {
+            word_start.get_or_insert(i);
+        } else {
+            if let Some(start) = word_start.take() {
+                flush(&mut out, &ty[start..i]);
+            }
+            out.push(c);
+        }
+    }
+    if let Some(start) = word_start {
+        flush(&mut out, &ty[start..]);
+    }
+    out
+}
+
+fn check_member_rename(class: &Class, member: &str, new_name: &str) -> Result<(), RefactorError> {
+    if !class.members.iter().any(|m| m.name() == member) {
+        return Err(RefactorError::UnknownMember {
+            class: class.name.clone(),
+            member: member.to_owned(),
+        });
+    }
+    if member != new_name && class.members.iter().any(|m| m.name() == new_name) {
+        return Err(RefactorError::MemberExists {
+            class: class.name.clone(),
+            member: new_name.to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// The interface class `ExtractInterface` would create
+fn extract(diagram: &Diagram, class: &str, interface: &str) -> Result<Class, RefactorError> {
+    let source = diagram
+        .class(class)
+        .ok_or_else(|| RefactorError::UnknownClass(class.to_owned()))?;
+    if diagram.class(interface).is_some() {
+        return Err(RefactorError::ClassExists(interface.to_owned()));
+    }
+    let mut extracted = Class::new(interface);
+    extracted.annotations.push("interface".into());
+    for member in &source.members {
+        if let Member::Method(m) = member
+            && m.visibility == Visibility::Public
+            && !m.is_static
+        {
+            let mut m = m.clone();
+            m.is_abstract = true;
+            extracted.members.push(Member::Method(m));
+        }
+    }
+    if extracted.members.is_empty() {
+        return Err(RefactorError::NothingToExtract(class.to_owned()));
+    }
+    Ok(extracted)
+}
+
+fn realization(class: &str, interface: &str) -> Relation {
+    Relation {
+        from: class.to_owned(),
+        to: interface.to_owned(),
+        kind: RelationKind::Extension,
+        line: LineStyle::Dotted,
+        label_from: None,
+        label_to: None,
+    }
+}
@@ -0,0 +1,203 @@
+//! Name resolution: link relation ends and member types to declared classes.
+//!
+//! `Relation::from`/`to` and every `data_type` are plain strings.  The pass in
+//! this module looks each of them up and hands out [`ClassId`]s, so tools can
+//! follow edges without re-implementing the lookup rules:
+//!
+//! * a relation end is taken as written, the same way
+//!   [`Diagram::add_relation`] registers it: `shop::Order --> Item` names the
+//!   top-level `Item`, never `shop::Item`;
+//! * a member type is tried in the owning class’s namespace first, then in
+//!   each parent namespace, and finally in [`DEFAULT_NAMESPACE`] – i.e. as
+//!   written;
+//! * type expressions such as `List~Item~` or `Map<K, Order>` contribute one
+//!   reference per identifier they contain.
+
+use std::collections::HashMap;
+
+use crate::types::{split_namespace, Class, Diagram, Member, DEFAULT_NAMESPACE};
+
+/// Stable handle to a class: its position in [`Diagram::classes`] order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ClassId(usize);
+
+impl ClassId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Where a class name occurs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefSite {
+    /// `Diagram::relations[relation].from`
+    RelationFrom { relation: usize },
+    /// `Diagram::relations[relation].to`
+    RelationTo { relation: usize },
+    /// Type of attribute `members[member]`
+    Attribute { class: ClassId, member: usize },
+    /// Type of parameter `param` of method `members[member]`
+    Parameter { class: ClassId, member: usize, param: usize },
+    /// Return type of method `members[member]`
+    ReturnType { class: ClassId, member: usize },
+}
+
+impl RefSite {
+    pub fn is_relation(self) -> bool {
+        matches!(self, RefSite::RelationFrom { .. } | RefSite::RelationTo { .. })
+    }
+}
+
+/// One occurrence of a class name and what it resolved to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub site: RefSite,
+    pub name: String,
+    pub target: Option<ClassId>, // `None` if no declared class matches
+}
+
+/// Result of [`Diagram::resolve`]
+#[derive(Debug, Clone, Default)]
+pub struct Resolution {
+    classes: Vec<String>,
+    ids: HashMap<String, ClassId>,
+    pub references: Vec<Reference>,
+}
+
+impl Resolution {
+    /// Handle of a fully-qualified class name
+    pub fn id(&self, fq_name: &str) -> Option<ClassId> {
+        self.ids.get(fq_name).copied()
+    }
+
+    /// Fully-qualified name behind a handle
+    pub fn name(&self, id: ClassId) -> &str {
+        &self.classes[id.0]
+    }
+
+    /// The class behind a handle, looked up in the diagram that was resolved
+    pub fn class<'d>(&self, diagram: &'d Diagram, id: ClassId) -> Option<&'d Class> {
+        diagram.class(self.name(id))
+    }
+
+    pub fn class_count(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Resolved `(from, to)` of `Diagram::relations[relation]`
+    pub fn relation_ends(&self, relation: usize) -> (Option<ClassId>, Option<ClassId>) {
+        let mut ends = (None, None);
+        for r in &self.references {
+            match r.site {
+                RefSite::RelationFrom { relation: i } if i == relation => ends.0 = r.target,
+                RefSite::RelationTo { relation: i } if i == relation => ends.1 = r.target,
+                _ => {}
+            }
+        }
+        ends
+    }
+
+    /// References from the members of a class
+    pub fn type_refs(&self, class: ClassId) -> impl Iterator<Item = &Reference> {
+        self.references.iter().filter(move |r| match r.site {
+            RefSite::Attribute { class: c, .. }
+            | RefSite::Parameter { class: c, .. }
+            | RefSite::ReturnType { class: c, .. } => c == class,
+            _ => false,
+        })
+    }
+
+    /// Every reference that matched no declared class
+    pub fn unresolved(&self) -> impl Iterator<Item = &Reference> {
+        self.references.iter().filter(|r| r.target.is_none())
+    }
+
+    /// Relation ends that name no declared class; type references to
+    /// undeclared names (`int`, `String` …) are usually expected and excluded
+    pub fn unresolved_relations(&self) -> impl Iterator<Item = &Reference> {
+        self.unresolved().filter(|r| r.site.is_relation())
+    }
+
+    /// Look `name` up as seen from namespace `scope`.
+    pub fn lookup(&self, name: &str, scope: &str) -> Option<ClassId> {
+        let mut scope = scope;
+        loop {
+            if scope == DEFAULT_NAMESPACE {
+                return self.id(name);
+            }
+            if let Some(id) = self.id(&format!("{scope}::{name}")) {
+                return Some(id);
+            }
+            scope = split_namespace(scope).0;
+        }
+    }
+}
+
+impl Diagram {
+    /// Resolve every relation end and member type against the declared classes.
+    pub fn resolve(&self) -> Resolution {
+        let mut res = Resolution::default();
+        for class in self.classes() {
+            let id = ClassId(res.classes.len());
+            res.classes.push(class.name.clone());
+            res.ids.entry(class.name.clone()).or_insert(id);
+        }
+
+        let mut refs = Vec::new();
+        for (i, relation) in self.relations.iter().enumerate() {
+            refs.push(Reference {
+                site: RefSite::RelationFrom { relation: i },
+                name: relation.from.clone(),
+                target: res.id(&relation.from),
+            });
+            refs.push(Reference {
+                site: RefSite::RelationTo { relation: i },
+                name: relation.to.clone(),
+                target: res.id(&relation.to),
+            });
+        }
+
+        for (index, class) in self.classes().enumerate() {
+            let id = ClassId(index);
+            let scope = class.namespace.as_str();
+            let mut push = |site: RefSite, ty: &str| {
+                for name in type_names(ty) {
+                    refs.push(Reference {
+                        site,
+                        name: name.to_owned(),
+                        target: res.lookup(name, scope),
+                    });
+                }
+            };
+            for (member, m) in class.members.iter().enumerate() {
+                match m {
+                    Member::Attribute(a) => {
+                        if let Some(ty) = &a.data_type {
+                            push(RefSite::Attribute { class: id, member }, ty);
+                        }
+                    }
+                    Member::Method(m) => {
+                        for (param, p) in m.parameters.iter().enumerate() {
+                            if let Some(ty) = &p.data_type {
+                                push(RefSite::Parameter { class: id, member, param }, ty);
+                            }
+                        }
+                        if let Some(ty) = &m.return_type {
+                            push(RefSite::ReturnType { class: id, member }, ty);
+                        }
+                    }
+                }
+            }
+        }
+
+        res.references = refs;
+        res
+    }
+}
+
+/// Identifiers (possibly `::`-qualified) inside a type expression
+fn type_names(ty: &str) -> impl Iterator<Item = &str> {
+    ty.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-' || c == ':'))
+        .map(|s| s.trim_matches(':'))
+        .filter(|s| !s.is_empty())
+}
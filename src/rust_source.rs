@@ -0,0 +1,575 @@
+//! Class diagrams from Rust source (`rust-source` feature).
+//!
+//! ```
+//! use mermaid_parser::rust_source;
+//! use mermaid_parser::types::RelationKind;
+//!
+//! let diagram = rust_source::parse(r#"
+//!     pub trait Shape { fn area(&self) -> f64; }
+//!     pub struct Circle { pub radius: f64 }
+//!     impl Shape for Circle { fn area(&self) -> f64 { 0.0 } }
+//!     pub struct Canvas { shapes: Vec<Circle> }
+//! "#).unwrap();
+//! assert_eq!(diagram.class("Shape").unwrap().annotations, ["interface"]);
+//! assert_eq!(diagram.implementors("Shape"), ["Circle"]);
+//! assert!(diagram.relations.iter().any(|r| r.kind == RelationKind::Composition && r.to == "Canvas"));
+//! ```
+//!
+//! | Rust                                 | diagram                                   |
+//! |--------------------------------------|-------------------------------------------|
+//! | `struct`                             | class, fields as attributes               |
+//! | `enum`                               | `<<enumeration>>`, variants as attributes |
+//! | `trait`                              | `<<interface>>`, supertraits extended     |
+//! | `impl T`                             | methods; associated consts static         |
+//! | `impl Trait for T`                   | realization `Trait <\|.. T`               |
+//! | `pub` / `pub(…)` / private           | `+` / `~` / `-`                           |
+//! | no `self` receiver, no default body  | static (`$`), abstract (`*`)              |
+//! | field of a type owned                | composition, `*` in a collection, `0..1` in an `Option` |
+//! | field behind `&`, `Rc`, `Arc`, `Weak` | association                              |
+//! | `mod a { mod b { … } }`, `a/b.rs`    | namespace `a::b`                          |
+//!
+//! Only types defined in the scanned sources take part in relations.  A
+//! type is found by its path when it has one (`crate::`, `super::`,
+//! `self::`), in the module using it, and a bare name otherwise by name
+//! alone if no other scanned type shares it – `io::Error` never matches a
+//! scanned `Error`; `use` declarations are not followed.  Items under
+//! `#[cfg(test)]` are skipped.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use syn::punctuated::Punctuated;
+use syn::{
+    FnArg, GenericArgument, GenericParam, Generics, ImplItem, Item, PathArguments, Pat, ReturnType,
+    Signature, TraitItem, Type, TypeParamBound,
+};
+use thiserror::Error;
+
+use crate::types::{
+    Attribute, Class, Diagram, LineStyle, Member, Method, Parameter, Relation, RelationKind, Visibility,
+};
+
+#[derive(Debug, Error)]
+pub enum ScanError {
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("{}: {source}", path.display())]
+    Syntax { path: PathBuf, source: syn::Error },
+}
+
+/// Diagram of a single source file, its inline modules as namespaces.
+pub fn parse(src: &str) -> Result<Diagram, syn::Error> {
+    let mut scanner = Scanner::new();
+    scanner.add_source("", src)?;
+    Ok(scanner.finish())
+}
+
+/// Diagram of every `.rs` file below `dir`, usually a crate's `src`.
+pub fn scan_dir(dir: &Path) -> Result<Diagram, ScanError> {
+    let mut scanner = Scanner::new();
+    scanner.add_dir(dir)?;
+    Ok(scanner.finish())
+}
+
+/// Collects items from several sources; relations are resolved by
+/// [`Scanner::finish`], once every type is known
+#[derive(Debug, Default)]
+pub struct Scanner {
+    diagram: Diagram,
+    impls: Vec<Impl>,
+    uses: Vec<Use>,
+}
+
+/// An `impl` block, waiting for its type to be known
+#[derive(Debug)]
+struct Impl {
+    module: String,
+    self_ty: Vec<String>,          // path segments
+    trait_: Option<Vec<String>>,
+    members: Vec<Member>,
+}
+
+/// A type named in a field, or a supertrait
+#[derive(Debug)]
+struct Use {
+    module: String,
+    from: String,                  // fully-qualified user
+    path: Vec<String>,
+    kind: UseKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UseKind {
+    Owned(Multiplicity),
+    Shared(Multiplicity),
+    Supertrait,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Multiplicity {
+    One,
+    Optional,
+    Many,
+}
+
+impl Scanner {
+    pub fn new() -> Self {
+        Scanner::default()
+    }
+
+    /// Add the items of `src`, a file of module `module` (`""` for the crate root,
+    /// `a::b` for `src/a/b.rs`).
+    pub fn add_source(&mut self, module: &str, src: &str) -> Result<&mut Self, syn::Error> {
+        let file = syn::parse_file(src)?;
+        self.items(module, &file.items);
+        Ok(self)
+    }
+
+    /// Add every `.rs` file below `dir`, naming modules after their paths:
+    /// `lib.rs`/`main.rs` are the root, `a/mod.rs` and `a.rs` are `a`.
+    /// Symbolic links are not followed.
+    pub fn add_dir(&mut self, dir: &Path) -> Result<&mut Self, ScanError> {
+        let mut files = Vec::new();
+        collect_files(dir, &mut files)?;
+        files.sort();
+        for path in files {
+            let src = fs::read_to_string(&path).map_err(|source| ScanError::Io { path: path.clone(), source })?;
+            let module = module_of(path.strip_prefix(dir).unwrap_or(&path));
+            self.add_source(&module, &src).map_err(|source| ScanError::Syntax { path, source })?;
+        }
+        Ok(self)
+    }
+
+    /// The diagram, with `impl` blocks and relations resolved.
+    pub fn finish(mut self) -> Diagram {
+        for imp in std::mem::take(&mut self.impls) {
+            let Some(target) = self.resolve(&imp.module, &imp.self_ty) else { continue };
+            match &imp.trait_ {
+                Some(trait_) => {
+                    if let Some(trait_) = self.resolve(&imp.module, trait_) {
+                        self.relate(&target, &trait_, RelationKind::Extension, LineStyle::Dotted, None, None);
+                    }
+                }
+                None => self.diagram.class_entry(&target).members.extend(imp.members),
+            }
+        }
+        for used in std::mem::take(&mut self.uses) {
+            let Some(to) = self.resolve(&used.module, &used.path) else { continue };
+            match used.kind {
+                UseKind::Supertrait => {
+                    self.relate(&used.from, &to, RelationKind::Extension, LineStyle::Solid, None, None)
+                }
+                // part ➜ whole, the multiplicity on the part
+                UseKind::Owned(m) => {
+                    self.relate(&to, &used.from, RelationKind::Composition, LineStyle::Solid, m.label(), None)
+                }
+                UseKind::Shared(m) => {
+                    self.relate(&used.from, &to, RelationKind::Dependency, LineStyle::Solid, None, m.label())
+                }
+            }
+        }
+        self.diagram
+    }
+
+    fn relate(
+        &mut self,
+        from: &str,
+        to: &str,
+        kind: RelationKind,
+        line: LineStyle,
+        label_from: Option<String>,
+        label_to: Option<String>,
+    ) {
+        let existing = self.diagram.relations.iter_mut().find(|r| r.from == from && r.to == to && r.kind == kind);
+        match existing {
+            // several fields of one type: the widest multiplicity wins
+            Some(r) => {
+                r.label_from = Multiplicity::widest(r.label_from.take(), label_from);
+                r.label_to = Multiplicity::widest(r.label_to.take(), label_to);
+            }
+            None => self.diagram.add_relation(Relation { from: from.into(), to: to.into(), kind, line, label_from, label_to }),
+        }
+    }
+
+    /// Fully-qualified name of the class a path names, seen from `module`
+    fn resolve(&self, module: &str, path: &[String]) -> Option<String> {
+        let (name, prefix) = path.split_last()?;
+        let mut scope: Vec<&str> = module.split("::").filter(|s| !s.is_empty()).collect();
+        let mut relative = false;
+        let mut segments = prefix.iter().map(String::as_str).peekable();
+        while let Some(&first) = segments.peek() {
+            match first {
+                "crate" => scope.clear(),
+                "super" => {
+                    scope.pop();
+                }
+                "self" => {}
+                _ => break,
+            }
+            relative = true;
+            segments.next();
+        }
+        scope.extend(segments);
+        let local = if scope.is_empty() { name.clone() } else { format!("{}::{name}", scope.join("::")) };
+        if self.diagram.class(&local).is_some_and(|c| !c.implicit) {
+            return Some(local);
+        }
+        if relative || !prefix.is_empty() {
+            return None;
+        }
+        // a bare name by name alone, if unambiguous
+        let mut found = self.diagram.classes().filter(|c| !c.implicit && crate::types::split_namespace(&c.name).1 == name);
+        match (found.next(), found.next()) {
+            (Some(class), None) => Some(class.name.clone()),
+            _ => None,
+        }
+    }
+
+    // ── items ───────────────────────────────────────────────────────────────
+
+    fn items(&mut self, module: &str, items: &[Item]) {
+        for item in items {
+            match item {
+                Item::Struct(s) if !is_test(&s.attrs) => {
+                    let name = qualify(module, &s.ident.to_string());
+                    let class = self.class(&name, &s.generics);
+                    let members: Vec<Member> = s
+                        .fields
+                        .iter()
+                        .enumerate()
+                        .map(|(i, f)| {
+                            Member::Attribute(Attribute {
+                                visibility: visibility(&f.vis),
+                                name: f.ident.as_ref().map_or_else(|| i.to_string(), ToString::to_string),
+                                data_type: Some(type_name(&f.ty)),
+                                is_static: false,
+                            })
+                        })
+                        .collect();
+                    class.members.extend(members);
+                    for field in &s.fields {
+                        self.uses_in(module, &name, &field.ty);
+                    }
+                }
+                Item::Enum(e) if !is_test(&e.attrs) => {
+                    let name = qualify(module, &e.ident.to_string());
+                    let class = self.class(&name, &e.generics);
+                    class.annotations.push("enumeration".into());
+                    for variant in &e.variants {
+                        class.members.push(Member::Attribute(Attribute {
+                            visibility: Visibility::Unspecified,
+                            name: variant.ident.to_string(),
+                            data_type: None,
+                            is_static: false,
+                        }));
+                    }
+                    for field in e.variants.iter().flat_map(|v| &v.fields) {
+                        self.uses_in(module, &name, &field.ty);
+                    }
+                }
+                Item::Trait(t) if !is_test(&t.attrs) => {
+                    let name = qualify(module, &t.ident.to_string());
+                    let class = self.class(&name, &t.generics);
+                    class.annotations.push("interface".into());
+                    for item in &t.items {
+                        match item {
+                            TraitItem::Fn(f) => class.members.push(Member::Method(Method {
+                                is_abstract: f.default.is_none(),
+                                ..method(Visibility::Public, &f.sig)
+                            })),
+                            TraitItem::Const(c) => class.members.push(Member::Attribute(Attribute {
+                                visibility: Visibility::Public,
+                                name: c.ident.to_string(),
+                                data_type: Some(type_name(&c.ty)),
+                                is_static: true,
+                            })),
+                            _ => {}
+                        }
+                    }
+                    for bound in &t.supertraits {
+                        if let TypeParamBound::Trait(bound) = bound {
+                            self.uses.push(Use {
+                                module: module.into(),
+                                from: name.clone(),
+                                path: segments(&bound.path),
+                                kind: UseKind::Supertrait,
+                            });
+                        }
+                    }
+                }
+                Item::Impl(i) if !is_test(&i.attrs) => {
+                    let Type::Path(self_ty) = &*i.self_ty else { continue };
+                    let mut members = Vec::new();
+                    for item in &i.items {
+                        match item {
+                            ImplItem::Fn(f) => members.push(Member::Method(method(visibility(&f.vis), &f.sig))),
+                            ImplItem::Const(c) => members.push(Member::Attribute(Attribute {
+                                visibility: visibility(&c.vis),
+                                name: c.ident.to_string(),
+                                data_type: Some(type_name(&c.ty)),
+                                is_static: true,
+                            })),
+                            _ => {}
+                        }
+                    }
+                    self.impls.push(Impl {
+                        module: module.into(),
+                        self_ty: segments(&self_ty.path),
+                        trait_: i.trait_.as_ref().map(|(_, path, _)| segments(path)),
+                        members,
+                    });
+                }
+                Item::Mod(m) if !is_test(&m.attrs) => {
+                    if let Some((_, items)) = &m.content {
+                        self.items(&qualify(module, &m.ident.to_string()), items);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn class(&mut self, name: &str, generics: &Generics) -> &mut Class {
+        let class = self.diagram.class_entry(name);
+        class.implicit = false;
+        let params: Vec<String> = generics
+            .params
+            .iter()
+            .filter_map(|p| match p {
+                GenericParam::Type(t) => Some(t.ident.to_string()),
+                GenericParam::Const(c) => Some(c.ident.to_string()),
+                GenericParam::Lifetime(_) => None,
+            })
+            .collect();
+        if !params.is_empty() {
+            class.generic = Some(params.join(", "));
+        }
+        class
+    }
+
+    /// Record the types a field of `from` names
+    fn uses_in(&mut self, module: &str, from: &str, ty: &Type) {
+        let mut found = Vec::new();
+        walk(ty, Multiplicity::One, false, &mut found);
+        for (path, multiplicity, shared) in found {
+            let kind = if shared { UseKind::Shared(multiplicity) } else { UseKind::Owned(multiplicity) };
+            self.uses.push(Use { module: module.into(), from: from.into(), path, kind });
+        }
+    }
+}
+
+impl Multiplicity {
+    fn label(self) -> Option<String> {
+        match self {
+            Multiplicity::One => None,
+            Multiplicity::Optional => Some("0..1".into()),
+            Multiplicity::Many => Some("*".into()),
+        }
+    }
+
+    /// The wider of two labels written by [`Multiplicity::label`]
+    fn widest(a: Option<String>, b: Option<String>) -> Option<String> {
+        let of = |label: &Option<String>| match label.as_deref() {
+            None => Multiplicity::One,
+            Some("0..1") => Multiplicity::Optional,
+            Some(_) => Multiplicity::Many,
+        };
+        of(&a).nest(of(&b)).label()
+    }
+
+    fn nest(self, inner: Multiplicity) -> Multiplicity {
+        match (self, inner) {
+            (Multiplicity::Many, _) | (_, Multiplicity::Many) => Multiplicity::Many,
+            (Multiplicity::Optional, _) | (_, Multiplicity::Optional) => Multiplicity::Optional,
+            _ => Multiplicity::One,
+        }
+    }
+}
+
+/// Paths a type names, with how many and whether they are owned
+fn walk(ty: &Type, multiplicity: Multiplicity, shared: bool, found: &mut Vec<(Vec<String>, Multiplicity, bool)>) {
+    match ty {
+        Type::Path(p) => {
+            let Some(last) = p.path.segments.last() else { return };
+            let args: Vec<&Type> = match &last.arguments {
+                PathArguments::AngleBracketed(a) => a
+                    .args
+                    .iter()
+                    .filter_map(|a| match a {
+                        GenericArgument::Type(t) => Some(t),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            let (inner, shared_inner) = match last.ident.to_string().as_str() {
+                "Vec" | "VecDeque" | "LinkedList" | "HashSet" | "BTreeSet" | "BinaryHeap" | "IndexSet" => {
+                    (Some(Multiplicity::Many), shared)
+                }
+                // keys are not parts, values are
+                "HashMap" | "BTreeMap" | "IndexMap" => {
+                    if let Some(value) = args.last() {
+                        walk(value, multiplicity.nest(Multiplicity::Many), shared, found);
+                    }
+                    return;
+                }
+                "Option" => (Some(Multiplicity::Optional), shared),
+                "Box" | "Cell" | "RefCell" | "Mutex" | "RwLock" | "Cow" => (Some(Multiplicity::One), shared),
+                "Rc" | "Arc" | "Weak" => (Some(Multiplicity::One), true),
+                _ => (None, shared),
+            };
+            match inner {
+                Some(m) => {
+                    for arg in args {
+                        walk(arg, multiplicity.nest(m), shared_inner, found);
+                    }
+                }
+                None => {
+                    found.push((segments(&p.path), multiplicity, shared));
+                    for arg in args {
+                        walk(arg, multiplicity, true, found);
+                    }
+                }
+            }
+        }
+        Type::Reference(r) => walk(&r.elem, multiplicity, true, found),
+        Type::Ptr(p) => walk(&p.elem, multiplicity, true, found),
+        Type::Slice(s) => walk(&s.elem, multiplicity.nest(Multiplicity::Many), shared, found),
+        Type::Array(a) => walk(&a.elem, multiplicity.nest(Multiplicity::Many), shared, found),
+        Type::Tuple(t) => {
+            for elem in &t.elems {
+                walk(elem, multiplicity, shared, found);
+            }
+        }
+        Type::Paren(p) => walk(&p.elem, multiplicity, shared, found),
+        Type::Group(g) => walk(&g.elem, multiplicity, shared, found),
+        _ => {}
+    }
+}
+
+// ── names ───────────────────────────────────────────────────────────────────
+
+/// A type in Mermaid notation: `Vec~Shape~`, `&str`, `u8[]`
+fn type_name(ty: &Type) -> String {
+    match ty {
+        Type::Path(p) => path_name(&p.path),
+        Type::Reference(r) => {
+            let mutability = if r.mutability.is_some() { "mut " } else { "" };
+            format!("&{mutability}{}", type_name(&r.elem))
+        }
+        Type::Ptr(p) => format!("*{}", type_name(&p.elem)),
+        Type::Slice(s) => format!("{}[]", type_name(&s.elem)),
+        Type::Array(a) => format!("{}[]", type_name(&a.elem)),
+        Type::Tuple(t) => {
+            let elems: Vec<String> = t.elems.iter().map(type_name).collect();
+            format!("({})", elems.join(", "))
+        }
+        Type::Paren(p) => type_name(&p.elem),
+        Type::Group(g) => type_name(&g.elem),
+        Type::TraitObject(t) => format!("dyn {}", bounds_name(&t.bounds)),
+        Type::ImplTrait(t) => format!("impl {}", bounds_name(&t.bounds)),
+        Type::BareFn(_) => "fn".into(),
+        Type::Never(_) => "!".into(),
+        _ => "_".into(),
+    }
+}
+
+fn path_name(path: &syn::Path) -> String {
+    let Some(last) = path.segments.last() else { return String::new() };
+    let mut out = last.ident.to_string();
+    if let PathArguments::AngleBracketed(args) = &last.arguments {
+        let args: Vec<String> = args
+            .args
+            .iter()
+            .filter_map(|a| match a {
+                GenericArgument::Type(t) => Some(type_name(t)),
+                _ => None,
+            })
+            .collect();
+        if !args.is_empty() {
+            out = format!("{out}~{}~", args.join(","));
+        }
+    }
+    out
+}
+
+fn bounds_name(bounds: &Punctuated<TypeParamBound, syn::Token![+]>) -> String {
+    bounds
+        .iter()
+        .find_map(|b| match b {
+            TypeParamBound::Trait(t) => Some(path_name(&t.path)),
+            _ => None,
+        })
+        .unwrap_or_else(|| "_".into())
+}
+
+fn segments(path: &syn::Path) -> Vec<String> {
+    path.segments.iter().map(|s| s.ident.to_string()).collect()
+}
+
+fn method(visibility: Visibility, sig: &Signature) -> Method {
+    let mut is_static = true;
+    let mut parameters = Vec::new();
+    for input in &sig.inputs {
+        match input {
+            FnArg::Receiver(_) => is_static = false,
+            FnArg::Typed(arg) => parameters.push(Parameter {
+                name: match &*arg.pat {
+                    Pat::Ident(p) => p.ident.to_string(),
+                    _ => "_".into(),
+                },
+                data_type: Some(type_name(&arg.ty)),
+            }),
+        }
+    }
+    let return_type = match &sig.output {
+        ReturnType::Default => None,
+        ReturnType::Type(_, ty) => Some(type_name(ty)),
+    };
+    Method { visibility, name: sig.ident.to_string(), parameters, return_type, is_static, is_abstract: false }
+}
+
+fn visibility(vis: &syn::Visibility) -> Visibility {
+    match vis {
+        syn::Visibility::Public(_) => Visibility::Public,
+        syn::Visibility::Restricted(_) => Visibility::Package,
+        syn::Visibility::Inherited => Visibility::Private,
+    }
+}
+
+/// `#[cfg(test)]`
+fn is_test(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|a| {
+        a.path().is_ident("cfg") && a.parse_args::<syn::Ident>().is_ok_and(|i| i == "test")
+    })
+}
+
+fn qualify(module: &str, name: &str) -> String {
+    if module.is_empty() { name.to_owned() } else { format!("{module}::{name}") }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), ScanError> {
+    let io = |source| ScanError::Io { path: dir.to_owned(), source };
+    for entry in fs::read_dir(dir).map_err(io)? {
+        let entry = entry.map_err(io)?;
+        // symlinks are skipped: a link to a parent directory would never end
+        let file_type = entry.file_type().map_err(io)?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_files(&path, files)?;
+        } else if file_type.is_file() && path.extension().is_some_and(|e| e == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// `a/b.rs`, `a/b/mod.rs` ➜ `a::b`; `lib.rs`, `main.rs` ➜ root
+fn module_of(relative: &Path) -> String {
+    let mut parts: Vec<String> =
+        relative.with_extension("").components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect();
+    if parts.len() == 1 && matches!(parts[0].as_str(), "lib" | "main") || parts.last().is_some_and(|p| p == "mod") {
+        parts.pop();
+    }
+    parts.join("::")
+}
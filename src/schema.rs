@@ -0,0 +1,20 @@
+//! JSON Schema of the serialized data model (`json-schema` feature).
+//!
+//! The schema describes a [`VersionedDiagram`] document exactly as the `serde`
+//! feature writes it.  A copy is checked in at `schema/diagram.schema.json`
+//! for clients that validate or generate code without building this crate.
+
+use crate::types::VersionedDiagram;
+
+/// Schema of a [`VersionedDiagram`] JSON document
+pub fn json_schema() -> serde_json::Value {
+    schemars::schema_for!(VersionedDiagram).to_value()
+}
+
+/// [`json_schema`] pretty-printed, byte-identical to the checked-in artifact
+pub fn json_schema_string() -> String {
+    let mut text = serde_json::to_string_pretty(&json_schema())
+        .expect("a schema value always serializes");
+    text.push('\n');
+    text
+}
@@ -0,0 +1,332 @@
+//! Standalone SVG rendering.
+//!
+//! ```
+//! let diagram = mermaid_parser::parser::parse("classDiagram\nShape : +area() : f64\nShape <|-- Circle\n").unwrap();
+//! let svg = diagram.to_svg();
+//! assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+//! assert!(svg.contains(">+area() : f64</text>"));
+//! assert!(svg.contains("marker-end=\"url(#extension)\""));
+//! ```
+//!
+//! Classes are laid out with [`layout::layered`](crate::layout::layered):
+//! the arrow head end of every relation (superclass, whole, dependency) is
+//! ranked above its source, as in Mermaid.  Each class box has a title
+//! compartment (annotations, name, generic) and one each for attributes and
+//! methods, members written as in Mermaid.  Boxes are sized from a fixed
+//! monospace advance, so no font metrics are needed.
+//!
+//! Edges are orthogonal and carry Mermaid’s arrow heads at their `to` end;
+//! multiplicity labels sit next to the end they belong to.  Notes are drawn
+//! as yellow boxes tied to their class by a dashed line.  Namespaces are not
+//! drawn; their classes are laid out like any other.
+
+use std::fmt::Write;
+
+use crate::layout::{self, Direction, LayoutOptions, Point, Rect, Scene};
+use crate::types::{split_namespace, Class, Diagram, LineStyle, Member, Relation, RelationKind};
+
+const MARGIN: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvgOptions {
+    pub direction: Direction,
+    pub font_size: f64,        // px; boxes and gaps scale with it
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions { direction: Direction::TopToBottom, font_size: 14.0 }
+    }
+}
+
+impl Diagram {
+    /// Render as a standalone SVG document with the default options.
+    pub fn to_svg(&self) -> String {
+        self.to_svg_with(&SvgOptions::default())
+    }
+
+    pub fn to_svg_with(&self, options: &SvgOptions) -> String {
+        let metrics = Metrics::new(options.font_size);
+        let scene = Scene::new(self);
+        let boxes: Vec<Node> = scene
+            .classes
+            .iter()
+            .map(|c| Node::class(c))
+            .chain(scene.notes.iter().map(|n| Node::note(&n.text)))
+            .collect();
+
+        let sizes: Vec<(f64, f64)> = boxes.iter().map(|b| b.size(&metrics)).collect();
+        let layout = layout::layered(
+            &sizes,
+            &scene.edges,
+            &LayoutOptions {
+                direction: options.direction,
+                node_gap: options.font_size * 3.0,
+                layer_gap: options.font_size * 5.0,
+            },
+        );
+
+        let (width, height) = (layout.width + 2.0 * MARGIN, layout.height + 2.0 * MARGIN);
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" \
+             font-family=\"monospace\" font-size=\"{}\">",
+            num(options.font_size),
+            w = num(width),
+            h = num(height),
+        );
+        out += STYLE;
+        out += DEFS;
+        let _ = writeln!(out, "<g transform=\"translate({MARGIN},{MARGIN})\">");
+
+        for (node, rect) in boxes.iter().zip(&layout.nodes) {
+            node.write(&mut out, *rect, &metrics);
+        }
+
+        for (link, points) in scene.links.iter().zip(&layout.edges) {
+            match link {
+                Some(relation) => write_relation(&mut out, relation, points, &metrics),
+                None => {
+                    let _ = writeln!(out, "<path class=\"note-link\" d=\"{}\"/>", path(points));
+                }
+            }
+        }
+
+        out += "</g>\n</svg>\n";
+        out
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Boxes
+// ────────────────────────────────────────────────────────────────────────────────
+
+/// Text measurements derived from the font size
+struct Metrics {
+    char_width: f64,
+    line_height: f64,
+    padding: f64,
+}
+
+impl Metrics {
+    fn new(font_size: f64) -> Self {
+        Metrics {
+            char_width: font_size * 0.6,
+            line_height: font_size * 1.5,
+            padding: font_size / 2.0,
+        }
+    }
+
+    fn text_width(&self, text: &str) -> f64 {
+        text.chars().count() as f64 * self.char_width
+    }
+}
+
+enum Node {
+    Class {
+        title: Vec<String>,        // annotations, then the name
+        attributes: Vec<String>,
+        methods: Vec<String>,
+    },
+    Note(Vec<String>),
+}
+
+impl Node {
+    fn class(class: &Class) -> Self {
+        let mut title: Vec<String> = class.annotations.iter().map(|a| format!("«{a}»")).collect();
+        let name = split_namespace(&class.name).1;
+        title.push(match &class.generic {
+            Some(generic) => format!("{name}<{generic}>"),
+            None => name.to_owned(),
+        });
+        let (mut attributes, mut methods) = (Vec::new(), Vec::new());
+        for member in &class.members {
+            match member {
+                Member::Attribute(_) => attributes.push(member.to_string()),
+                Member::Method(_) => methods.push(member.to_string()),
+            }
+        }
+        Node::Class { title, attributes, methods }
+    }
+
+    /// Mermaid notes break lines with a literal `\n`
+    fn note(text: &str) -> Self {
+        Node::Note(text.split("\\n").map(str::to_owned).collect())
+    }
+
+    fn sections(&self) -> Vec<&[String]> {
+        match self {
+            Node::Class { title, attributes, methods } => vec![title, attributes, methods],
+            Node::Note(lines) => vec![lines],
+        }
+    }
+
+    fn size(&self, m: &Metrics) -> (f64, f64) {
+        let sections = self.sections();
+        let widest = sections.iter().flat_map(|s| s.iter()).map(|l| m.text_width(l)).fold(0.0, f64::max);
+        let height: f64 = sections.iter().map(|s| section_height(s, m)).sum();
+        ((widest + 2.0 * m.padding).max(m.char_width * 10.0), height)
+    }
+
+    fn write(&self, out: &mut String, rect: Rect, m: &Metrics) {
+        let kind = match self {
+            Node::Class { .. } => "class",
+            Node::Note(_) => "note",
+        };
+        let _ = writeln!(out, "<g class=\"{kind}\">");
+        let _ = writeln!(
+            out,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+            num(rect.x),
+            num(rect.y),
+            num(rect.width),
+            num(rect.height)
+        );
+
+        let mut top = rect.y;
+        for (i, section) in self.sections().into_iter().enumerate() {
+            if i > 0 {
+                let _ = writeln!(
+                    out,
+                    "<line x1=\"{}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\"/>",
+                    num(rect.x),
+                    num(rect.x + rect.width),
+                    y = num(top)
+                );
+            }
+            // class titles are centred, everything else starts at the left
+            let centred = i == 0 && kind == "class";
+            for (k, line) in section.iter().enumerate() {
+                let y = top + m.padding / 2.0 + m.line_height * (k as f64 + 0.5);
+                let (x, anchor) = if centred {
+                    (rect.x + rect.width / 2.0, " text-anchor=\"middle\"")
+                } else {
+                    (rect.x + m.padding, "")
+                };
+                let _ = writeln!(out, "<text x=\"{}\" y=\"{}\"{anchor}>{}</text>", num(x), num(y), escape(line));
+            }
+            top += section_height(section, m);
+        }
+        out.push_str("</g>\n");
+    }
+}
+
+/// Empty compartments keep a little height so the box reads as a class
+fn section_height(lines: &[String], m: &Metrics) -> f64 {
+    lines.len() as f64 * m.line_height + m.padding
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Edges
+// ────────────────────────────────────────────────────────────────────────────────
+
+fn write_relation(out: &mut String, relation: &Relation, points: &[Point], m: &Metrics) {
+    let marker = match relation.kind {
+        RelationKind::Extension => "extension",
+        RelationKind::Composition => "composition",
+        RelationKind::Aggregation => "aggregation",
+        RelationKind::Dependency => "dependency",
+        RelationKind::Lollipop => "lollipop",
+    };
+    let dashed = match relation.line {
+        LineStyle::Solid => "",
+        LineStyle::Dotted => " stroke-dasharray=\"6 4\"",
+    };
+    let _ = writeln!(
+        out,
+        "<path class=\"relation\" d=\"{}\"{dashed} marker-end=\"url(#{marker})\"/>",
+        path(points)
+    );
+
+    let n = points.len();
+    if let Some(label) = &relation.label_from {
+        write_label(out, label, points[0], points[1], m);
+    }
+    if let Some(label) = &relation.label_to {
+        write_label(out, label, points[n - 1], points[n - 2], m);
+    }
+}
+
+/// Multiplicity next to `end`, beside the segment towards `next`
+fn write_label(out: &mut String, label: &str, end: Point, next: Point, m: &Metrics) {
+    let (dx, dy) = (next.x - end.x, next.y - end.y);
+    let length = dx.hypot(dy).max(f64::EPSILON);
+    let (ux, uy) = (dx / length, dy / length);
+    // clear of the arrow head along the edge, then off to one side
+    let along = (m.line_height * 1.2).min(length / 2.0);
+    let aside = m.text_width(label) / 2.0 + m.padding;
+    let x = end.x + ux * along - uy * aside;
+    let y = end.y + uy * along + ux * aside / 2.0;
+    let _ = writeln!(
+        out,
+        "<text class=\"label\" x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+        num(x),
+        num(y),
+        escape(label)
+    );
+}
+
+fn path(points: &[Point]) -> String {
+    let mut d = String::new();
+    for (i, p) in points.iter().enumerate() {
+        let _ = write!(d, "{}{},{}", if i == 0 { "M" } else { " L" }, num(p.x), num(p.y));
+    }
+    d
+}
+
+const STYLE: &str = "\
+<style>
+.class rect{fill:#ececff;stroke:#9370db;stroke-width:1}
+.class line{stroke:#9370db;stroke-width:1}
+.note rect{fill:#fff5ad;stroke:#aaaa33;stroke-width:1}
+.relation{fill:none;stroke:#333333;stroke-width:1}
+.note-link{fill:none;stroke:#aaaa33;stroke-width:1;stroke-dasharray:4 4}
+text{fill:#333333;dominant-baseline:central}
+</style>
+";
+
+/// Arrow heads, drawn pointing along +x with the tip at `refX`
+const DEFS: &str = "\
+<defs>
+<marker id=\"extension\" markerUnits=\"userSpaceOnUse\" markerWidth=\"14\" markerHeight=\"14\" refX=\"13\" refY=\"7\" orient=\"auto\">
+<path d=\"M1,1 L13,7 L1,13 z\" fill=\"#ffffff\" stroke=\"#333333\"/>
+</marker>
+<marker id=\"composition\" markerUnits=\"userSpaceOnUse\" markerWidth=\"18\" markerHeight=\"10\" refX=\"17\" refY=\"5\" orient=\"auto\">
+<path d=\"M1,5 L9,1 L17,5 L9,9 z\" fill=\"#333333\" stroke=\"#333333\"/>
+</marker>
+<marker id=\"aggregation\" markerUnits=\"userSpaceOnUse\" markerWidth=\"18\" markerHeight=\"10\" refX=\"17\" refY=\"5\" orient=\"auto\">
+<path d=\"M1,5 L9,1 L17,5 L9,9 z\" fill=\"#ffffff\" stroke=\"#333333\"/>
+</marker>
+<marker id=\"dependency\" markerUnits=\"userSpaceOnUse\" markerWidth=\"12\" markerHeight=\"12\" refX=\"11\" refY=\"6\" orient=\"auto\">
+<path d=\"M1,1 L11,6 L1,11\" fill=\"none\" stroke=\"#333333\"/>
+</marker>
+<marker id=\"lollipop\" markerUnits=\"userSpaceOnUse\" markerWidth=\"12\" markerHeight=\"12\" refX=\"11\" refY=\"6\" orient=\"auto\">
+<circle cx=\"6\" cy=\"6\" r=\"5\" fill=\"#ffffff\" stroke=\"#333333\"/>
+</marker>
+</defs>
+";
+
+/// At most two decimals, no trailing zeros
+fn num(value: f64) -> String {
+    let s = format!("{value:.2}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    match s {
+        "-0" => "0".to_owned(),
+        s => s.to_owned(),
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
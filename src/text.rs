@@ -0,0 +1,492 @@
+//! Plain-text rendering for terminals and CI logs.
+//!
+//! ```
+//! let diagram = mermaid_parser::parser::parse("classDiagram\nShape : +area() : f64\nShape <|-- Circle\n").unwrap();
+//! assert_eq!(
+//!     diagram.to_text(),
+//!     "\
+//! ┌───────────────┐
+//! │     Shape     │
+//! ├───────────────┤
+//! │ +area() : f64 │
+//! └───────────────┘
+//!          △
+//!          │
+//!          │
+//!          │
+//!     ┌────────┐
+//!     │ Circle │
+//!     └────────┘
+//! "
+//! );
+//! ```
+//!
+//! Classes are placed with [`layout::layered`](crate::layout::layered), one
+//! character per unit, superclasses and wholes above their parts.  Each box
+//! holds the annotations and name, then the attributes and the methods, each
+//! compartment under a rule; empty compartments are left out.  Dotted
+//! relations are drawn with dotted lines, and the head end carries:
+//!
+//! | relation    | Unicode   | ASCII     |
+//! |-------------|-----------|-----------|
+//! | extension   | `△▽◁▷`    | `A V < >` |
+//! | composition | `◆`       | `*`       |
+//! | aggregation | `◇`       | `o`       |
+//! | dependency  | `↑↓←→`    | `^ v < >` |
+//! | lollipop    | `○`       | `O`       |
+//!
+//! Multiplicity labels are written next to their end, notes as boxes tied to
+//! their class by a dotted line.
+
+use crate::layout::{self, LayoutOptions, Point, Rect, Scene};
+use crate::types::{split_namespace, Class, Diagram, LineStyle, Member, RelationKind};
+
+/// Narrowest a box gets when fitting `max_width`
+const MIN_BOX_WIDTH: usize = 12;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Charset {
+    #[default]
+    Unicode,       // box-drawing characters
+    Ascii,         // `+-|` only, for terminals without UTF-8
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextOptions {
+    pub charset: Charset,
+    pub max_width: Option<usize>,  // columns; boxes narrow first, then lines are cut
+    pub compact: bool,             // names only, tighter spacing
+}
+
+impl Diagram {
+    /// Render as Unicode box drawing with the default options.
+    pub fn to_text(&self) -> String {
+        self.to_text_with(&TextOptions::default())
+    }
+
+    /// Render as text.
+    ///
+    /// With `max_width` set, long lines inside the boxes are shortened until
+    /// the diagram fits; whatever still overflows is cut at the right edge.
+    pub fn to_text_with(&self, options: &TextOptions) -> String {
+        let glyphs = match options.charset {
+            Charset::Unicode => &UNICODE,
+            Charset::Ascii => &ASCII,
+        };
+        let scene = Scene::new(self);
+        let Scene { edges, links, .. } = &scene;
+        let mut boxes: Vec<TextBox> = scene
+            .classes
+            .iter()
+            .map(|c| TextBox::class(c, options, glyphs))
+            .chain(scene.notes.iter().map(|n| TextBox::note(&n.text, options)))
+            .collect();
+        let spacing = LayoutOptions {
+            direction: layout::Direction::TopToBottom,
+            node_gap: if options.compact { 2.0 } else { 4.0 },
+            layer_gap: 4.0,
+        };
+        let lay_out = |boxes: &[TextBox]| {
+            let sizes: Vec<(f64, f64)> = boxes.iter().map(|b| (b.width() as f64, b.height() as f64)).collect();
+            layout::layered(&sizes, edges, &spacing)
+        };
+        let mut layout = lay_out(&boxes);
+        // narrow the boxes until the layout fits, then cut what still does not
+        if let Some(limit) = options.max_width {
+            let mut cap = limit;
+            while layout.width > limit as f64 && cap > MIN_BOX_WIDTH {
+                cap = (cap * limit / layout.width.ceil() as usize).clamp(MIN_BOX_WIDTH, cap - 1);
+                boxes.iter_mut().for_each(|b| b.shorten(cap, glyphs));
+                layout = lay_out(&boxes);
+            }
+        }
+
+        let rects: Vec<Cells> = layout.nodes.iter().map(Cells::from).collect();
+        let mut canvas = Canvas::default();
+        for ((&(from, to), link), points) in edges.iter().zip(links).zip(&layout.edges) {
+            let points = snap(points, &rects[from], &rects[to]);
+            let dotted = link.is_none_or(|r| r.line == LineStyle::Dotted);
+            for pair in points.windows(2) {
+                canvas.line(pair[0], pair[1], dotted);
+            }
+            if let Some(relation) = link {
+                let n = points.len();
+                canvas.put(points[n - 1], Cell::Text(glyphs.head(relation.kind, heading(points[n - 2], points[n - 1]))));
+            }
+        }
+        for (b, rect) in boxes.iter().zip(&rects) {
+            b.draw(&mut canvas, rect, glyphs);
+        }
+        for ((&(from, to), link), points) in edges.iter().zip(links).zip(&layout.edges) {
+            let Some(relation) = link else { continue };
+            let points = snap(points, &rects[from], &rects[to]);
+            let n = points.len();
+            if let Some(label) = &relation.label_from {
+                canvas.label(points[0], label);
+            }
+            if let Some(label) = &relation.label_to {
+                canvas.label(points[n - 1], label);
+            }
+        }
+
+        canvas.render(glyphs, options.max_width)
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Boxes
+// ────────────────────────────────────────────────────────────────────────────────
+
+struct TextBox {
+    sections: Vec<Vec<String>>,    // non-empty compartments, title first
+    centred: bool,                 // title of a class
+}
+
+impl TextBox {
+    fn class(class: &Class, options: &TextOptions, glyphs: &Glyphs) -> Self {
+        let mut title: Vec<String> = class
+            .annotations
+            .iter()
+            .map(|a| format!("{}{a}{}", glyphs.guillemets.0, glyphs.guillemets.1))
+            .collect();
+        let name = split_namespace(&class.name).1;
+        title.push(match &class.generic {
+            Some(generic) => format!("{name}<{generic}>"),
+            None => name.to_owned(),
+        });
+
+        let mut sections = vec![title];
+        if !options.compact {
+            let (mut attributes, mut methods) = (Vec::new(), Vec::new());
+            for member in &class.members {
+                match member {
+                    Member::Attribute(_) => attributes.push(member.to_string()),
+                    Member::Method(_) => methods.push(member.to_string()),
+                }
+            }
+            sections.extend([attributes, methods].into_iter().filter(|s| !s.is_empty()));
+        }
+        TextBox { sections, centred: true }
+    }
+
+    /// Mermaid notes break lines with a literal `\n`
+    fn note(text: &str, options: &TextOptions) -> Self {
+        let lines: Vec<String> = text.split("\\n").map(str::to_owned).collect();
+        let lines = if options.compact { lines.into_iter().take(1).collect() } else { lines };
+        TextBox { sections: vec![lines], centred: false }
+    }
+
+    fn lines(&self) -> impl Iterator<Item = &String> {
+        self.sections.iter().flatten()
+    }
+
+    fn inner_width(&self) -> usize {
+        self.lines().map(|l| l.chars().count()).max().unwrap_or(0)
+    }
+
+    fn width(&self) -> usize {
+        self.inner_width() + 4
+    }
+
+    fn height(&self) -> usize {
+        self.lines().count() + self.sections.len() + 1
+    }
+
+    /// Cut lines so the whole box is at most `limit` columns wide
+    fn shorten(&mut self, limit: usize, glyphs: &Glyphs) {
+        let inner = limit.saturating_sub(4).max(glyphs.ellipsis.chars().count() + 1);
+        for line in self.sections.iter_mut().flatten() {
+            if line.chars().count() > inner {
+                let keep = inner - glyphs.ellipsis.chars().count();
+                *line = line.chars().take(keep).collect::<String>() + glyphs.ellipsis;
+            }
+        }
+    }
+
+    fn draw(&self, canvas: &mut Canvas, rect: &Cells, glyphs: &Glyphs) {
+        let inner = rect.width - 4;
+        let (x, mut y) = (rect.x, rect.y);
+        let rule = |canvas: &mut Canvas, y: usize, [left, fill, right]: [char; 3]| {
+            canvas.put_char(x, y, left);
+            for i in 1..rect.width - 1 {
+                canvas.put_char(x + i, y, fill);
+            }
+            canvas.put_char(x + rect.width - 1, y, right);
+        };
+
+        rule(canvas, y, glyphs.top);
+        for (s, section) in self.sections.iter().enumerate() {
+            if s > 0 {
+                y += 1;
+                rule(canvas, y, glyphs.rule);
+            }
+            for line in section {
+                y += 1;
+                let len = line.chars().count();
+                let pad = if self.centred && s == 0 { (inner - len) / 2 } else { 0 };
+                let text = format!("{}{line}", " ".repeat(pad));
+                canvas.put_char(x, y, glyphs.side);
+                canvas.put_str(x + 1, y, &format!(" {text:inner$} "));
+                canvas.put_char(x + rect.width - 1, y, glyphs.side);
+            }
+        }
+        rule(canvas, y + 1, glyphs.bottom);
+    }
+}
+
+/// A layout rectangle rounded to whole cells
+struct Cells {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl From<&Rect> for Cells {
+    fn from(rect: &Rect) -> Self {
+        Cells {
+            x: rect.x.round() as usize,
+            y: rect.y.round() as usize,
+            width: rect.width.round() as usize,
+            height: rect.height.round() as usize,
+        }
+    }
+}
+
+impl Cells {
+    fn contains(&self, (x, y): (usize, usize)) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Edges
+// ────────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Heading { Up, Down, Left, Right }
+
+fn heading(from: (usize, usize), to: (usize, usize)) -> Heading {
+    match (to.0.cmp(&from.0), to.1.cmp(&from.1)) {
+        (_, std::cmp::Ordering::Less) => Heading::Up,
+        (_, std::cmp::Ordering::Greater) => Heading::Down,
+        (std::cmp::Ordering::Less, _) => Heading::Left,
+        _ => Heading::Right,
+    }
+}
+
+/// Round a polyline to cells and step its ends off the box borders
+fn snap(points: &[Point], from: &Cells, to: &Cells) -> Vec<(usize, usize)> {
+    let mut cells: Vec<(usize, usize)> =
+        points.iter().map(|p| (p.x.round() as usize, p.y.round() as usize)).collect();
+    let n = cells.len();
+    if from.contains(cells[0]) {
+        cells[0] = step(cells[0], cells[1]);
+    }
+    if to.contains(cells[n - 1]) {
+        cells[n - 1] = step(cells[n - 1], cells[n - 2]);
+    }
+    cells
+}
+
+/// One cell from `at` towards `towards`
+fn step(at: (usize, usize), towards: (usize, usize)) -> (usize, usize) {
+    match heading(at, towards) {
+        Heading::Up => (at.0, at.1 - 1),
+        Heading::Down => (at.0, at.1 + 1),
+        Heading::Left => (at.0 - 1, at.1),
+        Heading::Right => (at.0 + 1, at.1),
+    }
+}
+
+// line directions leaving a cell
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Line { mask: u8, dotted: bool },
+    Text(char),
+}
+
+#[derive(Default)]
+struct Canvas {
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Canvas {
+    fn cell(&mut self, x: usize, y: usize) -> &mut Cell {
+        if self.rows.len() <= y {
+            self.rows.resize(y + 1, Vec::new());
+        }
+        let row = &mut self.rows[y];
+        if row.len() <= x {
+            row.resize(x + 1, Cell::Empty);
+        }
+        &mut row[x]
+    }
+
+    fn put(&mut self, (x, y): (usize, usize), cell: Cell) {
+        *self.cell(x, y) = cell;
+    }
+
+    fn put_char(&mut self, x: usize, y: usize, c: char) {
+        self.put((x, y), Cell::Text(c));
+    }
+
+    fn put_str(&mut self, x: usize, y: usize, s: &str) {
+        for (i, c) in s.chars().enumerate() {
+            self.put_char(x + i, y, c);
+        }
+    }
+
+    /// Text beside an edge end, only over blank cells
+    fn label(&mut self, (x, y): (usize, usize), text: &str) {
+        let x = x + 2;
+        let len = text.chars().count();
+        if (x..x + len).all(|x| *self.cell(x, y) == Cell::Empty) {
+            self.put_str(x, y, text);
+        }
+    }
+
+    /// Axis-aligned segment; crossings and bends merge into junctions
+    fn line(&mut self, a: (usize, usize), b: (usize, usize), dotted: bool) {
+        let mut mark = |x: usize, y: usize, bits: u8| {
+            let cell = self.cell(x, y);
+            *cell = match *cell {
+                Cell::Line { mask, .. } => Cell::Line { mask: mask | bits, dotted },
+                _ => Cell::Line { mask: bits, dotted },
+            };
+        };
+        if a.1 == b.1 {
+            let (lo, hi) = (a.0.min(b.0), a.0.max(b.0));
+            for x in lo..=hi {
+                let bits = if x > lo { LEFT } else { 0 } | if x < hi { RIGHT } else { 0 };
+                mark(x, a.1, bits);
+            }
+        } else {
+            let (lo, hi) = (a.1.min(b.1), a.1.max(b.1));
+            for y in lo..=hi {
+                let bits = if y > lo { UP } else { 0 } | if y < hi { DOWN } else { 0 };
+                mark(a.0, y, bits);
+            }
+        }
+    }
+
+    fn render(&self, glyphs: &Glyphs, max_width: Option<usize>) -> String {
+        let mut out = String::new();
+        for row in &self.rows {
+            let mut line: String = row
+                .iter()
+                .map(|cell| match *cell {
+                    Cell::Empty => ' ',
+                    Cell::Text(c) => c,
+                    Cell::Line { mask, dotted } => glyphs.line(mask, dotted),
+                })
+                .collect();
+            if let Some(limit) = max_width {
+                line = line.chars().take(limit).collect();
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Character sets
+// ────────────────────────────────────────────────────────────────────────────────
+
+struct Glyphs {
+    top: [char; 3],
+    rule: [char; 3],
+    bottom: [char; 3],
+    side: char,
+    guillemets: (&'static str, &'static str),
+    ellipsis: &'static str,
+    solid: (char, char),           // vertical, horizontal
+    dotted: (char, char),
+    corners: [char; 4],            // ┌ ┐ └ ┘
+    tees: [char; 5],               // ├ ┤ ┬ ┴ ┼
+    extension: [char; 4],          // up, down, left, right
+    dependency: [char; 4],
+    composition: char,
+    aggregation: char,
+    lollipop: char,
+}
+
+static UNICODE: Glyphs = Glyphs {
+    top: ['┌', '─', '┐'],
+    rule: ['├', '─', '┤'],
+    bottom: ['└', '─', '┘'],
+    side: '│',
+    guillemets: ("«", "»"),
+    ellipsis: "…",
+    solid: ('│', '─'),
+    dotted: ('┆', '┄'),
+    corners: ['┌', '┐', '└', '┘'],
+    tees: ['├', '┤', '┬', '┴', '┼'],
+    extension: ['△', '▽', '◁', '▷'],
+    dependency: ['↑', '↓', '←', '→'],
+    composition: '◆',
+    aggregation: '◇',
+    lollipop: '○',
+};
+
+static ASCII: Glyphs = Glyphs {
+    top: ['+', '-', '+'],
+    rule: ['+', '-', '+'],
+    bottom: ['+', '-', '+'],
+    side: '|',
+    guillemets: ("<<", ">>"),
+    ellipsis: "...",
+    solid: ('|', '-'),
+    dotted: (':', '.'),
+    corners: ['+'; 4],
+    tees: ['+'; 5],
+    extension: ['A', 'V', '<', '>'],
+    dependency: ['^', 'v', '<', '>'],
+    composition: '*',
+    aggregation: 'o',
+    lollipop: 'O',
+};
+
+impl Glyphs {
+    fn line(&self, mask: u8, dotted: bool) -> char {
+        let (vertical, horizontal) = if dotted { self.dotted } else { self.solid };
+        match mask {
+            0 => ' ',
+            m if m & (LEFT | RIGHT) == 0 => vertical,
+            m if m & (UP | DOWN) == 0 => horizontal,
+            m if m == DOWN | RIGHT => self.corners[0],
+            m if m == DOWN | LEFT => self.corners[1],
+            m if m == UP | RIGHT => self.corners[2],
+            m if m == UP | LEFT => self.corners[3],
+            m if m == UP | DOWN | RIGHT => self.tees[0],
+            m if m == UP | DOWN | LEFT => self.tees[1],
+            m if m == LEFT | RIGHT | DOWN => self.tees[2],
+            m if m == LEFT | RIGHT | UP => self.tees[3],
+            _ => self.tees[4],
+        }
+    }
+
+    fn head(&self, kind: RelationKind, heading: Heading) -> char {
+        let i = match heading {
+            Heading::Up => 0,
+            Heading::Down => 1,
+            Heading::Left => 2,
+            Heading::Right => 3,
+        };
+        match kind {
+            RelationKind::Extension => self.extension[i],
+            RelationKind::Dependency => self.dependency[i],
+            RelationKind::Composition => self.composition,
+            RelationKind::Aggregation => self.aggregation,
+            RelationKind::Lollipop => self.lollipop,
+        }
+    }
+}
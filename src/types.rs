@@ -1,10 +1,22 @@
-use std::collections::HashMap;
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::Hash;
+
+use indexmap::IndexMap;
 
 /// “default” (no explicit namespace in the diagram)  
 pub const DEFAULT_NAMESPACE: &str = "";
 
+/// Version of the serialized data model (`serde` feature).
+///
+/// Bumped whenever the JSON shape of the types below changes incompatibly.
+pub const SCHEMA_VERSION: u32 = 1;
+
 /// Public/Private/… like in Mermaid (# + ~ - or empty)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Visibility {
     Public,     // +
     Private,    // -
@@ -25,8 +37,23 @@ impl From<char> for Visibility {
     }
 }
 
+impl Visibility {
+    /// Mermaid prefix; empty for `Unspecified`
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Visibility::Public => "+",
+            Visibility::Private => "-",
+            Visibility::Protected => "#",
+            Visibility::Package => "~",
+            Visibility::Unspecified => "",
+        }
+    }
+}
+
 /// A single parameter in a method signature
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Parameter {
     pub name: String,
     pub data_type: Option<String>,     // `None` if omitted in the diagram
@@ -34,6 +61,9 @@ pub struct Parameter {
 
 /// A member inside a class box
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "lowercase"))]
 pub enum Member {
     /// `+fieldName: Type`
     Attribute(Attribute),
@@ -42,8 +72,88 @@ pub enum Member {
     Method(Method),
 }
 
+impl Member {
+    pub fn name(&self) -> &str {
+        match self {
+            Member::Attribute(a) => &a.name,
+            Member::Method(m) => &m.name,
+        }
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        match self {
+            Member::Attribute(a) => a.visibility,
+            Member::Method(m) => m.visibility,
+        }
+    }
+
+    pub fn is_static(&self) -> bool {
+        match self {
+            Member::Attribute(a) => a.is_static,
+            Member::Method(m) => m.is_static,
+        }
+    }
+
+    /// Same attribute name, or same method name and parameter types
+    pub fn same_signature(&self, other: &Member) -> bool {
+        match (self, other) {
+            (Member::Attribute(a), Member::Attribute(b)) => a.name == b.name,
+            (Member::Method(a), Member::Method(b)) => {
+                a.name == b.name
+                    && a.parameters.len() == b.parameters.len()
+                    && a.parameters.iter().zip(&b.parameters).all(|(x, y)| x.data_type == y.data_type)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Member declaration as written after `Class :` – `+$String name`,
+/// `-*area(int scale) : f64`
+impl fmt::Display for Member {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.visibility().symbol())?;
+        match self {
+            Member::Attribute(a) => {
+                if a.is_static {
+                    f.write_str("$")?;
+                }
+                if let Some(ty) = &a.data_type {
+                    write!(f, "{ty} ")?;
+                }
+                f.write_str(&a.name)
+            }
+            Member::Method(m) => {
+                if m.is_static {
+                    f.write_str("$")?;
+                }
+                if m.is_abstract {
+                    f.write_str("*")?;
+                }
+                write!(f, "{}(", m.name)?;
+                for (i, p) in m.parameters.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    if let Some(ty) = &p.data_type {
+                        write!(f, "{ty} ")?;
+                    }
+                    f.write_str(&p.name)?;
+                }
+                f.write_str(")")?;
+                if let Some(ty) = &m.return_type {
+                    write!(f, " : {ty}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
 /// Data that only an **attribute** has
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Attribute {
     pub visibility: Visibility,
     pub name: String,
@@ -53,6 +163,8 @@ pub struct Attribute {
 
 /// Data that only a **method** has
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Method {
     pub visibility: Visibility,
     pub name: String,
@@ -64,20 +176,51 @@ pub struct Method {
 
 /// A single class or interface in the diagram
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Class {
     pub name: String,                 // Fully-qualified (incl. namespace)
     pub generic: Option<String>,      // the “~T” from `Foo~T~`
     pub annotations: Vec<String>,     // <<interface>>, <<service>> …
     pub members: Vec<Member>,         // <── was Vec<ClassMember>
     pub namespace: String,            // DEFAULT_NAMESPACE if missing
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub implicit: bool,               // only named by a relation, never declared
+}
+
+impl Class {
+    /// Empty class; `namespace` is derived from the fully-qualified name
+    pub fn new(fq_name: &str) -> Self {
+        let (ns, _) = split_namespace(fq_name);
+        Class {
+            name: fq_name.to_owned(),
+            generic: None,
+            annotations: Vec::new(),
+            members: Vec::new(),
+            namespace: ns.to_owned(),
+            implicit: false,
+        }
+    }
 }
 
 /// Solid vs dotted line
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum LineStyle { Solid, Dotted }
 
 /// Mermaid’s five relation arrow-heads
+///
+/// Read with [`Relation`]’s `from` ➜ `to` direction:
+/// * `Extension` – `from` inherits from `to` (solid) or realizes it (dotted, `..|>`)
+/// * `Composition`/`Aggregation` – `to` is the whole, `from` the part
+/// * `Dependency` – `from` uses (dotted, `..>`) or is associated with (solid, `-->`, `--`) `to`
+/// * `Lollipop` – `from` provides the interface `to`
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum RelationKind {
     Aggregation,   // o--
     Composition,   // *--
@@ -87,7 +230,13 @@ pub enum RelationKind {
 }
 
 /// Edge between two classes
-#[derive(Debug, Clone)]
+///
+/// Normalised so that `to` is the end carrying the arrow head, whichever way
+/// the arrow was written: `Animal <|-- Duck` and `Duck --|> Animal` both give
+/// `from: "Duck", to: "Animal"`.  Head-less links (`--`) keep source order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Relation {
     pub from: String,            // fully-qualified class names
     pub to: String,
@@ -97,17 +246,241 @@ pub struct Relation {
     pub label_to: Option<String>,
 }
 
+impl Relation {
+    /// Mermaid arrow with the head on the left, e.g. `<|--` or `*--`
+    pub fn arrow(&self) -> &'static str {
+        match (self.kind, self.line) {
+            (RelationKind::Extension, LineStyle::Solid) => "<|--",
+            (RelationKind::Extension, LineStyle::Dotted) => "<|..",
+            (RelationKind::Composition, LineStyle::Solid) => "*--",
+            (RelationKind::Composition, LineStyle::Dotted) => "*..",
+            (RelationKind::Aggregation, LineStyle::Solid) => "o--",
+            (RelationKind::Aggregation, LineStyle::Dotted) => "o..",
+            (RelationKind::Dependency, LineStyle::Solid) => "<--",
+            (RelationKind::Dependency, LineStyle::Dotted) => "<..",
+            (RelationKind::Lollipop, LineStyle::Solid) => "()--",
+            (RelationKind::Lollipop, LineStyle::Dotted) => "()..",
+        }
+    }
+}
+
+/// Mermaid notation with the head end first: `Animal <|-- Duck`,
+/// `Order "1" *-- "many" Item`
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to)?;
+        if let Some(label) = &self.label_to {
+            write!(f, " \"{label}\"")?;
+        }
+        write!(f, " {} ", self.arrow())?;
+        if let Some(label) = &self.label_from {
+            write!(f, "\"{label}\" ")?;
+        }
+        f.write_str(&self.from)
+    }
+}
+
+/// `note "text"` or `note for Class "text"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct Note {
+    pub text: String,                // without the surrounding quotes
+    pub for_class: Option<String>,   // `None` for a free-floating note
+}
+
 /// Recursive namespace tree
+///
+/// `classes` and `children` iterate in the order they were first declared in
+/// the source.
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Namespace {
     pub name: String,
-    pub classes: HashMap<String, Class>,      // name ➜ class
-    pub children: HashMap<String, Namespace>, // nested namespaces
+    pub classes: IndexMap<String, Class>,      // name ➜ class
+    pub children: IndexMap<String, Namespace>, // nested namespaces
 }
 
 /// Whole diagram
+///
+/// Iteration is deterministic and follows source order: namespaces in the
+/// order their first class appears, classes in declaration order and
+/// relations in the order they are written.
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Diagram {
-    pub namespaces: HashMap<String, Namespace>,
+    pub namespaces: IndexMap<String, Namespace>,
     pub relations:  Vec<Relation>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub notes:      Vec<Note>,
+}
+
+impl Diagram {
+    /// Class by fully-qualified name, created (with its namespace) if missing
+    pub fn class_entry(&mut self, fq_name: &str) -> &mut Class {
+        class_entry(self, fq_name)
+    }
+
+    /// Explicitly declared class (`class X` or `X : member`), created if missing
+    pub fn declare_class(&mut self, fq_name: &str) -> &mut Class {
+        declare_class(self, fq_name)
+    }
+
+    /// Append a relation; ends that name no class yet become implicit classes,
+    /// just as Mermaid renders them.
+    pub fn add_relation(&mut self, relation: Relation) {
+        register_ends(self, [&relation.from, &relation.to]);
+        self.relations.push(relation);
+    }
+}
+
+/// Namespace and class storage shared by [`Diagram`] and
+/// [`borrowed::Diagram`](crate::borrowed::Diagram), keyed by owned names or by
+/// `'a` slices of the source; both register classes through the functions below.
+pub(crate) trait Model<'a> {
+    type Key: Hash + Eq + Borrow<str> + From<&'a str>;
+    type Namespace;
+    type Class;
+
+    fn namespaces_mut(&mut self) -> &mut IndexMap<Self::Key, Self::Namespace>;
+    fn new_namespace(name: &'a str) -> Self::Namespace;
+    fn classes_mut(namespace: &mut Self::Namespace) -> &mut IndexMap<Self::Key, Self::Class>;
+    fn new_class(fq_name: &'a str) -> Self::Class;
+    fn implicit_mut(class: &mut Self::Class) -> &mut bool;
+}
+
+impl<'a> Model<'a> for Diagram {
+    type Key = String;
+    type Namespace = Namespace;
+    type Class = Class;
+
+    fn namespaces_mut(&mut self) -> &mut IndexMap<String, Namespace> {
+        &mut self.namespaces
+    }
+
+    fn new_namespace(name: &str) -> Namespace {
+        Namespace { name: name.to_owned(), ..Default::default() }
+    }
+
+    fn classes_mut(namespace: &mut Namespace) -> &mut IndexMap<String, Class> {
+        &mut namespace.classes
+    }
+
+    fn new_class(fq_name: &str) -> Class {
+        Class::new(fq_name)
+    }
+
+    fn implicit_mut(class: &mut Class) -> &mut bool {
+        &mut class.implicit
+    }
+}
+
+/// See [`Diagram::class_entry`]
+pub(crate) fn class_entry<'m, 'a, M>(model: &'m mut M, fq_name: &'a str) -> &'m mut M::Class
+where
+    M: Model<'a, Key: 'm, Namespace: 'm, Class: 'm>,
+{
+    let (ns, name) = split_namespace(fq_name);
+    let namespace = model.namespaces_mut().entry(ns.into()).or_insert_with(|| M::new_namespace(ns));
+    M::classes_mut(namespace).entry(name.into()).or_insert_with(|| M::new_class(fq_name))
+}
+
+/// See [`Diagram::declare_class`]
+pub(crate) fn declare_class<'m, 'a, M>(model: &'m mut M, fq_name: &'a str) -> &'m mut M::Class
+where
+    M: Model<'a, Key: 'm, Namespace: 'm, Class: 'm>,
+{
+    let class = class_entry(model, fq_name);
+    *M::implicit_mut(class) = false;
+    class
+}
+
+/// Turn relation ends that name no class yet into implicit classes
+pub(crate) fn register_ends<'a, M: Model<'a>>(model: &mut M, ends: [&'a str; 2]) {
+    for end in ends {
+        let (ns, name) = split_namespace(end);
+        let known = model.namespaces_mut().get_mut(ns).is_some_and(|n| M::classes_mut(n).contains_key(name));
+        if !known {
+            *M::implicit_mut(class_entry(model, end)) = true;
+        }
+    }
+}
+
+/// Split `a::b::C` into (`a::b`, `C`); a bare name lives in [`DEFAULT_NAMESPACE`]
+pub fn split_namespace(fq: &str) -> (&str, &str) {
+    fq.rfind("::")
+        .map(|idx| (&fq[..idx], &fq[idx + 2..]))
+        .unwrap_or((DEFAULT_NAMESPACE, fq))
+}
+
+/// A [`Diagram`] tagged with the [`SCHEMA_VERSION`] it was written with.
+///
+/// This is the document format exchanged as JSON:
+///
+/// ```json
+/// {
+///   "schema_version": 1,
+///   "diagram": {
+///     "namespaces": {
+///       "": {
+///         "name": "",
+///         "classes": {
+///           "Duck": {
+///             "name": "Duck", "generic": null, "annotations": [], "namespace": "",
+///             "implicit": false,
+///             "members": [
+///               { "kind": "attribute", "visibility": "public", "name": "beak",
+///                 "data_type": "String", "is_static": false },
+///               { "kind": "method", "visibility": "unspecified", "name": "swim",
+///                 "parameters": [], "return_type": null,
+///                 "is_static": false, "is_abstract": false }
+///             ]
+///           }
+///         },
+///         "children": {}
+///       }
+///     },
+///     "relations": [
+///       { "from": "Duck", "to": "Animal", "kind": "extension", "line": "solid",
+///         "label_from": null, "label_to": null }
+///     ],
+///     "notes": [
+///       { "text": "can swim", "for_class": "Duck" }
+///     ]
+///   }
+/// }
+/// ```
+///
+/// Maps keep source order; enum values are lowercase variant names.
+#[cfg(feature = "serde")]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "json-schema",
+    schemars(description = "A diagram tagged with the schema version it was written with")
+)]
+pub struct VersionedDiagram {
+    pub schema_version: u32,
+    pub diagram: Diagram,
+}
+
+#[cfg(feature = "serde")]
+impl VersionedDiagram {
+    pub fn new(diagram: Diagram) -> Self {
+        VersionedDiagram {
+            schema_version: SCHEMA_VERSION,
+            diagram,
+        }
+    }
+
+    /// Unwrap the diagram, rejecting documents written for another schema version.
+    pub fn into_diagram(self) -> Result<Diagram, u32> {
+        if self.schema_version == SCHEMA_VERSION {
+            Ok(self.diagram)
+        } else {
+            Err(self.schema_version)
+        }
+    }
 }
@@ -0,0 +1,204 @@
+//! Visitor traits over the data model in [`types`](crate::types).
+//!
+//! Each `visit_*` method defaults to the matching `walk_*` function, which
+//! visits the node’s children.  Override the methods you care about and call
+//! `walk_*` from them to keep descending:
+//!
+//! ```
+//! use mermaid_parser::types::Method;
+//! use mermaid_parser::visit::{self, Visit};
+//!
+//! #[derive(Default)]
+//! struct CountMethods(usize);
+//!
+//! impl<'ast> Visit<'ast> for CountMethods {
+//!     fn visit_method(&mut self, method: &'ast Method) {
+//!         self.0 += 1;
+//!         visit::walk_method(self, method);
+//!     }
+//! }
+//!
+//! let diagram = mermaid_parser::parser::parse("classDiagram\nA : +run()\nB : +stop()\n").unwrap();
+//! let mut count = CountMethods::default();
+//! count.visit_diagram(&diagram);
+//! assert_eq!(count.0, 2);
+//! ```
+//!
+//! Namespaces are walked depth-first, classes before nested `children`.
+//! Every type written in the diagram – attribute, parameter and return
+//! types – also passes through `visit_type`.
+
+use crate::types::{Attribute, Class, Diagram, Member, Method, Namespace, Note, Parameter, Relation};
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Shared borrows
+// ────────────────────────────────────────────────────────────────────────────────
+
+pub trait Visit<'ast> {
+    fn visit_diagram(&mut self, diagram: &'ast Diagram) {
+        walk_diagram(self, diagram)
+    }
+    fn visit_namespace(&mut self, namespace: &'ast Namespace) {
+        walk_namespace(self, namespace)
+    }
+    fn visit_class(&mut self, class: &'ast Class) {
+        walk_class(self, class)
+    }
+    fn visit_member(&mut self, member: &'ast Member) {
+        walk_member(self, member)
+    }
+    fn visit_attribute(&mut self, attribute: &'ast Attribute) {
+        walk_attribute(self, attribute)
+    }
+    fn visit_method(&mut self, method: &'ast Method) {
+        walk_method(self, method)
+    }
+    fn visit_parameter(&mut self, parameter: &'ast Parameter) {
+        walk_parameter(self, parameter)
+    }
+    fn visit_type(&mut self, _ty: &'ast str) {}
+    fn visit_relation(&mut self, _relation: &'ast Relation) {}
+    fn visit_note(&mut self, _note: &'ast Note) {}
+}
+
+pub fn walk_diagram<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, diagram: &'ast Diagram) {
+    for namespace in diagram.namespaces.values() {
+        v.visit_namespace(namespace);
+    }
+    for relation in &diagram.relations {
+        v.visit_relation(relation);
+    }
+    for note in &diagram.notes {
+        v.visit_note(note);
+    }
+}
+
+pub fn walk_namespace<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, namespace: &'ast Namespace) {
+    for class in namespace.classes.values() {
+        v.visit_class(class);
+    }
+    for child in namespace.children.values() {
+        v.visit_namespace(child);
+    }
+}
+
+pub fn walk_class<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, class: &'ast Class) {
+    for member in &class.members {
+        v.visit_member(member);
+    }
+}
+
+pub fn walk_member<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, member: &'ast Member) {
+    match member {
+        Member::Attribute(a) => v.visit_attribute(a),
+        Member::Method(m) => v.visit_method(m),
+    }
+}
+
+pub fn walk_attribute<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, attribute: &'ast Attribute) {
+    if let Some(ty) = &attribute.data_type {
+        v.visit_type(ty);
+    }
+}
+
+pub fn walk_method<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, method: &'ast Method) {
+    for parameter in &method.parameters {
+        v.visit_parameter(parameter);
+    }
+    if let Some(ty) = &method.return_type {
+        v.visit_type(ty);
+    }
+}
+
+pub fn walk_parameter<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, parameter: &'ast Parameter) {
+    if let Some(ty) = &parameter.data_type {
+        v.visit_type(ty);
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────────
+// Mutable borrows
+// ────────────────────────────────────────────────────────────────────────────────
+
+/// In-place transformation; same shape as [`Visit`]
+pub trait VisitMut {
+    fn visit_diagram_mut(&mut self, diagram: &mut Diagram) {
+        walk_diagram_mut(self, diagram)
+    }
+    fn visit_namespace_mut(&mut self, namespace: &mut Namespace) {
+        walk_namespace_mut(self, namespace)
+    }
+    fn visit_class_mut(&mut self, class: &mut Class) {
+        walk_class_mut(self, class)
+    }
+    fn visit_member_mut(&mut self, member: &mut Member) {
+        walk_member_mut(self, member)
+    }
+    fn visit_attribute_mut(&mut self, attribute: &mut Attribute) {
+        walk_attribute_mut(self, attribute)
+    }
+    fn visit_method_mut(&mut self, method: &mut Method) {
+        walk_method_mut(self, method)
+    }
+    fn visit_parameter_mut(&mut self, parameter: &mut Parameter) {
+        walk_parameter_mut(self, parameter)
+    }
+    fn visit_type_mut(&mut self, _ty: &mut String) {}
+    fn visit_relation_mut(&mut self, _relation: &mut Relation) {}
+    fn visit_note_mut(&mut self, _note: &mut Note) {}
+}
+
+pub fn walk_diagram_mut<V: VisitMut + ?Sized>(v: &mut V, diagram: &mut Diagram) {
+    for namespace in diagram.namespaces.values_mut() {
+        v.visit_namespace_mut(namespace);
+    }
+    for relation in &mut diagram.relations {
+        v.visit_relation_mut(relation);
+    }
+    for note in &mut diagram.notes {
+        v.visit_note_mut(note);
+    }
+}
+
+pub fn walk_namespace_mut<V: VisitMut + ?Sized>(v: &mut V, namespace: &mut Namespace) {
+    for class in namespace.classes.values_mut() {
+        v.visit_class_mut(class);
+    }
+    for child in namespace.children.values_mut() {
+        v.visit_namespace_mut(child);
+    }
+}
+
+pub fn walk_class_mut<V: VisitMut + ?Sized>(v: &mut V, class: &mut Class) {
+    for member in &mut class.members {
+        v.visit_member_mut(member);
+    }
+}
+
+pub fn walk_member_mut<V: VisitMut + ?Sized>(v: &mut V, member: &mut Member) {
+    match member {
+        Member::Attribute(a) => v.visit_attribute_mut(a),
+        Member::Method(m) => v.visit_method_mut(m),
+    }
+}
+
+pub fn walk_attribute_mut<V: VisitMut + ?Sized>(v: &mut V, attribute: &mut Attribute) {
+    if let Some(ty) = &mut attribute.data_type {
+        v.visit_type_mut(ty);
+    }
+}
+
+pub fn walk_method_mut<V: VisitMut + ?Sized>(v: &mut V, method: &mut Method) {
+    for parameter in &mut method.parameters {
+        v.visit_parameter_mut(parameter);
+    }
+    if let Some(ty) = &mut method.return_type {
+        v.visit_type_mut(ty);
+    }
+}
+
+pub fn walk_parameter_mut<V: VisitMut + ?Sized>(v: &mut V, parameter: &mut Parameter) {
+    if let Some(ty) = &mut parameter.data_type {
+        v.visit_type_mut(ty);
+    }
+}
@@ -0,0 +1,56 @@
+// tests/borrowed.rs
+//! The borrowed model agrees with the owned one and points into the source.
+
+use mermaid_parser::borrowed::Member;
+use mermaid_parser::diff::diff;
+use mermaid_parser::parser::{parse, parse_borrowed};
+use mermaid_parser::types;
+
+const SRC: &str = r#"classDiagram
+class Order
+Order : -int id
+Order : +total(String currency) : Money
+Order *-- Item
+Customer --> Order
+note for Order "placed by a customer"
+"#;
+
+/// `true` if `s` lies inside `src`
+fn is_slice_of(s: &str, src: &str) -> bool {
+    let range = src.as_bytes().as_ptr_range();
+    range.contains(&s.as_ptr()) && s.len() <= src.len()
+}
+
+#[test]
+fn into_owned_matches_parse() {
+    let owned = parse(SRC).unwrap();
+    let borrowed: types::Diagram = parse_borrowed(SRC).unwrap().into_owned();
+
+    assert!(diff(&owned, &borrowed).is_empty());
+    assert_eq!(owned.relations, borrowed.relations);
+    assert_eq!(owned.notes, borrowed.notes);
+    let names = |d: &types::Diagram| d.classes().map(|c| c.name.clone()).collect::<Vec<_>>();
+    assert_eq!(names(&owned), names(&borrowed));
+}
+
+#[test]
+fn strings_are_slices_of_the_source() {
+    let diagram = parse_borrowed(SRC).unwrap();
+
+    let order = &diagram.namespaces[""].classes["Order"];
+    assert!(is_slice_of(order.name, SRC));
+    let Member::Method(total) = &order.members[1] else {
+        panic!("expected a method, got {:?}", order.members[1]);
+    };
+    assert_eq!(total.name, "total");
+    assert!(is_slice_of(total.name, SRC));
+    assert!(is_slice_of(total.parameters[0].data_type.unwrap(), SRC));
+    assert!(is_slice_of(total.return_type.unwrap(), SRC));
+
+    let relation = &diagram.relations[0];
+    assert_eq!((relation.from, relation.to), ("Item", "Order"));
+    assert!(is_slice_of(relation.from, SRC));
+
+    assert_eq!(diagram.notes[0].text, "placed by a customer");
+    assert!(is_slice_of(diagram.notes[0].text, SRC));
+}
@@ -0,0 +1,66 @@
+// tests/builder.rs
+//! Diagrams built in code must look exactly like parsed ones.
+
+use mermaid_parser::builder::BuildError;
+use mermaid_parser::types::{Diagram, Member, RelationKind, Visibility, DEFAULT_NAMESPACE};
+
+#[test]
+fn builder_matches_parser() {
+    let parsed = mermaid_parser::parser::parse(include_str!("./mermaid/test.mmd")).unwrap();
+    let built = Diagram::builder()
+        .class("BankAccount", |c| {
+            c.method(Visibility::Public, "withdrawal", |m| m.untyped_param("amount"))
+                .method(Visibility::Private, "withdrawal", |m| {
+                    m.untyped_param("amount").untyped_param("abc").untyped_param("qwer")
+                })
+                .attr(Visibility::Package, "testo", "String")
+        })
+        .build().unwrap();
+
+    let parsed = &parsed.namespaces[DEFAULT_NAMESPACE].classes["BankAccount"];
+    let built = &built.namespaces[DEFAULT_NAMESPACE].classes["BankAccount"];
+    assert_eq!(parsed.members, built.members);
+    assert_eq!(parsed.namespace, built.namespace);
+}
+
+#[test]
+fn namespaced_classes_stay_consistent() {
+    let diagram = Diagram::builder()
+        .class("a::b::Shape", |c| c.annotation("interface").generic("T"))
+        .class("a::b::Shape", |c| {
+            c.method(Visibility::Public, "area", |m| m.returns("f64").is_abstract())
+        })
+        .class("Circle", |c| c.static_attr(Visibility::Public, "PI", "f64"))
+        .relation("Circle", RelationKind::Extension, "a::b::Shape")
+        .note_for("Circle", "round")
+        .build().unwrap();
+
+    let ns = &diagram.namespaces["a::b"];
+    assert_eq!(ns.name, "a::b");
+    let shape = &ns.classes["Shape"];
+    assert_eq!(shape.name, "a::b::Shape");
+    assert_eq!(shape.namespace, "a::b");
+    assert_eq!(shape.annotations, ["interface"]);
+    assert!(matches!(&shape.members[..], [Member::Method(m)] if m.is_abstract));
+
+    assert!(diagram.namespaces[DEFAULT_NAMESPACE].classes.contains_key("Circle"));
+    assert_eq!(diagram.relations.len(), 1);
+    assert_eq!(diagram.notes[0].for_class.as_deref(), Some("Circle"));
+}
+
+#[test]
+fn invalid_class_names_fail_the_build() {
+    for name in ["", "a b", "x::", "::X", "Order~T~"] {
+        let err = Diagram::builder().class(name, |c| c).build().unwrap_err();
+        assert_eq!(err, BuildError::InvalidClassName(name.to_owned()));
+    }
+
+    // the first bad name wins, wherever it was given
+    let err = Diagram::builder()
+        .class("shop::Order", |c| c)
+        .relation("shop::Order", RelationKind::Composition, "Line Item")
+        .note_for("1st", "x")
+        .build()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "`Line Item` is not a valid class name");
+}
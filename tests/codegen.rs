@@ -0,0 +1,688 @@
+// tests/codegen.rs
+//! Code generation: one section per target language.
+//!
+//! Generated Rust is type-checked with the compiler running the tests.
+//! Checks needing another toolchain (`tsc`, `javac`, `kotlinc`, `python3`
+//! with Pydantic) are ignored by default and fail if the tool is missing; run
+//! them with `cargo test -- --include-ignored`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use indexmap::IndexMap;
+use mermaid_parser::codegen::TypeMap;
+use mermaid_parser::codegen::python::{PythonOptions, PythonStyle};
+use mermaid_parser::codegen::rust::{Aggregation, RustOptions};
+use mermaid_parser::codegen::typescript::{self, TypeScriptOptions};
+use mermaid_parser::types::{Diagram, LineStyle, Relation, RelationKind, Visibility};
+use pretty_assertions::assert_eq;
+
+/// Shapes on a canvas: an interface, a realization, an enumeration, a
+/// generic, namespaces and every kind of whole/part relation
+fn shapes() -> Diagram {
+    Diagram::builder()
+        .class("geo::Shape", |c| {
+            c.annotation("interface")
+                .attr(Visibility::Public, "name", "String")
+                .method(Visibility::Public, "area", |m| m.returns("double"))
+        })
+        .class("geo::Circle", |c| {
+            c.attr(Visibility::Private, "radius", "double")
+                .static_attr(Visibility::Public, "PI", "double")
+                .method(Visibility::Public, "area", |m| m.returns("double"))
+                .method(Visibility::Package, "scaleBy", |m| m.param("factor", "int").param("type", "String"))
+        })
+        .class("geo::Color", |c| {
+            c.annotation("enumeration")
+                .untyped_attr(Visibility::Unspecified, "RED")
+                .untyped_attr(Visibility::Unspecified, "DARK_GREEN")
+        })
+        .class("Canvas", |c| {
+            c.attr(Visibility::Public, "title", "String")
+                .attr(Visibility::Protected, "grid", "int[][]")
+                .attr(Visibility::Private, "tags", "List~String~")
+                .method(Visibility::Public, "render", |m| m.param("scale", "float").returns("boolean"))
+                .method(Visibility::Public, "create", |m| m.is_static().returns("Canvas"))
+        })
+        .class("Stack", |c| c.generic("T").method(Visibility::Public, "push", |m| m.param("item", "T")))
+        .class("Element", |c| c.attr(Visibility::Public, "id", "long"))
+        .class("Layer", |c| c.attr(Visibility::Public, "depth", "int"))
+        .relation("Canvas", RelationKind::Extension, "Element")
+        .add_relation(Relation {
+            from: "geo::Circle".into(),
+            to: "geo::Shape".into(),
+            kind: RelationKind::Extension,
+            line: LineStyle::Dotted,
+            label_from: None,
+            label_to: None,
+        })
+        .add_relation(Relation {
+            from: "geo::Circle".into(),
+            to: "Canvas".into(),
+            kind: RelationKind::Composition,
+            line: LineStyle::Solid,
+            label_from: Some("*".into()),
+            label_to: Some("1".into()),
+        })
+        .relation("Layer", RelationKind::Aggregation, "Canvas")
+        .build().unwrap()
+}
+
+/// Type-check generated Rust with the compiler that runs the tests
+fn compiles(name: &str, source: &str) {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join(format!("{name}.rs"));
+    std::fs::write(&file, source).unwrap();
+    let output = Command::new(std::env::var("RUSTC").unwrap_or_else(|_| "rustc".into()))
+        .args(["--edition", "2021", "--crate-type", "lib", "--emit", "metadata", "-A", "warnings", "--out-dir"])
+        .arg(&dir)
+        .arg(&file)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{source}\n{}", String::from_utf8_lossy(&output.stderr));
+}
+
+/// Write generated files to a fresh directory named after the check
+fn write_sources(name: &str, files: &IndexMap<PathBuf, String>) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("codegen").join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    for (path, source) in files {
+        let file = dir.join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, source).unwrap();
+    }
+    dir
+}
+
+/// Run a toolchain over generated files in `dir`; a missing tool is a failure
+fn toolchain(files: &IndexMap<PathBuf, String>, dir: &Path, command: &mut Command) {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command.current_dir(dir).output().unwrap_or_else(|e| panic!("cannot run {program}: {e}"));
+    let all: Vec<&str> = files.values().map(String::as_str).collect();
+    assert!(output.status.success(), "{}\n{}", all.join("\n"), String::from_utf8_lossy(&output.stderr));
+}
+
+// ── Rust ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn rust_items() {
+    let rust = shapes().to_rust();
+    for expected in [
+        "pub struct Canvas {\n    element: Element,\n    pub title: String,\n    pub(crate) grid: Vec<Vec<i32>>,\n    tags: Vec<String>,\n    circles: Vec<geo::Circle>,\n    layer: std::rc::Rc<Layer>,\n}",
+        "    pub fn render(&self, scale: f32) -> bool {\n        todo!()\n    }",
+        "    pub fn create() -> Self {",
+        "pub struct Stack<T> {\n    _marker: std::marker::PhantomData<(T,)>,\n}",
+        "impl<T> Stack<T> {\n    pub fn push(&self, item: T) {",
+        "pub mod geo {\n    pub trait Shape {\n        fn name(&self) -> String;\n        fn area(&self) -> f64;\n    }",
+        "        pub const PI: f64 = todo!();",
+        "        pub(crate) fn scale_by(&self, factor: i32, r#type: String) {",
+        "    impl Shape for Circle {\n        fn name(&self) -> String {\n            todo!()\n        }\n\n        fn area(&self) -> f64 {",
+        "    pub enum Color {\n        Red,\n        DarkGreen,\n    }",
+    ] {
+        assert!(rust.contains(expected), "missing\n{expected}\nin\n{rust}");
+    }
+    // `area` is implemented for the trait, not again as an inherent method
+    assert_eq!(rust.matches("fn area").count(), 2);
+    compiles("rust_items", &rust);
+}
+
+#[test]
+fn rust_borrowed_aggregation_adds_a_lifetime() {
+    let options = RustOptions { aggregation: Aggregation::Borrow, ..RustOptions::default() };
+    let rust = shapes().to_rust_with(&options);
+    assert!(rust.contains("pub struct Canvas<'a> {"));
+    assert!(rust.contains("    layer: &'a Layer,\n"));
+    assert!(rust.contains("impl<'a> Canvas<'a> {"));
+    compiles("rust_borrowed", &rust);
+
+    // `A o-- B`, `B o-- C`: `A` borrows a `B` that itself borrows
+    let mut diagram = shapes();
+    diagram.add_relation(Relation {
+        from: "B".into(),
+        to: "A".into(),
+        kind: RelationKind::Aggregation,
+        line: LineStyle::Solid,
+        label_from: None,
+        label_to: None,
+    });
+    diagram.add_relation(Relation {
+        from: "C".into(),
+        to: "B".into(),
+        kind: RelationKind::Aggregation,
+        line: LineStyle::Solid,
+        label_from: None,
+        label_to: None,
+    });
+    let rust = diagram.to_rust_with(&options);
+    assert!(rust.contains("pub struct A<'a> {\n    b: &'a B<'a>,\n}"));
+    assert!(rust.contains("pub struct B<'a> {\n    c: &'a C,\n}"));
+    compiles("rust_borrowed_chain", &rust);
+}
+
+#[test]
+fn rust_recursive_fields_are_boxed() {
+    let diagram = Diagram::builder()
+        .class("Node", |c| c.attr(Visibility::Public, "next", "Node").attr(Visibility::Public, "children", "List~Node~"))
+        .class("Parent", |c| c.attr(Visibility::Public, "child", "Optional~Child~"))
+        .class("Child", |c| c.attr(Visibility::Public, "parent", "Parent"))
+        .add_relation(Relation {
+            from: "Tree".into(),
+            to: "Tree".into(),
+            kind: RelationKind::Composition,
+            line: LineStyle::Solid,
+            label_from: Some("0..1".into()),
+            label_to: None,
+        })
+        .build().unwrap();
+    let rust = diagram.to_rust();
+    for expected in [
+        "pub struct Node {\n    pub next: Box<Self>,\n    pub children: Vec<Self>,\n}",
+        "pub struct Tree {\n    tree: Option<Box<Self>>,\n}",
+        "pub struct Parent {\n    pub child: Option<Box<Child>>,\n}",
+        "pub struct Child {\n    pub parent: Box<Parent>,\n}",
+    ] {
+        assert!(rust.contains(expected), "missing\n{expected}\nin\n{rust}");
+    }
+    compiles("rust_recursive", &rust);
+}
+
+#[test]
+fn rust_traits_stay_dyn_compatible() {
+    let diagram = Diagram::builder()
+        .class("Shape", |c| {
+            c.annotation("interface")
+                .static_attr(Visibility::Public, "SIDES", "int")
+                .method(Visibility::Public, "unit", |m| m.is_static().returns("Shape"))
+                .method(Visibility::Public, "area", |m| m.returns("double"))
+        })
+        .class("Square", |c| c)
+        .class("Drawing", |c| c.attr(Visibility::Public, "shape", "Shape"))
+        .add_relation(Relation {
+            from: "Square".into(),
+            to: "Shape".into(),
+            kind: RelationKind::Extension,
+            line: LineStyle::Dotted,
+            label_from: None,
+            label_to: None,
+        })
+        .build().unwrap();
+    let rust = diagram.to_rust();
+    assert!(rust.contains("    fn sides() -> i32 where Self: Sized;\n"));
+    assert!(rust.contains("    fn unit() -> Box<dyn Shape> where Self: Sized;\n"));
+    assert!(rust.contains("    fn area(&self) -> f64;\n"));
+    assert!(rust.contains("pub shape: Box<dyn Shape>,"));
+    compiles("rust_dyn_compatible", &rust);
+}
+
+#[test]
+fn rust_type_map_is_configurable() {
+    let diagram = Diagram::builder()
+        .class("Money", |c| c.attr(Visibility::Public, "amount", "Decimal").attr(Visibility::Public, "history", "List~Map~String,int~~"))
+        .build().unwrap();
+    let mut options = RustOptions::default();
+    options.types.insert("Decimal", "u128");
+    options.types.insert("String", "Box<str>");
+    let rust = diagram.to_rust_with(&options);
+    assert!(rust.contains("pub amount: u128,"));
+    assert!(rust.contains("pub history: Vec<std::collections::HashMap<Box<str>, i32>>,"));
+
+    let bare = RustOptions { types: TypeMap::new(), ..RustOptions::default() };
+    assert!(diagram.to_rust_with(&bare).contains("pub history: List<Map<String, int>>,"));
+}
+
+// ── TypeScript ──────────────────────────────────────────────────────────────
+
+#[test]
+fn typescript_items() {
+    let ts = shapes().to_typescript();
+    for expected in [
+        "export class Canvas extends Element {\n    public title!: string;\n    protected grid!: number[][];\n    private tags!: Array<string>;\n    circles!: geo.Circle[];\n    layer!: Layer;\n",
+        "    public render(scale: number): boolean {\n        throw new Error(\"not implemented\");\n    }",
+        "    public static create(): Canvas {",
+        "export class Stack<T> {\n    public push(item: T): void {",
+        "export namespace geo {\n    export interface Shape {\n        name: string;\n        area(): number;\n    }",
+        "    export class Circle implements Shape {\n        private radius!: number;\n        public static PI: number;\n        public name!: string;\n",
+        "        scaleBy(factor: number, type: string): void {",
+        "    export enum Color {\n        RED,\n        DARK_GREEN,\n    }",
+    ] {
+        assert!(ts.contains(expected), "missing\n{expected}\nin\n{ts}");
+    }
+    // a base class is declared before the class extending it
+    assert!(ts.find("export class Element").unwrap() < ts.find("export class Canvas").unwrap());
+}
+
+fn polygons() -> Diagram {
+    Diagram::builder()
+        .class("Named", |c| c.annotation("interface").method(Visibility::Public, "name", |m| m.returns("String")))
+        .class("Shape", |c| c.annotation("interface").method(Visibility::Public, "area", |m| m.returns("double")))
+        .class("Polygon", |c| {
+            c.generic("P")
+                .attr(Visibility::Protected, "points", "List~P~")
+                .method(Visibility::Public, "sides", |m| m.is_abstract().returns("int"))
+        })
+        .relation("Shape", RelationKind::Extension, "Named")
+        .add_relation(Relation {
+            from: "Polygon".into(),
+            to: "Shape".into(),
+            kind: RelationKind::Extension,
+            line: LineStyle::Dotted,
+            label_from: None,
+            label_to: None,
+        })
+        .build().unwrap()
+}
+
+#[test]
+fn typescript_abstract_and_interface_inheritance() {
+    let ts = polygons().to_typescript();
+    assert!(ts.contains("export interface Shape extends Named {"));
+    assert!(ts.contains("export abstract class Polygon<P> implements Shape {\n    protected points!: Array<P>;\n"));
+    assert!(ts.contains("    public abstract sides(): number;\n"));
+    // members of both interfaces, the inherited one included
+    assert!(ts.contains("    public area(): number {"));
+    assert!(ts.contains("    public name(): string {"));
+}
+
+fn widgets() -> Diagram {
+    Diagram::builder()
+        .class("Main", |c| c)
+        .class("app::Button", |c| c)
+        .class("ui::Widget", |c| c)
+        .class("ui::form::Field", |c| c)
+        .class("Base", |c| c)
+        .relation("Main", RelationKind::Extension, "app::Button")
+        .relation("app::Button", RelationKind::Extension, "ui::form::Field")
+        .relation("ui::Widget", RelationKind::Extension, "Base")
+        .build().unwrap()
+}
+
+#[test]
+fn typescript_namespaces_follow_their_bases() {
+    let ts = widgets().to_typescript();
+    let at = |item: &str| ts.find(item).unwrap_or_else(|| panic!("missing {item} in\n{ts}"));
+    // `ui` holds the base of `app::Button`, `app` that of `Main`, and `Base`
+    // is needed inside `ui`
+    assert!(at("export class Base") < at("export namespace ui"));
+    assert!(at("export namespace ui") < at("export namespace app"));
+    assert!(at("export namespace app") < at("export class Main"));
+    // within `ui`, the nested namespace still follows the classes it does not need
+    assert!(at("export class Widget") < at("export namespace form"));
+}
+
+#[test]
+fn typescript_inherited_abstract_methods_and_generic_interfaces() {
+    let ts = inherited().to_typescript();
+    assert!(ts.contains("export class Dog extends Animal {\n    public makeSound(): void {\n"));
+    assert!(ts.contains("export class Puppy extends Dog {\n}"));
+    assert!(ts.contains("export class UserRepo<T> implements Repo<T> {\n    public get(id: number): T {\n"));
+}
+
+/// Type-check generated TypeScript in strict mode
+fn tsc(name: &str, source: &str) {
+    let files = IndexMap::from([(PathBuf::from("index.ts"), source.to_owned())]);
+    let dir = write_sources(name, &files);
+    toolchain(&files, &dir, Command::new("tsc").args(["--strict", "--noEmit", "--target", "es2022", "index.ts"]));
+}
+
+#[test]
+#[ignore = "needs tsc"]
+fn typescript_compiles() {
+    tsc("typescript_shapes", &shapes().to_typescript());
+    tsc("typescript_polygons", &polygons().to_typescript());
+    tsc("typescript_widgets", &widgets().to_typescript());
+    tsc("typescript_inherited", &inherited().to_typescript());
+}
+
+#[test]
+fn typescript_type_map_is_configurable() {
+    let diagram = Diagram::builder()
+        .class("Money", |c| c.attr(Visibility::Public, "amount", "Decimal").untyped_attr(Visibility::Public, "note"))
+        .build().unwrap();
+    let options = TypeScriptOptions { types: typescript::default_types().with("Decimal", "bigint") };
+    let ts = diagram.to_typescript_with(&options);
+    assert!(ts.contains("public amount!: bigint;"));
+    assert!(ts.contains("public note!: unknown;"));
+}
+
+// ── Java ────────────────────────────────────────────────────────────────────
+
+fn javac(name: &str, files: &IndexMap<PathBuf, String>) {
+    let dir = write_sources(name, files);
+    toolchain(files, &dir, Command::new("javac").args(["-d", "classes"]).args(files.keys()));
+}
+
+#[test]
+fn java_one_file_per_class() {
+    let files = shapes().to_java();
+    let paths: Vec<&str> = files.keys().map(|p| p.to_str().unwrap()).collect();
+    assert_eq!(
+        paths,
+        ["geo/Shape.java", "geo/Circle.java", "geo/Color.java", "Canvas.java", "Stack.java", "Element.java", "Layer.java"]
+    );
+
+    let canvas = &files[Path::new("Canvas.java")];
+    assert!(canvas.contains("\nimport geo.Circle;\nimport java.util.List;\n\npublic class Canvas extends Element {\n    public String title;\n    protected int[][] grid;\n    private List<String> tags;\n    private List<Circle> circles;\n    private Layer layer;\n"));
+    assert!(canvas.contains("    public static Canvas create() {\n        throw new UnsupportedOperationException(\"not implemented\");\n    }"));
+
+    let shape = &files[Path::new("geo/Shape.java")];
+    assert!(shape.contains("package geo;\n\npublic interface Shape {\n    String getName();\n    double area();\n}"));
+
+    let circle = &files[Path::new("geo/Circle.java")];
+    assert!(circle.contains("public class Circle implements Shape {\n    private double radius;\n    public static double PI;\n    private String name;\n"));
+    assert!(circle.contains("    @Override\n    public double area() {"));
+    assert!(circle.contains("    void scaleBy(int factor, String type) {"));
+    assert!(circle.contains("    @Override\n    public String getName() {\n        return name;\n    }"));
+
+    assert!(files[Path::new("geo/Color.java")].contains("public enum Color {\n    RED,\n    DARK_GREEN\n}"));
+    assert!(files[Path::new("Stack.java")].contains("public class Stack<T> {\n    public void push(T item) {"));
+}
+
+fn model() -> Diagram {
+    Diagram::builder()
+        .class("model::Named", |c| c.annotation("interface").method(Visibility::Public, "label", |m| m.returns("String")))
+        .class("model::Entity", |c| {
+            c.attr(Visibility::Protected, "scores", "Map~String,int~")
+                .method(Visibility::Public, "validate", |m| m.is_abstract().returns("boolean"))
+        })
+        .class("model::Status", |c| {
+            c.annotation("enumeration")
+                .untyped_attr(Visibility::Unspecified, "ACTIVE")
+                .method(Visibility::Public, "isFinal", |m| m.returns("boolean"))
+        })
+        .class("model::User", |c| c.attr(Visibility::Private, "new", "Status").method(Visibility::Public, "validate", |m| m.returns("boolean")))
+        .relation("model::Entity", RelationKind::Extension, "model::Named")
+        .relation("model::User", RelationKind::Extension, "model::Entity")
+        .build().unwrap()
+}
+
+#[test]
+fn java_abstract_generic_and_boxed() {
+    let files = model().to_java();
+    let entity = &files[Path::new("model/Entity.java")];
+    assert!(entity.contains("import java.util.Map;\n\npublic abstract class Entity implements Named {\n    protected Map<String, Integer> scores;\n"));
+    assert!(entity.contains("    public abstract boolean validate();\n"));
+    assert!(entity.contains("    @Override\n    public String label() {"));
+    let user = &files[Path::new("model/User.java")];
+    assert!(user.contains("public class User extends Entity {\n    private Status new_;\n\n    @Override\n    public boolean validate() {"));
+    assert!(files[Path::new("model/Status.java")].contains("public enum Status {\n    ACTIVE;\n\n    public boolean isFinal() {"));
+}
+
+/// `Dog` extends an abstract `Animal`, `UserRepo` realizes a generic `Repo`
+fn inherited() -> Diagram {
+    Diagram::builder()
+        .class("Animal", |c| c.method(Visibility::Public, "makeSound", |m| m.is_abstract()).method(Visibility::Public, "eat", |m| m))
+        .class("Dog", |c| c)
+        .class("Puppy", |c| c)
+        .class("Repo", |c| {
+            c.annotation("interface").generic("T").method(Visibility::Public, "get", |m| m.param("id", "int").returns("T"))
+        })
+        .class("UserRepo", |c| c)
+        .relation("Dog", RelationKind::Extension, "Animal")
+        .relation("Puppy", RelationKind::Extension, "Dog")
+        .add_relation(Relation {
+            from: "UserRepo".into(),
+            to: "Repo".into(),
+            kind: RelationKind::Extension,
+            line: LineStyle::Dotted,
+            label_from: None,
+            label_to: None,
+        })
+        .build().unwrap()
+}
+
+#[test]
+fn java_inherited_abstract_methods_and_generic_interfaces() {
+    let files = inherited().to_java();
+    let dog = &files[Path::new("Dog.java")];
+    assert!(dog.contains("public class Dog extends Animal {\n    @Override\n    public void makeSound() {\n"));
+    assert!(!dog.contains("eat"));
+    // implemented by `Dog` already
+    assert!(files[Path::new("Puppy.java")].contains("public class Puppy extends Dog {\n}"));
+    let repo = &files[Path::new("UserRepo.java")];
+    assert!(repo.contains("public class UserRepo<T> implements Repo<T> {\n    @Override\n    public T get(int id) {\n"));
+}
+
+#[test]
+#[ignore = "needs javac"]
+fn java_compiles() {
+    javac("java_shapes", &shapes().to_java());
+    javac("java_model", &model().to_java());
+    javac("java_inherited", &inherited().to_java());
+}
+
+// ── Kotlin ──────────────────────────────────────────────────────────────────
+
+fn kotlinc(name: &str, files: &IndexMap<PathBuf, String>) {
+    let dir = write_sources(name, files);
+    toolchain(files, &dir, Command::new("kotlinc").args(files.keys()).args(["-d", "classes"]));
+}
+
+#[test]
+fn kotlin_one_file_per_class() {
+    let files = shapes().to_kotlin();
+    assert_eq!(files.len(), 7);
+
+    let canvas = &files[Path::new("Canvas.kt")];
+    assert!(canvas.contains("\nimport geo.Circle\n\nclass Canvas(\n    id: Long,\n    var title: String,\n    protected var grid: Array<Array<Int>>,\n    private var tags: List<String>,\n    private var circles: List<Circle> = emptyList(),\n    private var layer: Layer,\n) : Element(id) {\n"));
+    assert!(canvas.contains("    companion object {\n        fun create(): Canvas {\n            TODO(\"not implemented\")\n        }\n    }\n}"));
+    assert!(files[Path::new("Element.kt")].contains("open class Element(\n    var id: Long,\n)\n"));
+
+    let shape = &files[Path::new("geo/Shape.kt")];
+    assert!(shape.contains("package geo\n\ninterface Shape {\n    val name: String\n    fun area(): Double\n}"));
+
+    let circle = &files[Path::new("geo/Circle.kt")];
+    assert!(circle.contains("class Circle(\n    private var radius: Double,\n    override var name: String,\n) : Shape {\n    override fun area(): Double {"));
+    assert!(circle.contains("    internal fun scaleBy(factor: Int, type: String) {"));
+    assert!(circle.contains("    companion object {\n        val PI: Double\n            get() = TODO(\"not implemented\")\n    }"));
+
+    assert!(files[Path::new("geo/Color.kt")].contains("enum class Color {\n    RED,\n    DARK_GREEN,\n}"));
+    assert!(files[Path::new("Stack.kt")].contains("class Stack<T> {\n    fun push(item: T) {"));
+}
+
+fn entities() -> Diagram {
+    Diagram::builder()
+        .class("Entity", |c| {
+            c.attr(Visibility::Package, "id", "long")
+                .method(Visibility::Public, "validate", |m| m.is_abstract().returns("boolean"))
+                .method(Visibility::Public, "describe", |m| m.returns("String"))
+        })
+        .class("User", |c| c.attr(Visibility::Public, "in", "String").method(Visibility::Public, "validate", |m| m.returns("boolean")))
+        .class("Admin", |c| c.untyped_attr(Visibility::Public, "level"))
+        .relation("User", RelationKind::Extension, "Entity")
+        .relation("Admin", RelationKind::Extension, "User")
+        .build().unwrap()
+}
+
+#[test]
+fn kotlin_open_abstract_and_overrides() {
+    let files = entities().to_kotlin();
+    let entity = &files[Path::new("Entity.kt")];
+    assert!(entity.contains("abstract class Entity(\n    internal var id: Long,\n) {\n    abstract fun validate(): Boolean\n\n    open fun describe(): String {"));
+    let user = &files[Path::new("User.kt")];
+    assert!(user.contains("open class User(\n    id: Long,\n    var `in`: String,\n) : Entity(id) {\n    override fun validate(): Boolean {"));
+    // the whole chain is passed on
+    let admin = &files[Path::new("Admin.kt")];
+    assert!(admin.contains("class Admin(\n    id: Long,\n    `in`: String,\n    var level: Any?,\n) : User(id, `in`)\n"));
+}
+
+#[test]
+fn kotlin_inherited_abstract_methods_and_generic_interfaces() {
+    let files = inherited().to_kotlin();
+    assert!(files[Path::new("Dog.kt")].contains("open class Dog : Animal() {\n    override fun makeSound() {\n"));
+    assert!(files[Path::new("Puppy.kt")].contains("class Puppy : Dog()\n"));
+    assert!(files[Path::new("UserRepo.kt")].contains("class UserRepo<T> : Repo<T> {\n    override fun get(id: Int): T {\n"));
+}
+
+/// Enumerations with methods, a companion, or entries only
+fn enums() -> Diagram {
+    Diagram::builder()
+        .class("Mode", |c| c.annotation("enumeration").method(Visibility::Public, "label", |m| m.returns("String")))
+        .class("Level", |c| c.annotation("enumeration").method(Visibility::Public, "parse", |m| m.is_static().returns("Level")))
+        .class("Color", |c| c.annotation("enumeration").untyped_attr(Visibility::Unspecified, "RED"))
+        .build().unwrap()
+}
+
+#[test]
+fn kotlin_enum_members_follow_a_semicolon() {
+    let files = enums().to_kotlin();
+    assert!(files[Path::new("Mode.kt")].contains("enum class Mode {\n    ;\n\n    fun label(): String {"));
+    assert!(files[Path::new("Level.kt")].contains("enum class Level {\n    ;\n\n    companion object {"));
+    assert!(files[Path::new("Color.kt")].contains("enum class Color {\n    RED,\n}"));
+}
+
+#[test]
+#[ignore = "needs kotlinc"]
+fn kotlin_compiles() {
+    kotlinc("kotlin_shapes", &shapes().to_kotlin());
+    kotlinc("kotlin_open", &entities().to_kotlin());
+    kotlinc("kotlin_inherited", &inherited().to_kotlin());
+    kotlinc("kotlin_enums", &enums().to_kotlin());
+}
+
+// ── Python ──────────────────────────────────────────────────────────────────
+
+/// Run `script` next to the generated modules
+fn python(name: &str, files: &IndexMap<PathBuf, String>, script: &str) {
+    let dir = write_sources(name, files);
+    toolchain(files, &dir, Command::new("python3").args(["-c", script]));
+}
+
+fn pydantic() -> PythonOptions {
+    PythonOptions { style: PythonStyle::Pydantic, ..PythonOptions::default() }
+}
+
+#[test]
+fn python_dataclasses() {
+    let files = shapes().to_python();
+    let paths: Vec<&str> = files.keys().map(|p| p.to_str().unwrap()).collect();
+    assert_eq!(paths, ["geo.py", "models.py"]);
+
+    let geo = &files[Path::new("geo.py")];
+    assert!(geo.contains("from abc import ABC, abstractmethod\nfrom dataclasses import dataclass\nfrom enum import Enum, auto\nfrom typing import ClassVar\n"));
+    assert!(geo.contains("class Shape(ABC):\n    name: str\n\n    @abstractmethod\n    def area(self) -> float:\n        ...\n"));
+    assert!(geo.contains("@dataclass(kw_only=True)\nclass Circle(Shape):\n    _radius: float\n    PI: ClassVar[float]\n    name: str\n"));
+    assert!(geo.contains("    def scale_by(self, factor: int, type: str) -> None:\n        raise NotImplementedError\n"));
+    assert!(geo.contains("class Color(Enum):\n    RED = auto()\n    DARK_GREEN = auto()\n"));
+
+    let models = &files[Path::new("models.py")];
+    // named in annotations only, so not imported at runtime
+    assert!(models.contains("from typing import Generic, TYPE_CHECKING, TypeVar\n\nif TYPE_CHECKING:\n    from geo import Circle\n\nT = TypeVar(\"T\")\n"));
+    // a base class comes first
+    assert!(models.contains("@dataclass(kw_only=True)\nclass Element:\n    id: int\n\n\n@dataclass(kw_only=True)\nclass Canvas(Element):\n    title: str\n    _grid: list[list[int]]\n    _tags: list[str]\n    circles: list[Circle] = field(default_factory=list)\n    layer: Layer\n"));
+    assert!(models.contains("    @staticmethod\n    def create() -> Canvas:\n"));
+    assert!(models.contains("@dataclass(kw_only=True)\nclass Stack(Generic[T]):\n    def push(self, item: T) -> None:\n"));
+}
+
+#[test]
+fn python_pydantic_models() {
+    let files = shapes().to_python_with(&pydantic());
+    let geo = &files[Path::new("geo.py")];
+    assert!(geo.contains("from pydantic import BaseModel, Field\n"));
+    // Pydantic would drop a field named `_radius`
+    assert!(geo.contains("class Circle(Shape, BaseModel):\n    radius: float = Field(alias=\"_radius\")\n"));
+    let models = &files[Path::new("models.py")];
+    assert!(models.contains("class Element(BaseModel):\n    id: int\n\n\nclass Canvas(Element):\n"));
+    assert!(models.contains("    circles: list[Circle] = []\n"));
+    assert!(models.contains("class Stack(BaseModel, Generic[T]):\n"));
+    assert!(!models.contains("dataclass"));
+    // imported once the classes exist, for Pydantic to resolve on first use
+    assert!(models.ends_with("\n\n\nfrom geo import Circle\n"));
+}
+
+/// `Canvas` names `geo.Point`, which holds a `Canvas` in turn
+fn cycle() -> Diagram {
+    Diagram::builder()
+        .class("Canvas", |c| c.attr(Visibility::Public, "origin", "geo::Point"))
+        .class("geo::Point", |c| c)
+        .add_relation(Relation {
+            from: "Canvas".into(),
+            to: "geo::Point".into(),
+            kind: RelationKind::Composition,
+            line: LineStyle::Solid,
+            label_from: Some("0..1".into()),
+            label_to: None,
+        })
+        .build().unwrap()
+}
+
+#[test]
+fn python_modules_may_refer_to_each_other() {
+    // only base classes are imported up front
+    let geo = &cycle().to_python()[Path::new("geo.py")];
+    assert!(geo.contains("\nif TYPE_CHECKING:\n    from models import Canvas\n"), "{geo}");
+    let geo = &cycle().to_python_with(&pydantic())[Path::new("geo.py")];
+    assert!(geo.ends_with("\n\n\nfrom models import Canvas\n"), "{geo}");
+}
+
+fn shop() -> (Diagram, PythonOptions) {
+    let diagram = Diagram::builder()
+        .class("shop::Entity", |c| {
+            c.attr(Visibility::Public, "createdAt", "Date")
+                .untyped_attr(Visibility::Public, "extra")
+                .method(Visibility::Public, "validate", |m| m.is_abstract().returns("boolean"))
+        })
+        .class("shop::orders::Order", |c| {
+            c.attr(Visibility::Public, "total", "decimal")
+                .attr(Visibility::Public, "lines", "Map~String,int~")
+                .method(Visibility::Public, "validate", |m| m.returns("boolean"))
+                .method(Visibility::Public, "from", |m| m.param("lambda", "String"))
+        })
+        .relation("shop::orders::Order", RelationKind::Extension, "shop::Entity")
+        .build().unwrap();
+    let mut options = PythonOptions { root_module: "root".into(), ..PythonOptions::default() };
+    options.types.insert("Date", "datetime.date");
+    (diagram, options)
+}
+
+#[test]
+fn python_packages_abstract_members_and_type_map() {
+    let (diagram, options) = shop();
+    let files = diagram.to_python_with(&options);
+    let paths: Vec<&str> = files.keys().map(|p| p.to_str().unwrap()).collect();
+    assert_eq!(paths, ["shop/__init__.py", "shop/orders.py"]);
+
+    let entity = &files[Path::new("shop/__init__.py")];
+    assert!(entity.contains("from datetime import date\n"));
+    assert!(entity.contains("@dataclass(kw_only=True)\nclass Entity(ABC):\n    created_at: date\n    extra: Any\n\n    @abstractmethod\n    def validate(self) -> bool:\n        ...\n"));
+    let order = &files[Path::new("shop/orders.py")];
+    assert!(order.contains("from decimal import Decimal\n\nfrom shop import Entity\n"));
+    assert!(order.contains("class Order(Entity):\n    total: Decimal\n    lines: dict[str, int]\n"));
+    assert!(order.contains("    def from_(self, lambda_: str) -> None:\n"));
+}
+
+#[test]
+#[ignore = "needs python3 with pydantic"]
+fn python_imports() {
+    python(
+        "python_dataclasses",
+        &shapes().to_python(),
+        "import geo, models\n\
+         canvas = models.Canvas(id=1, title='t', _grid=[], _tags=[], layer=models.Layer(depth=0))\n\
+         assert canvas.circles == []\n\
+         assert isinstance(geo.Circle(_radius=1.0, name='c'), geo.Shape)\n\
+         try:\n    geo.Shape()\nexcept TypeError:\n    pass\nelse:\n    raise AssertionError('Shape is abstract')\n",
+    );
+    python(
+        "python_pydantic",
+        &shapes().to_python_with(&pydantic()),
+        "import geo, models\n\
+         canvas = models.Canvas.model_validate({'id': 1, 'title': 't', '_grid': [], '_tags': [], 'layer': {'depth': 2}, 'circles': [{'_radius': 1.0, 'name': 'c'}]})\n\
+         assert canvas.layer.depth == 2 and canvas.circles[0].name == 'c'\n\
+         assert models.Stack[int]().model_dump() == {}\n\
+         assert 'radius' in geo.Circle.model_fields and geo.Circle(_radius=1.0, name='c').radius == 1.0\n",
+    );
+    // either module may be imported first
+    for (name, options) in [("python_cycle_dataclasses", PythonOptions::default()), ("python_cycle_pydantic", pydantic())] {
+        let files = cycle().to_python_with(&options);
+        for order in ["geo, models", "models, geo"] {
+            python(name, &files, &format!("import {order}\nassert models.Canvas(origin=geo.Point()).origin.canvas is None\n"));
+        }
+    }
+    let (diagram, options) = shop();
+    python(
+        "python_packages",
+        &diagram.to_python_with(&options),
+        "from shop.orders import Order\nassert Order(created_at=None, extra=1, total=1, lines={}).validate is not None\n",
+    );
+}
@@ -0,0 +1,13 @@
+// tests/common/mod.rs
+//! Helpers shared by the integration tests.
+
+/// Compare `actual` with the checked-in file at `path` (relative to the
+/// crate root).  With `UPDATE_GOLDEN` set the file is rewritten first.
+pub fn golden(path: &str, actual: &str) {
+    let path = format!("{}/{path}", env!("CARGO_MANIFEST_DIR"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+    }
+    let on_disk = std::fs::read_to_string(&path).unwrap();
+    assert!(on_disk == actual, "{path} is stale, regenerate with UPDATE_GOLDEN=1:\n{actual}");
+}
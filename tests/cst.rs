@@ -0,0 +1,71 @@
+// tests/cst.rs
+//! The lossless syntax tree must reproduce its input byte-for-byte and let
+//! edits touch only the regions they target.
+
+use mermaid_parser::cst::TokenKind;
+use mermaid_parser::parser::{parse_lossless, Rule};
+
+const SRC: &str = "classDiagram\n\
+    %% the account model\n\
+    \n\
+    class BankAccount\n\
+    \tBankAccount : +withdrawal(amount)   %% trailing\n\
+    BankAccount : ~String owner\r\n\
+    \n\
+    Animal <|-- Duck\n";
+
+#[test]
+fn reemits_source_verbatim() {
+    let (diagram, tree) = parse_lossless(SRC).unwrap();
+    assert_eq!(tree.to_string(), SRC);
+    assert_eq!(diagram.relations.len(), 1);
+
+    let comments: Vec<_> = tree.comments().map(|t| tree.text(&t.span)).collect();
+    assert_eq!(comments, ["%% the account model", "%% trailing"]);
+
+    let newlines = tree
+        .root()
+        .tokens()
+        .filter(|t| t.kind == TokenKind::Newline)
+        .count();
+    assert_eq!(newlines, 8);
+}
+
+#[test]
+fn rewriter_only_touches_edited_nodes() {
+    let (_, tree) = parse_lossless(SRC).unwrap();
+    let mut rw = tree.rewriter();
+
+    // rename every BankAccount identifier
+    for node in tree.root().descendants() {
+        if node.rule == Rule::class_identifier && tree.text(&node.span) == "BankAccount" {
+            rw.replace(node, "Account").unwrap();
+        }
+    }
+    // drop the relation line
+    let relation = tree
+        .statements()
+        .find(|s| s.rule == Rule::relation_stmt)
+        .unwrap();
+    rw.remove(relation).unwrap();
+
+    assert_eq!(
+        rw.finish(),
+        "classDiagram\n\
+         %% the account model\n\
+         \n\
+         class Account\n\
+         \tAccount : +withdrawal(amount)   %% trailing\n\
+         Account : ~String owner\r\n\
+         \n"
+    );
+}
+
+#[test]
+fn overlapping_edits_are_rejected() {
+    let (_, tree) = parse_lossless(SRC).unwrap();
+    let class = tree.statements().next().unwrap();
+    let mut rw = tree.rewriter();
+    rw.replace(class, "class Other").unwrap();
+    assert!(rw.replace(class.child(Rule::class_identifier).unwrap(), "X").is_err());
+}
@@ -5,7 +5,7 @@
 //! **Adjust the `mycrate` import path** to match the `[package]` name in your
 //! `Cargo.toml` if it differs.
 
-use mermaid_parser::types::DEFAULT_NAMESPACE;
+use mermaid_parser::types::{Member, DEFAULT_NAMESPACE};
 
 #[test]
 fn single_class_in_default_namespace() {
@@ -25,3 +25,104 @@ class Marc
 
     assert!(default_ns.classes.contains_key("Chrissy"));
 }
+
+#[test]
+fn classes_iterate_in_declaration_order() {
+    let mermaid = r#"classDiagram
+class Tom
+class Amy
+Marc : +int age
+class Chrissy
+Amy : +talk()
+"#;
+
+    let diagram = mermaid_parser::parser::parse(mermaid).unwrap();
+    let names: Vec<_> = diagram.namespaces[DEFAULT_NAMESPACE]
+        .classes
+        .keys()
+        .map(String::as_str)
+        .collect();
+    assert_eq!(names, ["Tom", "Amy", "Marc", "Chrissy"]);
+}
+
+#[test]
+fn notes_are_collected() {
+    let mermaid = r#"classDiagram
+note "General remark"
+class Duck
+note for Duck "can swim"
+"#;
+
+    let diagram = mermaid_parser::parser::parse(mermaid).unwrap();
+    assert_eq!(diagram.notes.len(), 2);
+    assert_eq!(diagram.notes[0].text, "General remark");
+    assert_eq!(diagram.notes[0].for_class, None);
+    assert_eq!(diagram.notes[1].for_class.as_deref(), Some("Duck"));
+}
+
+#[test]
+fn method_parameters_are_collected() {
+    let mermaid = r#"classDiagram
+Shape : +scale(double factor, times)
+Shape : +reset()
+"#;
+
+    let diagram = mermaid_parser::parser::parse(mermaid).unwrap();
+    let members = &diagram.namespaces[DEFAULT_NAMESPACE].classes["Shape"].members;
+    let Member::Method(scale) = &members[0] else { panic!("{:?}", members[0]) };
+    let params: Vec<_> = scale
+        .parameters
+        .iter()
+        .map(|p| (p.name.as_str(), p.data_type.as_deref()))
+        .collect();
+    assert_eq!(params, [("factor", Some("double")), ("times", None)]);
+    assert!(matches!(&members[1], Member::Method(m) if m.parameters.is_empty()));
+}
+
+#[test]
+fn repeated_class_statements_keep_members() {
+    let mermaid = r#"classDiagram
+Duck : +swim()
+class Duck
+Duck : +int age
+class Duck
+"#;
+
+    let diagram = mermaid_parser::parser::parse(mermaid).unwrap();
+    let classes = &diagram.namespaces[DEFAULT_NAMESPACE].classes;
+    assert_eq!(classes.len(), 1);
+    let members = &classes["Duck"].members;
+    assert_eq!(members.len(), 2);
+    assert!(matches!(&members[0], Member::Method(m) if m.name == "swim"));
+    assert!(matches!(&members[1], Member::Attribute(a) if a.name == "age"));
+}
+
+#[test]
+fn relation_ends_declare_implicit_classes() {
+    let mermaid = r#"classDiagram
+Animal <|-- Duck
+Duck : +swim()
+"#;
+
+    let diagram = mermaid_parser::parser::parse(mermaid).unwrap();
+    let classes = &diagram.namespaces[DEFAULT_NAMESPACE].classes;
+    assert_eq!(classes.len(), 2);
+    assert!(classes["Animal"].implicit);
+    // a later member statement declares `Duck` explicitly
+    assert!(!classes["Duck"].implicit);
+}
+
+#[test]
+fn classifiers_mark_static_and_abstract_members() {
+    let mermaid = r#"classDiagram
+Shape : +$int count
+Shape : +*area() : int
+Shape : +$create() : Shape
+"#;
+
+    let diagram = mermaid_parser::parser::parse(mermaid).unwrap();
+    let members = &diagram.namespaces[DEFAULT_NAMESPACE].classes["Shape"].members;
+    assert!(members[0].is_static());
+    assert!(matches!(&members[1], Member::Method(m) if m.is_abstract && !m.is_static));
+    assert!(matches!(&members[2], Member::Method(m) if m.is_static && !m.is_abstract));
+}
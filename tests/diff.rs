@@ -0,0 +1,73 @@
+// tests/diff.rs
+//! Structured and rendered semantic diffs.
+
+use mermaid_parser::diff::{diff, Change};
+use mermaid_parser::parser::parse;
+
+const OLD: &str = r#"classDiagram
+class Order
+Order : -int id
+Order : +total(String currency) : Money
+Order : +ship()
+Order *-- Item
+Customer --> Order
+class Legacy
+"#;
+
+const NEW: &str = r#"classDiagram
+class Order
+Order : -long id
+Order : +total(Currency currency) : Money
+Order : +ship()
+Order : +cancel(String reason)
+Order o-- Item
+Customer --> Order
+class Invoice
+"#;
+
+#[test]
+fn identical_diagrams_have_no_changes() {
+    assert!(diff(&parse(OLD).unwrap(), &parse(OLD).unwrap()).is_empty());
+}
+
+#[test]
+fn structured_changes() {
+    let d = diff(&parse(OLD).unwrap(), &parse(NEW).unwrap());
+    let kinds: Vec<_> = d
+        .changes
+        .iter()
+        .map(|c| match c {
+            Change::ClassRemoved { class } => format!("-{class}"),
+            Change::ClassAdded { class } => format!("+{class}"),
+            Change::MemberChanged { old, .. } => format!("~{}", old.name()),
+            Change::MemberAdded { member, .. } => format!("+{}", member.name()),
+            Change::RelationChanged { new, .. } => format!("~{}", new.to),
+            other => format!("{other:?}"),
+        })
+        .collect();
+    assert_eq!(kinds, ["-Legacy", "~id", "~total", "+cancel", "+Invoice", "~Order"]);
+}
+
+#[test]
+fn human_readable_rendering() {
+    let d = diff(&parse(OLD).unwrap(), &parse(NEW).unwrap());
+    pretty_assertions::assert_eq!(
+        d.to_string(),
+        "class `Legacy` removed\n\
+         class `Order` changed attribute `-int id` to `-long id`\n\
+         class `Order` changed method `+total(String currency) : Money` to `+total(Currency currency) : Money`\n\
+         class `Order` added method `+cancel(String reason)`\n\
+         class `Invoice` added\n\
+         relation `Order *-- Item` changed to aggregation: `Order o-- Item`\n"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_rendering() {
+    let d = diff(&parse(OLD).unwrap(), &parse(NEW).unwrap());
+    let json = serde_json::to_value(&d).unwrap();
+    assert_eq!(json["changes"][0], serde_json::json!({"change": "class_removed", "class": "Legacy"}));
+    assert_eq!(json["changes"][5]["change"], "relation_changed");
+    assert_eq!(json["changes"][5]["new"]["kind"], "aggregation");
+}
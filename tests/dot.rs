@@ -0,0 +1,80 @@
+// tests/dot.rs
+//! Graphviz DOT export: records, clusters and edge styles.
+
+use mermaid_parser::types::{Diagram, LineStyle, Relation, RelationKind, Visibility};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn records_clusters_and_notes() {
+    let diagram = Diagram::builder()
+        .class("Shape", |c| {
+            c.annotation("interface")
+                .generic("T")
+                .attr(Visibility::Private, "name", "String")
+                .method(Visibility::Public, "area", |m| m.returns("f64"))
+        })
+        .class("geo::Circle", |c| c.static_attr(Visibility::Public, "PI", "f64"))
+        .relation("geo::Circle", RelationKind::Extension, "Shape")
+        .note_for("Shape", "say \"hi\"")
+        .build().unwrap();
+
+    assert_eq!(
+        diagram.to_dot(),
+        r#"digraph classes {
+  rankdir=BT;
+  node [shape=record, fontname="Helvetica", fontsize=10];
+  edge [fontname="Helvetica", fontsize=9];
+  "Shape" [label="{«interface»\nShape\<T\>|-String name\l|+area() : f64\l}"];
+  subgraph cluster_0 {
+    label="geo";
+    "geo::Circle" [label="{Circle|+$f64 PI\l|}"];
+  }
+  "geo::Circle" -> "Shape" [arrowhead=empty];
+  "note 0" [shape=note, label="say \"hi\""];
+  "note 0" -> "Shape" [style=dotted, arrowhead=none];
+}
+"#
+    );
+}
+
+#[test]
+fn edge_styles_follow_kind_and_line() {
+    let mut diagram = Diagram::default();
+    let cases = [
+        (RelationKind::Extension, LineStyle::Dotted, "arrowhead=empty, style=dashed"),
+        (RelationKind::Composition, LineStyle::Solid, "arrowhead=diamond"),
+        (RelationKind::Aggregation, LineStyle::Solid, "arrowhead=odiamond"),
+        (RelationKind::Dependency, LineStyle::Solid, "arrowhead=vee"),
+        (RelationKind::Dependency, LineStyle::Dotted, "arrowhead=vee, style=dashed"),
+        (RelationKind::Lollipop, LineStyle::Solid, "arrowhead=odot"),
+    ];
+    for (kind, line, _) in cases {
+        diagram.add_relation(Relation {
+            from: "A".into(),
+            to: "B".into(),
+            kind,
+            line,
+            label_from: Some("*".into()),
+            label_to: Some("1".into()),
+        });
+    }
+
+    let dot = diagram.to_dot();
+    let edges: Vec<&str> = dot.lines().filter(|l| l.contains("->")).collect();
+    for (edge, (_, _, attributes)) in edges.iter().zip(cases) {
+        assert_eq!(*edge, format!(r#"  "A" -> "B" [{attributes}, taillabel="*", headlabel="1"];"#));
+    }
+}
+
+#[test]
+fn nested_namespaces_nest_clusters() {
+    let mut diagram = Diagram::builder().class("a::Top", |c| c).build().unwrap();
+    let inner = Diagram::builder().class("a::b::Inner", |c| c).build().unwrap();
+    let child = inner.namespaces.into_values().next().unwrap();
+    diagram.namespaces["a"].children.insert("a::b".into(), child);
+
+    let dot = diagram.to_dot();
+    assert!(dot.contains(
+        "  subgraph cluster_0 {\n    label=\"a\";\n    \"a::Top\" [label=\"{Top||}\"];\n    subgraph cluster_1 {\n      label=\"a::b\";\n      \"a::b::Inner\" [label=\"{Inner||}\"];\n    }\n  }\n"
+    ), "{dot}");
+}
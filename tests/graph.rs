@@ -0,0 +1,68 @@
+// tests/graph.rs
+//! petgraph conversion (`--features graph`).
+#![cfg(feature = "graph")]
+
+use mermaid_parser::types::{Diagram, LineStyle, Relation, RelationKind};
+use petgraph::algo::{is_cyclic_directed, toposort};
+
+const SRC: &str = r#"classDiagram
+class Shape
+Shape <|-- Polygon
+Polygon <|-- Square
+Canvas *-- Shape
+"#;
+
+#[test]
+fn classes_become_nodes_and_relations_edges() {
+    let diagram = mermaid_parser::parser::parse(SRC).unwrap();
+    let graph = diagram.to_graph();
+    assert_eq!(graph.node_count(), 4);
+    assert_eq!(graph.edge_count(), 3);
+
+    // edges point child ➜ parent, so a topological order puts leaves first
+    let order: Vec<_> = toposort(&graph, None)
+        .unwrap()
+        .into_iter()
+        .map(|n| graph[n].name.as_str())
+        .collect();
+    let pos = |name| order.iter().position(|n| *n == name).unwrap();
+    assert!(pos("Square") < pos("Polygon") && pos("Polygon") < pos("Shape"));
+    assert!(!is_cyclic_directed(&graph));
+
+    let shape = graph.node_indices().find(|&n| graph[n].name == "Shape").unwrap();
+    // Polygon extends Shape; Shape is a part of Canvas
+    let incoming = graph.edges_directed(shape, petgraph::Direction::Incoming).count();
+    let outgoing = graph.edges_directed(shape, petgraph::Direction::Outgoing).count();
+    assert_eq!((incoming, outgoing), (1, 1));
+}
+
+#[test]
+fn roundtrips_through_graph() {
+    let mut diagram = mermaid_parser::parser::parse(SRC).unwrap();
+    // Mermaid source has no lollipop syntax, so add one by hand
+    let plug = Relation {
+        from: "Plug".into(),
+        to: "Shape".into(),
+        kind: RelationKind::Lollipop,
+        line: LineStyle::Solid,
+        label_from: None,
+        label_to: Some("draws".into()),
+    };
+    diagram.add_relation(plug.clone());
+    let mut graph = diagram.to_graph();
+
+    // rewire on the graph side: Square now extends Shape directly
+    let square = graph.node_indices().find(|&n| graph[n].name == "Square").unwrap();
+    let shape = graph.node_indices().find(|&n| graph[n].name == "Shape").unwrap();
+    let edge = graph.find_edge(square, graph.neighbors(square).next().unwrap()).unwrap();
+    let weight = graph.remove_edge(edge).unwrap();
+    graph.add_edge(square, shape, weight);
+
+    let back = Diagram::from_graph(&graph);
+    assert_eq!(back.classes().count(), 5);
+    assert_eq!(back.superclasses("Square"), ["Shape"]);
+    assert_eq!(back.composed_of("Canvas"), ["Shape"]);
+    let lollipops: Vec<_> = back.relations.iter().filter(|r| r.kind == RelationKind::Lollipop).collect();
+    assert_eq!(lollipops, [&plug]);
+    assert!(back.class("Plug").unwrap().implicit);
+}
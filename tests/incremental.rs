@@ -0,0 +1,139 @@
+// tests/incremental.rs
+//! Incremental reparsing agrees with a full parse after every edit.
+
+use mermaid_parser::cst::TextEdit;
+use mermaid_parser::diff::diff;
+use mermaid_parser::incremental::{Changes, Document, IncrementalError};
+use mermaid_parser::parser::parse;
+use mermaid_parser::types::Diagram;
+
+const SRC: &str = r#"classDiagram
+class Order
+Order : -int id
+Order : +total(String currency) : Money
+
+Order *-- Item
+Customer --> Order
+"#;
+
+/// Apply `edit` and check the document against a fresh parse of its source
+fn edit_and_check(doc: &mut Document, edit: TextEdit) -> Changes {
+    let changes = doc.edit(edit).unwrap();
+    let full = parse(doc.source()).unwrap();
+    assert!(diff(&full, doc.diagram()).is_empty(), "{}", diff(&full, doc.diagram()));
+    assert_eq!(full.relations, doc.diagram().relations);
+    let names = |d: &Diagram| d.classes().map(|c| c.name.clone()).collect::<Vec<_>>();
+    assert_eq!(names(&full), names(doc.diagram()));
+    for class in full.classes() {
+        let patched = doc.diagram().class(&class.name).unwrap();
+        assert_eq!(patched.members, class.members, "{}", class.name);
+        assert_eq!(patched.implicit, class.implicit, "{}", class.name);
+    }
+    assert_eq!(full.notes, doc.diagram().notes);
+    changes
+}
+
+fn at(src: &str, needle: &str) -> std::ops::Range<usize> {
+    let start = src.find(needle).unwrap();
+    start..start + needle.len()
+}
+
+#[test]
+fn edit_within_a_line_reparses_only_that_line() {
+    let mut doc = Document::new(SRC).unwrap();
+    let changes = edit_and_check(&mut doc, TextEdit::new(at(SRC, "-int id"), "-long id"));
+
+    assert_eq!(changes.removed, [at(SRC, "Order : -int id")]);
+    assert_eq!(changes.inserted.len(), 1);
+    assert_eq!(&doc.source()[changes.inserted[0].clone()], "Order : -long id");
+    assert_eq!(changes.classes, ["Order"]);
+}
+
+#[test]
+fn inserting_and_deleting_lines_shifts_later_statements() {
+    let mut doc = Document::new(SRC).unwrap();
+    let pos = at(SRC, "Order *-- Item").start;
+    let changes = edit_and_check(&mut doc, TextEdit::new(pos..pos, "class Invoice\nInvoice ..> Order\n"));
+    assert_eq!(changes.classes, ["Item", "Order", "Invoice"]);
+    assert!(doc.diagram().class("Invoice").is_some());
+
+    // the untouched last line still maps to the right text
+    let src = doc.source().to_owned();
+    let changes = edit_and_check(&mut doc, TextEdit::new(at(&src, "Customer --> Order\n"), ""));
+    assert_eq!(changes.removed, [at(&src, "Customer --> Order")]);
+    assert!(changes.inserted.is_empty());
+    assert!(doc.diagram().class("Customer").is_none());
+
+    // every remaining relation still survives another edit next to it
+    let src = doc.source().to_owned();
+    edit_and_check(&mut doc, TextEdit::new(at(&src, "*--"), "o--"));
+}
+
+#[test]
+fn header_edits_reparse_everything() {
+    let mut doc = Document::new(SRC).unwrap();
+    let changes = edit_and_check(&mut doc, TextEdit::new(12..12, "\n%% header comment"));
+    assert_eq!(changes.removed.len(), 5);
+    assert_eq!(changes.inserted.len(), 5);
+}
+
+#[test]
+fn a_failed_edit_leaves_the_document_untouched() {
+    let mut doc = Document::new(SRC).unwrap();
+    let before = doc.source().to_owned();
+
+    let err = doc.edit(TextEdit::new(at(SRC, "*--"), "*-?")).unwrap_err();
+    let IncrementalError::Parse(err) = err else {
+        panic!("expected a parse error, got {err:?}");
+    };
+    // the error points into the edited file, not into the re-parsed chunk
+    assert!(err.to_string().contains(" 6:"), "{err}");
+    assert_eq!(doc.source(), before);
+
+    assert!(matches!(
+        doc.edit(TextEdit::new(0..before.len() + 1, "")),
+        Err(IncrementalError::Edit(_))
+    ));
+}
+
+#[test]
+fn patched_classes_keep_full_parse_order_and_flags() {
+    let mut doc = Document::new(SRC).unwrap();
+
+    // `Order`'s first mention goes away: it now comes after `Item`'s
+    let changes = edit_and_check(&mut doc, TextEdit::new(at(SRC, "class Order\n"), "class Item\n"));
+    assert_eq!(changes.classes, ["Order", "Item"]);
+    let names: Vec<_> = doc.diagram().classes().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["Item", "Order", "Customer"]);
+    assert!(!doc.diagram().class("Item").unwrap().implicit);
+
+    // `Customer` gets a member and stops being implicit; `Item` is implicit again
+    let src = doc.source().to_owned();
+    edit_and_check(&mut doc, TextEdit::new(at(&src, "class Item"), "Customer : +pay()"));
+    assert!(!doc.diagram().class("Customer").unwrap().implicit);
+    assert!(doc.diagram().class("Item").unwrap().implicit);
+
+    // dropping the last mention of `Item` removes it
+    let src = doc.source().to_owned();
+    edit_and_check(&mut doc, TextEdit::new(at(&src, "Order *-- Item\n"), ""));
+    assert!(doc.diagram().class("Item").is_none());
+    let kept: Vec<_> = doc.diagram().class("Order").unwrap().members.iter().map(|m| m.name().to_owned()).collect();
+    assert_eq!(kept, ["id", "total"]);
+
+    // notes are spliced in place like relations
+    let src = doc.source().to_owned();
+    let pos = at(&src, "Customer --> Order").start;
+    edit_and_check(&mut doc, TextEdit::new(pos..pos, "note for Order \"root\"\nnote \"all\"\n"));
+    let src = doc.source().to_owned();
+    edit_and_check(&mut doc, TextEdit::new(at(&src, "root"), "aggregate"));
+    assert_eq!(doc.diagram().notes[0].text, "aggregate");
+}
+
+#[test]
+fn a_note_does_not_declare_the_class_it_names() {
+    let mut doc = Document::new("classDiagram\nclass A\n").unwrap();
+    let end = doc.source().len();
+    let changes = edit_and_check(&mut doc, TextEdit::new(end..end, "note for Ghost \"x\"\n"));
+    assert!(changes.classes.is_empty());
+    assert!(doc.diagram().class("Ghost").is_none());
+}
@@ -0,0 +1,75 @@
+// tests/merge.rs
+//! Merging per-service diagrams into one architecture view.
+
+use mermaid_parser::merge::{merge, ConflictKind, MergeError, MergeStrategy};
+use mermaid_parser::parser::parse;
+use mermaid_parser::types::Member;
+
+const ORDERS: &str = r#"classDiagram
+class Order
+Order : -int id
+Order : +cancel()
+Order *-- Item
+Order --> Customer
+"#;
+
+const BILLING: &str = r#"classDiagram
+class Invoice
+Order : -long id
+Order : +cancel()
+Order : +bill(Invoice invoice)
+Order *-- Item
+Invoice --> Order
+"#;
+
+fn attr_type(member: &Member) -> Option<&str> {
+    match member {
+        Member::Attribute(a) => a.data_type.as_deref(),
+        Member::Method(_) => None,
+    }
+}
+
+#[test]
+fn unions_classes_members_and_relations() {
+    let (a, b) = (parse(ORDERS).unwrap(), parse(BILLING).unwrap());
+    let out = merge([&a, &b], MergeStrategy::KeepFirst).unwrap();
+
+    let names: Vec<_> = out.diagram.classes().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["Order", "Item", "Customer", "Invoice"]);
+
+    let order = out.diagram.class("Order").unwrap();
+    let members: Vec<_> = order.members.iter().map(Member::name).collect();
+    assert_eq!(members, ["id", "cancel", "bill"]);
+    assert_eq!(attr_type(&order.members[0]), Some("int"));
+
+    // `Order *-- Item` appears in both and is kept once
+    assert_eq!(out.diagram.relations.len(), 3);
+
+    assert_eq!(out.conflicts.len(), 1);
+    assert!(matches!(&out.conflicts[0].kind, ConflictKind::Member { .. }));
+    assert_eq!(out.conflicts[0].source, 1);
+    assert_eq!(
+        out.conflicts[0].to_string(),
+        "class `Order`: `-int id` conflicts with `-long id` from diagram #1"
+    );
+}
+
+#[test]
+fn strategy_decides_the_winner() {
+    let (a, b) = (parse(ORDERS).unwrap(), parse(BILLING).unwrap());
+
+    let out = merge([&a, &b], MergeStrategy::KeepLast).unwrap();
+    let order = out.diagram.class("Order").unwrap();
+    assert_eq!(attr_type(&order.members[0]), Some("long"));
+
+    let err = merge([&a, &b], MergeStrategy::Fail).unwrap_err();
+    assert!(matches!(err, MergeError::Conflict(c) if c.class == "Order"));
+}
+
+#[test]
+fn explicit_declaration_beats_implicit() {
+    let a = parse("classDiagram\nOrder --> Customer\n").unwrap();
+    let b = parse("classDiagram\nclass Customer\n").unwrap();
+    let out = merge([&a, &b], MergeStrategy::default()).unwrap();
+    assert!(!out.diagram.class("Customer").unwrap().implicit);
+}
@@ -8,5 +8,10 @@ mod tests {
         let diagram = mermaid_parser::parser::parse(mermaid).unwrap();
         let ns = diagram.namespaces.get(DEFAULT_NAMESPACE).unwrap();
         println!("{:?}", diagram);
+
+        let members = &ns.classes["BankAccount"].members;
+        assert_eq!(members.len(), 3);
+        assert!(matches!(&members[0], Member::Method(m) if m.visibility == Visibility::Public));
+        assert!(matches!(&members[2], Member::Attribute(a) if a.visibility == Visibility::Package));
     }
 }
@@ -0,0 +1,277 @@
+// tests/plantuml.rs
+//! PlantUML export of every construct the data model can hold, and import
+//! back into the same model.
+
+use mermaid_parser::diff::diff;
+use mermaid_parser::plantuml::{self, ImportError};
+use mermaid_parser::types::{Diagram, LineStyle, Member, Relation, RelationKind, Visibility};
+use pretty_assertions::assert_eq;
+
+fn relation(from: &str, kind: RelationKind, line: LineStyle, to: &str) -> Relation {
+    Relation {
+        from: from.into(),
+        to: to.into(),
+        kind,
+        line,
+        label_from: None,
+        label_to: None,
+    }
+}
+
+#[test]
+fn classes_members_and_packages() {
+    let diagram = Diagram::builder()
+        .class("Account", |c| {
+            c.attr(Visibility::Private, "balance", "Money")
+                .static_attr(Visibility::Public, "count", "int")
+                .untyped_attr(Visibility::Unspecified, "owner")
+                .method(Visibility::Public, "deposit", |m| m.param("amount", "Money").untyped_param("memo"))
+                .method(Visibility::Protected, "audit", |m| m.is_abstract().returns("bool"))
+                .method(Visibility::Package, "open", |m| m.is_static().returns("Account"))
+        })
+        .class("shop::billing::Payable", |c| c.annotation("interface").generic("T"))
+        .class("shop::billing::Invoice", |c| c.annotation("entity").annotation("abstract"))
+        .class("shop::Status", |c| c.annotation("enumeration"))
+        .build().unwrap();
+
+    assert_eq!(
+        diagram.to_plantuml(),
+        r#"@startuml
+set separator ::
+class Account {
+  -balance : Money
+  {static} +count : int
+  owner
+  +deposit(amount : Money, memo)
+  {abstract} #audit() : bool
+  {static} ~open() : Account
+}
+package shop::billing {
+  interface Payable<T>
+  abstract class Invoice <<entity>>
+}
+package shop {
+  enum Status
+}
+@enduml
+"#
+    );
+}
+
+#[test]
+fn every_relation_kind_and_line_style() {
+    let mut diagram = Diagram::default();
+    for (kind, line) in [
+        (RelationKind::Extension, LineStyle::Solid),
+        (RelationKind::Extension, LineStyle::Dotted),
+        (RelationKind::Composition, LineStyle::Solid),
+        (RelationKind::Aggregation, LineStyle::Solid),
+        (RelationKind::Dependency, LineStyle::Solid),
+        (RelationKind::Dependency, LineStyle::Dotted),
+        (RelationKind::Lollipop, LineStyle::Solid),
+    ] {
+        diagram.add_relation(relation("B", kind, line, "A"));
+    }
+    diagram.relations[2].label_to = Some("1".into());
+    diagram.relations[2].label_from = Some("many".into());
+
+    assert_eq!(
+        diagram.to_plantuml(),
+        r#"@startuml
+A <|-- B
+A <|.. B
+A "1" *-- "many" B
+A o-- B
+A <-- B
+A <.. B
+A ()-- B
+@enduml
+"#
+    );
+}
+
+#[test]
+fn parsed_diagram_with_notes() {
+    let diagram = mermaid_parser::parser::parse(
+        "classDiagram\nclass Duck\nDuck : +$int count\nAnimal <|-- Duck\nnote \"zoo\"\nnote for Duck \"quacks\"\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        diagram.to_plantuml(),
+        r#"@startuml
+class Duck {
+  {static} +count : int
+}
+Animal <|-- Duck
+note "zoo" as N1
+note top of Duck : quacks
+@enduml
+"#
+    );
+}
+
+fn class_names(diagram: &Diagram) -> Vec<(String, bool)> {
+    diagram.classes().map(|c| (c.name.clone(), c.implicit)).collect()
+}
+
+#[test]
+fn import_matches_the_mermaid_parser() {
+    let mermaid = mermaid_parser::parser::parse(
+        r#"classDiagram
+class Order
+Order : -int id
+Order : +total(String currency) : Money
+Order : +$create() : Order
+Order *-- Item
+Customer --> Order
+Invoice ..|> Payable
+Logger ..> Order
+note for Order "placed online"
+"#,
+    )
+    .unwrap();
+
+    let imported = plantuml::parse(
+        r#"@startuml
+' same diagram, PlantUML flavour
+class Order {
+  -id : int
+  +Money total(String currency)
+  {static} +create() : Order
+}
+Order *-- Item
+Customer --> Order
+Invoice ..|> Payable
+Order <.. Logger
+note top of Order : placed online
+@enduml
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(imported.diagnostics, []);
+    assert!(diff(&mermaid, &imported.diagram).is_empty(), "{}", diff(&mermaid, &imported.diagram));
+    assert_eq!(class_names(&mermaid), class_names(&imported.diagram));
+    assert_eq!(mermaid.relations, imported.diagram.relations);
+    assert_eq!(mermaid.notes, imported.diagram.notes);
+}
+
+#[test]
+fn export_then_import_round_trips() {
+    let diagram = Diagram::builder()
+        .class("Account", |c| {
+            c.attr(Visibility::Private, "balance", "Money")
+                .static_attr(Visibility::Public, "count", "int")
+                .method(Visibility::Protected, "audit", |m| m.is_abstract().param("depth", "int").returns("bool"))
+        })
+        .class("shop::billing::Payable", |c| c.annotation("interface").generic("T"))
+        .class("shop::billing::Invoice", |c| c.annotation("abstract").annotation("entity"))
+        .class("shop::Status", |c| c.annotation("enumeration").untyped_attr(Visibility::Unspecified, "OPEN"))
+        .relation("shop::billing::Invoice", RelationKind::Extension, "shop::billing::Payable")
+        .relation("Account", RelationKind::Aggregation, "shop::billing::Invoice")
+        .note("ledger")
+        .build().unwrap();
+
+    let imported = plantuml::parse(&diagram.to_plantuml()).unwrap();
+    assert_eq!(imported.diagnostics, []);
+    assert!(diff(&diagram, &imported.diagram).is_empty(), "{}", diff(&diagram, &imported.diagram));
+    assert_eq!(class_names(&diagram), class_names(&imported.diagram));
+    assert_eq!(diagram.relations, imported.diagram.relations);
+    assert_eq!(diagram.notes, imported.diagram.notes);
+}
+
+#[test]
+fn packages_separators_and_inline_inheritance() {
+    let imported = plantuml::parse(
+        r#"@startuml
+package com.shop {
+  abstract class Shape<T extends Number> <<model>> {
+    #List<String> tags
+  }
+  class Circle extends Shape implements Drawable
+  package geo {
+    class Point
+  }
+}
+interface Drawable
+note "floating" as N1
+N1 .. Point
+Circle "1" o-- "*" Point
+@enduml
+"#,
+    )
+    .unwrap();
+    assert_eq!(imported.diagnostics, []);
+    let d = &imported.diagram;
+
+    let shape = d.class("com::shop::Shape").unwrap();
+    assert_eq!(shape.annotations, ["abstract", "model"]);
+    assert_eq!(shape.generic.as_deref(), Some("T extends Number"));
+    let Member::Attribute(tags) = &shape.members[0] else { panic!("{:?}", shape.members) };
+    assert_eq!((tags.name.as_str(), tags.data_type.as_deref()), ("tags", Some("List<String>")));
+
+    assert!(d.class("com::shop::geo::Point").is_some());
+    assert_eq!(d.superclasses("com::shop::Circle"), ["com::shop::Shape"]);
+    assert_eq!(d.implemented_interfaces("com::shop::Circle"), ["Drawable"]);
+
+    assert_eq!(d.notes[0].for_class.as_deref(), Some("com::shop::geo::Point"));
+    let whole_part = &d.relations[2];
+    assert_eq!((whole_part.from.as_str(), whole_part.to.as_str()), ("com::shop::geo::Point", "com::shop::Circle"));
+    assert_eq!((whole_part.label_from.as_deref(), whole_part.label_to.as_deref()), (Some("*"), Some("1")));
+}
+
+#[test]
+fn unsupported_constructs_are_reported() {
+    let imported = plantuml::parse(
+        r#"@startuml
+skinparam class {
+  BackgroundColor White
+}
+hide empty members
+entity Row {
+  +id : int = 0
+  +name : String
+}
+Row --> Table : reads
+Row <--> Index
+Row #-- Cell
+@enduml
+"#,
+    )
+    .unwrap();
+
+    let messages: Vec<String> = imported.diagnostics.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        [
+            "line 2: unsupported `skinparam class {`, ignored",
+            "line 5: unsupported `hide empty members`, ignored",
+            "line 6: `entity` imported as a class annotated `<<entity>>`",
+            "line 7: unsupported member `+id : int = 0` in `Row`, ignored",
+            "line 10: relation label `reads` dropped",
+            "line 11: two-headed arrow `<…>` is not supported, ignored",
+            "line 12: arrow head `#` is not supported, ignored",
+        ]
+    );
+
+    let d = &imported.diagram;
+    assert_eq!(d.class("Row").unwrap().members.len(), 1);
+    assert_eq!(d.relations.len(), 1);
+}
+
+#[test]
+fn malformed_structure_is_an_error() {
+    assert!(matches!(plantuml::parse("class A\n"), Err(ImportError::Pest(_))));
+    assert!(plantuml::parse("@startuml\nclass A {\n+x : int\n@enduml\n").is_err());
+}
+
+#[test]
+fn multi_line_notes_keep_their_breaks() {
+    let imported = plantuml::parse(
+        "@startuml\nclass A\nnote right of A\n  first\n  second\nend note\n@enduml\n",
+    )
+    .unwrap();
+    assert_eq!(imported.diagram.notes[0].text, r"first\nsecond");
+    assert_eq!(imported.diagram.notes[0].for_class.as_deref(), Some("A"));
+}
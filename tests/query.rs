@@ -0,0 +1,65 @@
+// tests/query.rs
+//! Relation queries must read every arrow the right way round, however it
+//! was written.
+
+const SRC: &str = r#"classDiagram
+Shape <|-- Polygon
+Square --|> Polygon
+Polygon <|-- Triangle
+Square ..|> Drawable
+Drawable <|.. Circle
+Circle --|> Shape
+Canvas *-- Layer
+Layer --* Canvas
+Brush --o Canvas
+Canvas ..> Renderer
+Renderer <.. Printer
+Canvas --> Palette
+"#;
+
+fn diagram() -> mermaid_parser::types::Diagram {
+    mermaid_parser::parser::parse(SRC).unwrap()
+}
+
+#[test]
+fn relations_are_normalised_to_head_end() {
+    let d = diagram();
+    let first = &d.relations[0];
+    assert_eq!((first.from.as_str(), first.to.as_str()), ("Polygon", "Shape"));
+    let second = &d.relations[1];
+    assert_eq!((second.from.as_str(), second.to.as_str()), ("Square", "Polygon"));
+}
+
+#[test]
+fn inheritance() {
+    let d = diagram();
+    assert_eq!(d.superclasses("Square"), ["Polygon"]);
+    assert_eq!(d.ancestors("Square"), ["Polygon", "Shape"]);
+    assert_eq!(d.subclasses("Shape"), ["Polygon", "Circle"]);
+    assert_eq!(d.descendants("Shape"), ["Polygon", "Circle", "Square", "Triangle"]);
+    // realization is not inheritance
+    assert!(d.subclasses("Drawable").is_empty());
+}
+
+#[test]
+fn interfaces_parts_and_usage() {
+    let d = diagram();
+    assert_eq!(d.implemented_interfaces("Square"), ["Drawable"]);
+    assert_eq!(d.implementors("Drawable"), ["Square", "Circle"]);
+
+    // `Canvas *-- Layer` and `Layer --* Canvas` are the same edge
+    assert_eq!(d.composed_of("Canvas"), ["Layer"]);
+    assert_eq!(d.aggregates("Canvas"), ["Brush"]);
+    assert_eq!(d.part_of("Layer"), ["Canvas"]);
+
+    assert_eq!(d.dependencies("Canvas"), ["Renderer", "Palette"]);
+    assert_eq!(d.dependents("Renderer"), ["Canvas", "Printer"]);
+}
+
+#[test]
+fn class_lookup_by_fully_qualified_name() {
+    let d = mermaid_parser::parser::parse("classDiagram\nclass Duck\n").unwrap();
+    assert_eq!(d.class("Duck").unwrap().name, "Duck");
+    assert!(d.class("geo::Duck").is_none());
+    assert_eq!(d.classes().count(), 1);
+}
@@ -0,0 +1,129 @@
+// tests/refactor.rs
+//! Refactorings keep every reference consistent, in the model and in source.
+
+use mermaid_parser::refactor::{apply_to_source, RefactorError, Refactoring};
+use mermaid_parser::types::{Diagram, LineStyle, Member, RelationKind, Visibility};
+
+fn shop() -> Diagram {
+    Diagram::builder()
+        .class("shop::Order", |c| {
+            c.attr(Visibility::Private, "items", "List~Item~")
+                .method(Visibility::Public, "merge", |m| m.param("other", "shop::Order").returns("Order"))
+                .method(Visibility::Public, "total", |m| m.returns("Money"))
+                .method(Visibility::Private, "audit", |m| m)
+        })
+        .class("shop::Item", |c| c)
+        .class("Customer", |c| c.attr(Visibility::Public, "orders", "List~shop::Order~"))
+        .relation("Customer", RelationKind::Dependency, "shop::Order")
+        .relation("shop::Item", RelationKind::Composition, "shop::Order")
+        .note_for("shop::Order", "aggregate root")
+        .build().unwrap()
+}
+
+fn attr_type<'a>(d: &'a Diagram, class: &str, member: usize) -> &'a str {
+    match &d.class(class).unwrap().members[member] {
+        Member::Attribute(a) => a.data_type.as_deref().unwrap(),
+        Member::Method(m) => m.return_type.as_deref().unwrap(),
+    }
+}
+
+#[test]
+fn rename_class_updates_every_reference() {
+    let mut d = shop();
+    d.rename_class("shop::Order", "Purchase").unwrap();
+
+    let names: Vec<_> = d.namespaces["shop"].classes.keys().map(String::as_str).collect();
+    assert_eq!(names, ["Purchase", "Item"], "position is kept");
+    assert_eq!(d.class("shop::Purchase").unwrap().namespace, "shop");
+    assert_eq!(d.relations[0].to, "shop::Purchase");
+    assert_eq!(d.notes[0].for_class.as_deref(), Some("shop::Purchase"));
+    assert_eq!(attr_type(&d, "Customer", 0), "List~shop::Purchase~");
+    // relative self reference stays relative
+    assert_eq!(attr_type(&d, "shop::Purchase", 1), "Purchase");
+}
+
+#[test]
+fn move_class_respells_relative_names() {
+    let mut d = shop();
+    d.move_class("shop::Item", "catalog").unwrap();
+
+    assert!(d.class("shop::Item").is_none());
+    assert_eq!(d.class("catalog::Item").unwrap().namespace, "catalog");
+    assert_eq!(attr_type(&d, "shop::Order", 0), "List~catalog::Item~");
+    assert_eq!(d.relations[1].from, "catalog::Item");
+
+    assert!(matches!(
+        d.move_class("Customer", "catalog").and(d.move_class("catalog::Customer", "catalog")),
+        Ok(())
+    ));
+    // the default namespace lost its only class and is gone; `shop` still has `Order`
+    let namespaces: Vec<_> = d.namespaces.keys().map(String::as_str).collect();
+    assert_eq!(namespaces, ["shop", "catalog"]);
+    assert!(matches!(d.move_class("Nope", "x"), Err(RefactorError::UnknownClass(_))));
+}
+
+#[test]
+fn rename_member_and_extract_interface() {
+    let mut d = shop();
+    d.rename_member("shop::Order", "total", "sum").unwrap();
+    assert!(matches!(
+        d.rename_member("shop::Order", "sum", "merge"),
+        Err(RefactorError::MemberExists { .. })
+    ));
+
+    d.extract_interface("shop::Order", "shop::Billable").unwrap();
+    let iface = d.class("shop::Billable").unwrap();
+    assert_eq!(iface.annotations, ["interface"]);
+    let names: Vec<_> = iface.members.iter().map(Member::name).collect();
+    assert_eq!(names, ["merge", "sum"]);
+    let realization = d.relations.last().unwrap();
+    assert_eq!((realization.kind, realization.line), (RelationKind::Extension, LineStyle::Dotted));
+    assert_eq!(d.implemented_interfaces("shop::Order"), ["shop::Billable"]);
+}
+
+const SRC: &str = "classDiagram\n\
+    %% ordering\n\
+    class Order\n\
+    Order : +total() : Money\n\
+    Order : -int total_cache   %% cached\n\
+    Customer --> Order\n";
+
+#[test]
+fn text_edits_touch_only_references() {
+    let out = apply_to_source(
+        SRC,
+        &Refactoring::RenameMember {
+            class: "Order".into(),
+            member: "total".into(),
+            new_name: "sum".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(out, SRC.replace("+total()", "+sum()"));
+
+    let out = apply_to_source(
+        SRC,
+        &Refactoring::ExtractInterface {
+            class: "Order".into(),
+            interface: "Priced".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(out, format!("{SRC}class Priced\nPriced : +*total() : Money\nPriced <|.. Order\n"));
+    // the appended text parses back to the same model change
+    let reparsed = mermaid_parser::parser::parse(&out).unwrap();
+    assert_eq!(reparsed.implemented_interfaces("Order"), ["Priced"]);
+
+    assert!(matches!(
+        apply_to_source(SRC, &Refactoring::MoveClass { class: "Order".into(), namespace: "x".into() }),
+        Err(RefactorError::NotRepresentable(_))
+    ));
+    // new names must parse back as class identifiers
+    for refactoring in [
+        Refactoring::RenameClass { class: "Order".into(), new_name: "shop::Order".into() },
+        Refactoring::RenameClass { class: "Order".into(), new_name: "Big Order".into() },
+        Refactoring::ExtractInterface { class: "Order".into(), interface: "shop::Billable".into() },
+    ] {
+        assert!(matches!(apply_to_source(SRC, &refactoring), Err(RefactorError::NotRepresentable(_))));
+    }
+}
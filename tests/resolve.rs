@@ -0,0 +1,84 @@
+// tests/resolve.rs
+//! Relation ends resolve as written; member types resolve to declared classes,
+//! nearest namespace first.
+
+use mermaid_parser::resolve::RefSite;
+use mermaid_parser::types::{Diagram, RelationKind, Visibility};
+
+fn shop() -> Diagram {
+    Diagram::builder()
+        .class("shop::Order", |c| {
+            c.attr(Visibility::Private, "items", "List~Item~")
+                .attr(Visibility::Private, "customer", "Customer")
+                .method(Visibility::Public, "total", |m| m.param("tax", "shop::Tax").returns("Money"))
+        })
+        .class("shop::Item", |c| c)
+        .class("shop::Tax", |c| c)
+        .class("Customer", |c| c)
+        .class("Item", |c| c)
+        .relation("shop::Order", RelationKind::Composition, "Item")
+        .relation("Customer", RelationKind::Dependency, "shop::Order")
+        .build().unwrap()
+}
+
+#[test]
+fn relation_ends_resolve_as_written() {
+    let mut diagram = shop();
+    // pushed directly, bypassing implicit class creation
+    let mut ghost = diagram.relations[1].clone();
+    ghost.to = "Ghost".into();
+    diagram.relations.push(ghost);
+    let res = diagram.resolve();
+    assert_eq!(res.class_count(), 5);
+
+    // the top-level `Item`, even though `shop::Item` is nearer to `shop::Order`
+    let (from, to) = res.relation_ends(0);
+    assert_eq!(res.name(from.unwrap()), "shop::Order");
+    assert_eq!(res.name(to.unwrap()), "Item");
+
+    let (from, to) = res.relation_ends(1);
+    assert_eq!(res.name(from.unwrap()), "Customer");
+    assert_eq!(res.class(&diagram, to.unwrap()).unwrap().name, "shop::Order");
+
+    let missing: Vec<_> = res.unresolved_relations().collect();
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].name, "Ghost");
+    assert_eq!(missing[0].site, RefSite::RelationTo { relation: 2 });
+}
+
+#[test]
+fn member_types_resolve_through_enclosing_namespace() {
+    let diagram = shop();
+    let res = diagram.resolve();
+    let order = res.id("shop::Order").unwrap();
+
+    let resolved: Vec<_> = res
+        .type_refs(order)
+        .map(|r| (r.name.as_str(), r.target.map(|id| res.name(id))))
+        .collect();
+    assert_eq!(
+        resolved,
+        [
+            ("List", None),
+            ("Item", Some("shop::Item")),
+            ("Customer", Some("Customer")),
+            ("shop::Tax", Some("shop::Tax")),
+            ("Money", None),
+        ]
+    );
+}
+
+#[test]
+fn relation_ends_match_the_classes_add_relation_registers() {
+    let diagram = Diagram::builder()
+        .class("shop::Order", |c| c)
+        .class("shop::Item", |c| c)
+        .relation("shop::Order", RelationKind::Composition, "Item")
+        .build().unwrap();
+    // `Item` is a new, implicit top-level class, and the relation points at it
+    assert!(diagram.class("Item").unwrap().implicit);
+    let res = diagram.resolve();
+    let (_, to) = res.relation_ends(0);
+    assert_eq!(res.name(to.unwrap()), "Item");
+    assert_eq!(res.unresolved_relations().count(), 0);
+}
@@ -0,0 +1,264 @@
+// tests/rust_source.rs
+//! Class diagrams reverse-engineered from Rust (`--features rust-source`).
+#![cfg(feature = "rust-source")]
+
+use std::path::PathBuf;
+
+use mermaid_parser::rust_source::{self, ScanError, Scanner};
+use mermaid_parser::types::{Attribute, Diagram, LineStyle, Member, Method, Parameter, Relation, RelationKind, Visibility};
+use pretty_assertions::assert_eq;
+
+const SRC: &str = r#"
+pub mod geo {
+    pub trait Named {
+        fn name(&self) -> String;
+    }
+
+    pub trait Shape: Named {
+        const SIDES: u32;
+        fn area(&self) -> f64;
+        fn describe(&self) -> String { self.name() }
+    }
+
+    pub struct Circle {
+        pub radius: f64,
+        pub(crate) center: (f64, f64),
+        label: Option<String>,
+    }
+
+    impl Circle {
+        pub const UNIT: f64 = 1.0;
+        pub fn new(radius: f64) -> Self { todo!() }
+        pub fn scale(&mut self, factor: f64) {}
+        fn check(&self) -> bool { true }
+    }
+
+    impl Named for Circle {
+        fn name(&self) -> String { todo!() }
+    }
+
+    impl Shape for Circle {
+        const SIDES: u32 = 0;
+        fn area(&self) -> f64 { 0.0 }
+    }
+
+    impl std::fmt::Debug for Circle {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { Ok(()) }
+    }
+}
+
+pub enum Color {
+    Red,
+    Rgb(u8, u8, u8),
+}
+
+pub struct Layer<'a, T> {
+    pub items: Vec<T>,
+    canvas: &'a Canvas,
+}
+
+pub struct Canvas {
+    pub circles: Vec<geo::Circle>,
+    pub background: Option<Color>,
+    pub shared: std::rc::Rc<Palette>,
+    pub lookup: std::collections::HashMap<String, Box<crate::geo::Circle>>,
+}
+
+pub struct Palette(pub Vec<Color>);
+
+#[cfg(test)]
+mod tests {
+    struct Fixture;
+}
+"#;
+
+fn relation(from: &str, kind: RelationKind, line: LineStyle, to: &str) -> Relation {
+    Relation {
+        from: from.into(),
+        to: to.into(),
+        kind,
+        line,
+        label_from: None,
+        label_to: None,
+    }
+}
+
+fn names(diagram: &Diagram) -> Vec<&str> {
+    diagram.classes().map(|c| c.name.as_str()).collect()
+}
+
+#[test]
+fn items_become_classes_in_namespaces() {
+    let diagram = rust_source::parse(SRC).unwrap();
+    assert_eq!(
+        names(&diagram),
+        ["geo::Named", "geo::Shape", "geo::Circle", "Color", "Layer", "Canvas", "Palette"]
+    );
+    assert_eq!(diagram.class("geo::Shape").unwrap().annotations, ["interface"]);
+    assert_eq!(diagram.class("Layer").unwrap().generic.as_deref(), Some("T"));
+
+    let color = diagram.class("Color").unwrap();
+    assert_eq!(color.annotations, ["enumeration"]);
+    let variants: Vec<&str> = color.members.iter().map(Member::name).collect();
+    assert_eq!(variants, ["Red", "Rgb"]);
+    assert!(diagram.classes().all(|c| !c.implicit));
+}
+
+#[test]
+fn fields_and_methods_keep_visibility_and_types() {
+    let diagram = rust_source::parse(SRC).unwrap();
+    let attr = |visibility, name: &str, ty: &str, is_static| {
+        Member::Attribute(Attribute { visibility, name: name.into(), data_type: Some(ty.into()), is_static })
+    };
+    let method = |visibility, name: &str, parameters: Vec<Parameter>, ret: Option<&str>, is_static| {
+        Member::Method(Method {
+            visibility,
+            name: name.into(),
+            parameters,
+            return_type: ret.map(Into::into),
+            is_static,
+            is_abstract: false,
+        })
+    };
+    let param = |name: &str, ty: &str| Parameter { name: name.into(), data_type: Some(ty.into()) };
+
+    // trait impl methods belong to the trait, not the struct
+    assert_eq!(
+        diagram.class("geo::Circle").unwrap().members,
+        [
+            attr(Visibility::Public, "radius", "f64", false),
+            attr(Visibility::Package, "center", "(f64, f64)", false),
+            attr(Visibility::Private, "label", "Option~String~", false),
+            attr(Visibility::Public, "UNIT", "f64", true),
+            method(Visibility::Public, "new", vec![param("radius", "f64")], Some("Self"), true),
+            method(Visibility::Public, "scale", vec![param("factor", "f64")], None, false),
+            method(Visibility::Private, "check", vec![], Some("bool"), false),
+        ]
+    );
+
+    let shape = diagram.class("geo::Shape").unwrap();
+    let abstract_: Vec<(&str, bool)> = shape
+        .members
+        .iter()
+        .filter_map(|m| match m {
+            Member::Method(m) => Some((m.name.as_str(), m.is_abstract)),
+            Member::Attribute(_) => None,
+        })
+        .collect();
+    assert_eq!(abstract_, [("area", true), ("describe", false)]);
+    assert!(shape.members.contains(&attr(Visibility::Public, "SIDES", "u32", true)));
+
+    let canvas = diagram.class("Canvas").unwrap();
+    assert_eq!(
+        canvas.members[3],
+        attr(Visibility::Public, "lookup", "HashMap~String,Box~Circle~~", false)
+    );
+    assert_eq!(diagram.class("Palette").unwrap().members, [attr(Visibility::Public, "0", "Vec~Color~", false)]);
+}
+
+#[test]
+fn relations_from_traits_impls_and_field_types() {
+    let diagram = rust_source::parse(SRC).unwrap();
+    let labelled = |mut r: Relation, from: Option<&str>, to: Option<&str>| {
+        r.label_from = from.map(Into::into);
+        r.label_to = to.map(Into::into);
+        r
+    };
+    assert_eq!(
+        diagram.relations,
+        [
+            // impls first, in source order; `Debug` is not in the sources
+            relation("geo::Circle", RelationKind::Extension, LineStyle::Dotted, "geo::Named"),
+            relation("geo::Circle", RelationKind::Extension, LineStyle::Dotted, "geo::Shape"),
+            // then supertraits and field types, in source order
+            relation("geo::Shape", RelationKind::Extension, LineStyle::Solid, "geo::Named"),
+            relation("Layer", RelationKind::Dependency, LineStyle::Solid, "Canvas"),
+            labelled(relation("geo::Circle", RelationKind::Composition, LineStyle::Solid, "Canvas"), Some("*"), None),
+            labelled(relation("Color", RelationKind::Composition, LineStyle::Solid, "Canvas"), Some("0..1"), None),
+            relation("Canvas", RelationKind::Dependency, LineStyle::Solid, "Palette"),
+            labelled(relation("Color", RelationKind::Composition, LineStyle::Solid, "Palette"), Some("*"), None),
+        ]
+    );
+    assert_eq!(names(&diagram).len(), 7);
+    assert_eq!(diagram.implementors("geo::Shape"), ["geo::Circle"]);
+}
+
+#[test]
+fn fields_of_one_type_keep_the_widest_multiplicity() {
+    let diagram = rust_source::parse(
+        "pub struct Point;\n\
+         pub struct Path { start: Point, via: Option<Point>, rest: Vec<Point>, end: Point }\n\
+         pub struct Pin { at: Point, next: Option<Point> }",
+    )
+    .unwrap();
+    let labels: Vec<(&str, Option<&str>)> =
+        diagram.relations.iter().map(|r| (r.to.as_str(), r.label_from.as_deref())).collect();
+    assert_eq!(labels, [("Path", Some("*")), ("Pin", Some("0..1"))]);
+}
+
+#[test]
+fn qualified_paths_are_not_matched_by_their_last_segment() {
+    let diagram = rust_source::parse(
+        "mod a { pub struct Error; }\n\
+         pub struct Loader { last: io::Error, kind: fmt::Result }\n\
+         pub struct Reader { failed: Option<a::Error>, last: Error }",
+    )
+    .unwrap();
+    // `io::Error` is not `a::Error`; the bare `Error` is, as the only one
+    let ends: Vec<(&str, &str)> = diagram.relations.iter().map(|r| (r.from.as_str(), r.to.as_str())).collect();
+    assert_eq!(ends, [("a::Error", "Reader")]);
+}
+
+#[test]
+fn scan_dir_maps_files_to_namespaces() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("rust_source");
+    let _ = std::fs::remove_dir_all(&dir);
+    let files = [
+        ("lib.rs", "mod shop; pub struct App { pub orders: Vec<shop::orders::Order> }"),
+        ("shop/mod.rs", "pub mod orders; pub struct Customer { pub name: String }"),
+        ("shop/orders.rs", "pub struct Order { pub customer: super::Customer, pub lines: Vec<Line> }\npub struct Line;"),
+    ];
+    for (path, src) in files {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, src).unwrap();
+    }
+
+    let diagram = rust_source::scan_dir(&dir).unwrap();
+    assert_eq!(names(&diagram), ["App", "shop::Customer", "shop::orders::Order", "shop::orders::Line"]);
+    let parts: Vec<(&str, &str)> = diagram.relations.iter().map(|r| (r.from.as_str(), r.to.as_str())).collect();
+    assert_eq!(
+        parts,
+        [
+            ("shop::orders::Order", "App"),
+            ("shop::Customer", "shop::orders::Order"),
+            ("shop::orders::Line", "shop::orders::Order"),
+        ]
+    );
+
+    // one more file through the scanner, then a broken one
+    let mut scanner = Scanner::new();
+    scanner.add_dir(&dir).unwrap().add_source("extra", "pub struct Audit { pub app: crate::App }").unwrap();
+    let diagram = scanner.finish();
+    assert!(diagram.relations.iter().any(|r| r.from == "App" && r.to == "extra::Audit"));
+
+    std::fs::write(dir.join("shop/broken.rs"), "pub struct {").unwrap();
+    let err = rust_source::scan_dir(&dir).unwrap_err();
+    assert!(matches!(&err, ScanError::Syntax { path, .. } if path.ends_with("shop/broken.rs")), "{err}");
+}
+
+#[cfg(unix)]
+#[test]
+fn scan_dir_skips_symlinks() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("rust_source_links");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("shop")).unwrap();
+    std::fs::write(dir.join("lib.rs"), "pub struct App;").unwrap();
+    std::fs::write(dir.join("shop/mod.rs"), "pub struct Order;").unwrap();
+    // a loop back to the root, and a second name for a file
+    std::os::unix::fs::symlink(&dir, dir.join("shop/root")).unwrap();
+    std::os::unix::fs::symlink(dir.join("lib.rs"), dir.join("alias.rs")).unwrap();
+
+    let diagram = rust_source::scan_dir(&dir).unwrap();
+    assert_eq!(names(&diagram), ["App", "shop::Order"]);
+}
@@ -0,0 +1,27 @@
+// tests/schema.rs
+//! The checked-in JSON Schema must match the one generated from the types.
+//!
+//! Regenerate with `UPDATE_GOLDEN=1 cargo test --features json-schema`.
+#![cfg(feature = "json-schema")]
+
+use mermaid_parser::schema::{json_schema, json_schema_string};
+
+mod common;
+
+#[test]
+fn checked_in_schema_is_up_to_date() {
+    common::golden("schema/diagram.schema.json", &json_schema_string());
+}
+
+#[test]
+fn schema_covers_the_model() {
+    let schema = json_schema();
+    let defs = schema["$defs"].as_object().unwrap();
+    for name in ["Namespace", "Class", "Member", "Relation", "Note", "Visibility", "RelationKind"] {
+        assert!(defs.contains_key(name), "missing definition {name}");
+    }
+    assert_eq!(
+        schema["$defs"]["Visibility"]["enum"],
+        serde_json::json!(["public", "private", "protected", "package", "unspecified"])
+    );
+}
@@ -0,0 +1,46 @@
+// tests/serde.rs
+//! JSON shape of the data model (`--features serde`).
+#![cfg(feature = "serde")]
+
+use mermaid_parser::types::{VersionedDiagram, DEFAULT_NAMESPACE, SCHEMA_VERSION};
+use serde_json::json;
+
+#[test]
+fn member_is_tagged_and_enums_are_lowercase() {
+    let diagram = mermaid_parser::parser::parse(
+        "classDiagram\nDuck : +String beak\nDuck : swim()\nDuck --|> Animal\n",
+    )
+    .unwrap();
+    let value = serde_json::to_value(VersionedDiagram::new(diagram)).unwrap();
+
+    assert_eq!(value["schema_version"], SCHEMA_VERSION);
+    let duck = &value["diagram"]["namespaces"][DEFAULT_NAMESPACE]["classes"]["Duck"];
+    assert_eq!(
+        duck["members"][0],
+        json!({
+            "kind": "attribute",
+            "visibility": "public",
+            "name": "beak",
+            "data_type": "String",
+            "is_static": false,
+        })
+    );
+    assert_eq!(duck["members"][1]["kind"], "method");
+    assert_eq!(value["diagram"]["relations"][0]["kind"], "extension");
+    assert_eq!(value["diagram"]["relations"][0]["line"], "solid");
+}
+
+#[test]
+fn roundtrips_and_checks_version() {
+    let diagram = mermaid_parser::parser::parse(include_str!("./mermaid/test.mmd")).unwrap();
+    let text = serde_json::to_string(&VersionedDiagram::new(diagram)).unwrap();
+
+    let back: VersionedDiagram = serde_json::from_str(&text).unwrap();
+    let diagram = back.into_diagram().unwrap();
+    assert_eq!(diagram.namespaces[DEFAULT_NAMESPACE].classes["BankAccount"].members.len(), 3);
+
+    let mut future: serde_json::Value = serde_json::from_str(&text).unwrap();
+    future["schema_version"] = json!(SCHEMA_VERSION + 1);
+    let future: VersionedDiagram = serde_json::from_value(future).unwrap();
+    assert_eq!(future.into_diagram().unwrap_err(), SCHEMA_VERSION + 1);
+}
@@ -0,0 +1,99 @@
+// tests/svg.rs
+//! SVG rendering: golden files plus checks on the layout itself.
+//!
+//! Regenerate the goldens with `UPDATE_GOLDEN=1 cargo test --test svg`.
+
+use mermaid_parser::layout::{self, Direction, LayoutOptions};
+use mermaid_parser::parser::parse;
+use mermaid_parser::svg::SvgOptions;
+use mermaid_parser::types::{Diagram, RelationKind};
+use pretty_assertions::assert_eq;
+
+mod common;
+
+const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+
+fn golden(name: &str, direction: Direction, suffix: &str) -> String {
+    let src = std::fs::read_to_string(format!("{GOLDEN}/{name}.mmd")).unwrap();
+    let svg = parse(&src).unwrap().to_svg_with(&SvgOptions { direction, ..SvgOptions::default() });
+    common::golden(&format!("tests/golden/{name}.{suffix}.svg"), &svg);
+    svg
+}
+
+#[test]
+fn shapes_top_to_bottom() {
+    let svg = golden("shapes", Direction::TopToBottom, "tb");
+    assert_eq!(svg.matches("<g class=\"class\">").count(), 6);
+    assert_eq!(svg.matches("<g class=\"note\">").count(), 1);
+    assert_eq!(svg.matches("class=\"relation\"").count(), 5);
+    assert_eq!(svg.matches("class=\"note-link\"").count(), 1);
+    for marker in ["extension", "composition", "aggregation", "dependency"] {
+        assert!(svg.contains(&format!("marker-end=\"url(#{marker})\"")), "{marker}");
+    }
+    assert!(svg.contains(">+area() : f64</text>"));
+    assert!(svg.contains("stroke-dasharray=\"6 4\""));
+}
+
+#[test]
+fn shapes_left_to_right() {
+    let svg = golden("shapes", Direction::LeftToRight, "lr");
+    assert_eq!(svg.matches("<g class=\"class\">").count(), 6);
+}
+
+#[test]
+fn annotations_generics_and_lollipops() {
+    let diagram = Diagram::builder()
+        .class("geo::Shape", |c| c.annotation("interface").generic("T"))
+        .relation("Plug", RelationKind::Lollipop, "geo::Shape")
+        .build().unwrap();
+    let svg = diagram.to_svg();
+    assert!(svg.contains("marker-end=\"url(#lollipop)\""));
+    assert!(svg.contains(">«interface»</text>"));
+    assert!(svg.contains(">Shape&lt;T&gt;</text>"));
+}
+
+#[test]
+fn multiplicity_labels_are_drawn() {
+    let mut diagram = parse("classDiagram\nOrder *-- Item\n").unwrap();
+    diagram.relations[0].label_from = Some("many".into());
+    diagram.relations[0].label_to = Some("1".into());
+    let svg = diagram.to_svg();
+    assert_eq!(svg.matches("class=\"label\"").count(), 2);
+    assert!(svg.contains(">many</text>"));
+    assert!(svg.contains(">1</text>"));
+}
+
+#[test]
+fn parents_rank_above_children() {
+    let sizes = [(100.0, 40.0), (80.0, 40.0), (80.0, 40.0)];
+    let edges = [(1, 0), (2, 0)]; // 1 and 2 extend 0
+    let down = layout::layered(&sizes, &edges, &LayoutOptions::default());
+    assert!(down.nodes[0].y + down.nodes[0].height < down.nodes[1].y);
+    assert_eq!(down.nodes[1].y, down.nodes[2].y);
+    // every edge leaves its `from` box and ends on its `to` box
+    for (&(from, to), points) in edges.iter().zip(&down.edges) {
+        assert_eq!(points[0].y, down.nodes[from].y);
+        assert_eq!(points[points.len() - 1].y, down.nodes[to].y + down.nodes[to].height);
+    }
+
+    let options = |direction| LayoutOptions { direction, ..LayoutOptions::default() };
+    let up = layout::layered(&sizes, &edges, &options(Direction::BottomToTop));
+    assert!(up.nodes[1].y + up.nodes[1].height < up.nodes[0].y);
+
+    let right = layout::layered(&sizes, &edges, &options(Direction::LeftToRight));
+    assert!(right.nodes[0].x + right.nodes[0].width < right.nodes[1].x);
+    assert_eq!((right.nodes[0].width, right.nodes[0].height), (100.0, 40.0));
+}
+
+#[test]
+fn cycles_and_self_loops_are_laid_out() {
+    let sizes = [(50.0, 30.0); 3];
+    let edges = [(0, 1), (1, 2), (2, 0), (1, 1)];
+    let layout = layout::layered(&sizes, &edges, &LayoutOptions::default());
+    assert_eq!(layout.edges.len(), 4);
+    assert!(layout.edges.iter().all(|e| e.len() >= 2));
+    for rect in &layout.nodes {
+        assert!(rect.x >= 0.0 && rect.x + rect.width <= layout.width);
+        assert!(rect.y >= 0.0 && rect.y + rect.height <= layout.height);
+    }
+}
@@ -0,0 +1,76 @@
+// tests/text.rs
+//! Text rendering: golden files plus width limit, compact mode and labels.
+//!
+//! Regenerate the goldens with `UPDATE_GOLDEN=1 cargo test --test text`.
+
+use mermaid_parser::parser::parse;
+use mermaid_parser::text::{Charset, TextOptions};
+use mermaid_parser::types::{Diagram, RelationKind};
+
+mod common;
+
+const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+
+fn shapes() -> Diagram {
+    parse(&std::fs::read_to_string(format!("{GOLDEN}/shapes.mmd")).unwrap()).unwrap()
+}
+
+fn golden(file: &str, text: &str) {
+    common::golden(&format!("tests/golden/{file}"), text);
+}
+
+#[test]
+fn shapes_unicode() {
+    let text = shapes().to_text();
+    golden("shapes.txt", &text);
+    for head in ['△', '◆', '◇', '↑'] {
+        assert!(text.contains(head), "{head}");
+    }
+    assert!(text.contains("│ +area() : f64 │"));
+    assert!(text.contains('┆'));
+}
+
+#[test]
+fn shapes_ascii_compact() {
+    let options = TextOptions { charset: Charset::Ascii, compact: true, ..TextOptions::default() };
+    let text = shapes().to_text_with(&options);
+    golden("shapes.compact.txt", &text);
+    assert!(text.is_ascii());
+    assert!(text.contains("| Canvas |"));
+    assert!(!text.contains("area"));
+}
+
+#[test]
+fn width_limit_narrows_boxes_before_cutting() {
+    let options = TextOptions { max_width: Some(40), ..TextOptions::default() };
+    let text = shapes().to_text_with(&options);
+    assert!(text.lines().all(|l| l.chars().count() <= 40));
+    // every box is still closed on the right
+    assert!(text.contains("│  Circle   │"));
+    assert!(text.contains("…"));
+
+    let narrow = TextOptions { max_width: Some(20), ..options };
+    assert!(shapes().to_text_with(&narrow).lines().all(|l| l.chars().count() <= 20));
+}
+
+#[test]
+fn labels_annotations_and_lollipops() {
+    let mut diagram = Diagram::builder()
+        .class("Shape", |c| c.annotation("interface").generic("T"))
+        .relation("Plug", RelationKind::Lollipop, "Shape")
+        .relation("Order", RelationKind::Composition, "Shape")
+        .build().unwrap();
+    diagram.relations[1].label_from = Some("many".into());
+    diagram.relations[1].label_to = Some("1".into());
+
+    let text = diagram.to_text();
+    assert!(text.contains("«interface»"));
+    assert!(text.contains("Shape<T>"));
+    assert!(text.contains('○'));
+    assert!(text.contains("many"));
+    assert!(text.contains(" 1"));
+
+    let ascii = diagram.to_text_with(&TextOptions { charset: Charset::Ascii, ..TextOptions::default() });
+    assert!(ascii.contains("<<interface>>"));
+    assert!(ascii.contains('O') && ascii.contains('*'));
+}
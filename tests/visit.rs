@@ -0,0 +1,70 @@
+// tests/visit.rs
+//! Visitors reach every node, nested namespaces included.
+
+use mermaid_parser::types::{Class, Diagram, Namespace, Visibility};
+use mermaid_parser::visit::{self, Visit, VisitMut};
+
+fn nested() -> Diagram {
+    let mut diagram = Diagram::builder()
+        .class("Order", |c| {
+            c.attr(Visibility::Private, "total", "Money")
+                .method(Visibility::Public, "pay", |m| m.param("amount", "Money").returns("Receipt"))
+        })
+        .build().unwrap();
+    // a hand-built child namespace, which the parser never produces
+    let mut child = Namespace {
+        name: "billing".into(),
+        ..Default::default()
+    };
+    let mut invoice = Class::new("billing::Invoice");
+    invoice.members = diagram.class("Order").unwrap().members.clone();
+    child.classes.insert("Invoice".into(), invoice);
+    diagram.namespaces[""].children.insert("billing".into(), child);
+    diagram
+}
+
+#[derive(Default)]
+struct Collect<'ast> {
+    classes: Vec<&'ast str>,
+    types: Vec<&'ast str>,
+}
+
+impl<'ast> Visit<'ast> for Collect<'ast> {
+    fn visit_class(&mut self, class: &'ast Class) {
+        self.classes.push(&class.name);
+        visit::walk_class(self, class);
+    }
+    fn visit_type(&mut self, ty: &'ast str) {
+        self.types.push(ty);
+    }
+}
+
+#[test]
+fn visit_reaches_children_and_types() {
+    let diagram = nested();
+    let mut collect = Collect::default();
+    collect.visit_diagram(&diagram);
+    assert_eq!(collect.classes, ["Order", "billing::Invoice"]);
+    assert_eq!(collect.types, ["Money", "Money", "Receipt", "Money", "Money", "Receipt"]);
+}
+
+struct Rename;
+
+impl VisitMut for Rename {
+    fn visit_type_mut(&mut self, ty: &mut String) {
+        if ty == "Money" {
+            *ty = "Decimal".into();
+        }
+    }
+}
+
+#[test]
+fn visit_mut_rewrites_in_place() {
+    let mut diagram = nested();
+    Rename.visit_diagram_mut(&mut diagram);
+
+    let mut collect = Collect::default();
+    collect.visit_diagram(&diagram);
+    assert!(collect.types.iter().all(|t| *t != "Money"));
+    assert_eq!(collect.types.iter().filter(|t| **t == "Decimal").count(), 4);
+}